@@ -7,7 +7,7 @@ use std::time::Duration;
 
 use mpris::{
     LoopStatus, Metadata, PlaybackStatus, Player, PlayerFinder, Progress, ProgressTick,
-    ProgressTracker, TrackID, TrackList,
+    ProgressTracker, TrackID, TrackList, Volume,
 };
 use termion::color;
 use termion::input::TermRead;
@@ -292,8 +292,7 @@ fn cycle_loop_status(player: &Player<'_>) -> Result<(), mpris::DBusError> {
 
 fn change_volume(player: &Player<'_>, diff: f64) -> Result<(), mpris::DBusError> {
     let current_volume = player.get_volume()?;
-    let new_volume = (current_volume + diff).max(0.0).min(1.0);
-    player.set_volume(new_volume)
+    player.set_volume(Volume::clamp(current_volume + diff))
 }
 
 fn print_playback_info(screen: &mut Screen, progress: &Progress) {
@@ -15,4 +15,5 @@ fi
 
 cargo build --verbose &&
   cargo test --verbose &&
+  cargo test --verbose --all-features &&
   cargo doc --no-deps
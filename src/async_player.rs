@@ -0,0 +1,430 @@
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::mpsc;
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+
+use super::{
+    BusType, DBusError, LoopStatus, Metadata, PlaybackStatus, Player, PlayerCapabilities,
+    PlayerEvent, PlayerProperties, Playlist, PlaylistID, PlaylistOrdering, Progress, RetryPolicy,
+    RootProperties, TrackID, TrackList, Volume,
+};
+use crate::worker::{self, forward_to_player, Job, WorkerConfig};
+
+/// An async handle to a MPRIS-compatible player.
+///
+/// The D-Bus connection used by [`Player`] cannot be shared between threads, so an [`AsyncPlayer`]
+/// owns a worker thread with its own connection and a blocking [`Player`]. Every method call is
+/// sent to that thread and the reply is awaited through a [tokio] channel, which means that the
+/// calling executor is never blocked by D-Bus.
+///
+/// The methods mirror the ones on [`Player`] and return the same [`DBusError`]s. Arguments that
+/// [`Player`] borrows are taken by value here, as they have to be sent to the worker thread.
+///
+/// Cloning an [`AsyncPlayer`] gives you another handle to the same worker thread. The thread shuts
+/// down when the last handle is dropped.
+///
+/// **Requires the `async` feature.**
+///
+/// [tokio]: https://tokio.rs/
+#[derive(Debug, Clone)]
+pub struct AsyncPlayer {
+    sender: mpsc::Sender<Job>,
+    config: WorkerConfig,
+    unique_name: String,
+    identity: String,
+    has_tracklist_interface: bool,
+}
+
+impl AsyncPlayer {
+    /// Create a new [`AsyncPlayer`] for the given bus name and path, with a new D-Bus session
+    /// connection.
+    ///
+    /// If no player is running on this bus name an [`Err`] will be returned.
+    pub async fn new<B, P>(bus_name: B, path: P, timeout_ms: i32) -> Result<Self, DBusError>
+    where
+        B: Into<String>,
+        P: Into<String>,
+    {
+        AsyncPlayer::for_bus(BusType::SessionBus, bus_name, path, timeout_ms).await
+    }
+
+    /// Create a new [`AsyncPlayer`] for the given bus name and path, with a new D-Bus connection
+    /// to the given bus.
+    ///
+    /// If no player is running on this bus name an [`Err`] will be returned.
+    pub async fn for_bus<B, P>(
+        bus: BusType,
+        bus_name: B,
+        path: P,
+        timeout_ms: i32,
+    ) -> Result<Self, DBusError>
+    where
+        B: Into<String>,
+        P: Into<String>,
+    {
+        AsyncPlayer::start(bus, bus_name, path, timeout_ms, RetryPolicy::default()).await
+    }
+
+    /// Like [`for_bus`](Self::for_bus), but the [`Player`] retries calls with the given policy.
+    pub(crate) async fn start<B, P>(
+        bus: BusType,
+        bus_name: B,
+        path: P,
+        timeout_ms: i32,
+        retry_policy: RetryPolicy,
+    ) -> Result<Self, DBusError>
+    where
+        B: Into<String>,
+        P: Into<String>,
+    {
+        let config = WorkerConfig {
+            retry_policy,
+            ..WorkerConfig::new(bus, bus_name.into(), path.into(), timeout_ms)?
+        };
+
+        let (details_sender, details_receiver) = oneshot::channel();
+        let sender = worker::spawn(config.clone(), move |details| {
+            details_sender.send(details).is_ok()
+        });
+
+        let details = details_receiver.await.map_err(|_| worker_stopped())??;
+
+        Ok(AsyncPlayer {
+            sender,
+            config,
+            unique_name: details.unique_name,
+            identity: details.identity,
+            has_tracklist_interface: details.has_tracklist_interface,
+        })
+    }
+
+    /// Run a closure with the underlying [`Player`] on the worker thread and return its result.
+    ///
+    /// This can be used to reach [`Player`] methods that do not have an async version here.
+    pub async fn call<T, F>(&self, f: F) -> Result<T, DBusError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Player<'_>) -> Result<T, DBusError> + Send + 'static,
+    {
+        let (reply_sender, reply_receiver) = oneshot::channel();
+
+        self.sender
+            .send(Box::new(move |player| {
+                let _ = reply_sender.send(f(player));
+            }))
+            .map_err(|_| worker_stopped())?;
+
+        reply_receiver.await.map_err(|_| worker_stopped())?
+    }
+
+    /// Returns the player's D-Bus bus name.
+    pub fn bus_name(&self) -> &str {
+        &self.config.bus_name
+    }
+
+    /// Returns the player's unique D-Bus bus name (usually something like `:1.1337`).
+    pub fn unique_name(&self) -> &str {
+        &self.unique_name
+    }
+
+    /// Returns the player's MPRIS [`Identity`][identity].
+    ///
+    /// [identity]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Identity
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// Checks if the Player implements the `org.mpris.MediaPlayer2.TrackList` interface.
+    pub fn supports_track_lists(&self) -> bool {
+        self.has_tracklist_interface
+    }
+
+    /// Returns a [`PlayerEventStream`] of the raw signals that the player emits.
+    ///
+    /// This is the async version of [`Player::subscribe_events`]. The signals are read on a
+    /// separate thread with its own D-Bus connection, so they are not held up by other calls on
+    /// this [`AsyncPlayer`].
+    pub fn subscribe_events(&self) -> PlayerEventStream {
+        let (sender, receiver) = tokio_mpsc::unbounded_channel();
+        let config = WorkerConfig {
+            bus_name: self.unique_name.clone(),
+            ..self.config.clone()
+        };
+
+        thread::spawn(move || {
+            // If the player cannot be reached the stream simply ends.
+            let player = match config.connect() {
+                Ok(player) => player,
+                Err(_) => return,
+            };
+
+            for event in player.subscribe_events() {
+                if sender.send(event).is_err() {
+                    // The stream was dropped.
+                    return;
+                }
+            }
+        });
+
+        PlayerEventStream { receiver }
+    }
+
+    /// Async version of [`Player::is_running`].
+    ///
+    /// Returns [`false`] if the worker thread has stopped.
+    pub async fn is_running(&self) -> bool {
+        self.call(|player| Ok(player.is_running()))
+            .await
+            .unwrap_or(false)
+    }
+
+    /// Async version of [`Player::checked_can_edit_tracks`].
+    pub async fn checked_can_edit_tracks(&self) -> bool {
+        self.call(|player| Ok(player.checked_can_edit_tracks()))
+            .await
+            .unwrap_or(false)
+    }
+}
+
+forward_to_player! {
+    async impl AsyncPlayer as "Async";
+
+    get_desktop_entry() -> Option<String> = |p| p.get_desktop_entry();
+    get_supported_mime_types() -> Vec<String> = |p| p.get_supported_mime_types();
+    get_supported_uri_schemes() -> Vec<String> = |p| p.get_supported_uri_schemes();
+    get_has_track_list() -> bool = |p| p.get_has_track_list();
+
+    get_position() -> Duration = |p| p.get_position();
+    checked_get_position() -> Option<Duration> = |p| p.checked_get_position();
+    get_position_in_microseconds() -> u64 = |p| p.get_position_in_microseconds();
+    set_position(track_id: TrackID, position: Duration) -> () =
+        |p| p.set_position(track_id, &position);
+    checked_set_position(track_id: TrackID, position: Duration) -> bool =
+        |p| p.checked_set_position(track_id, &position);
+    set_position_in_microseconds(track_id: TrackID, position_in_us: u64) -> () =
+        |p| p.set_position_in_microseconds(track_id, position_in_us);
+
+    get_playback_rate() -> f64 = |p| p.get_playback_rate();
+    checked_get_playback_rate() -> Option<f64> = |p| p.checked_get_playback_rate();
+    set_playback_rate(rate: f64) -> () = |p| p.set_playback_rate(rate);
+    checked_set_playback_rate(rate: f64) -> bool = |p| p.checked_set_playback_rate(rate);
+    get_minimum_playback_rate() -> f64 = |p| p.get_minimum_playback_rate();
+    get_maximum_playback_rate() -> f64 = |p| p.get_maximum_playback_rate();
+    get_valid_playback_rate_range() -> Range<f64> = |p| p.get_valid_playback_rate_range();
+
+    get_all_properties() -> PlayerProperties = |p| p.get_all_properties();
+    get_all_root_properties() -> RootProperties = |p| p.get_all_root_properties();
+    get_capabilities() -> PlayerCapabilities = |p| p.get_capabilities();
+    get_metadata() -> Metadata = |p| p.get_metadata();
+    get_progress() -> Progress = |p| p.get_progress();
+    get_track_list() -> TrackList = |p| p.get_track_list();
+    checked_get_track_list() -> Option<TrackList> = |p| p.checked_get_track_list();
+    can_edit_tracks() -> bool = |p| p.can_edit_tracks();
+    get_tracks_metadata(track_ids: Vec<TrackID>) -> Vec<Metadata> =
+        |p| p.get_tracks_metadata(&track_ids);
+    get_track_metadata(track_id: TrackID) -> Metadata = |p| p.get_track_metadata(&track_id);
+
+    play_pause() -> () = |p| p.play_pause();
+    play() -> () = |p| p.play();
+    pause() -> () = |p| p.pause();
+    stop() -> () = |p| p.stop();
+    next() -> () = |p| p.next();
+    previous() -> () = |p| p.previous();
+    seek(offset_in_microseconds: i64) -> () = |p| p.seek(offset_in_microseconds);
+    seek_forwards(offset: Duration) -> () = |p| p.seek_forwards(&offset);
+    seek_backwards(offset: Duration) -> () = |p| p.seek_backwards(&offset);
+    raise() -> () = |p| p.raise();
+    checked_raise() -> bool = |p| p.checked_raise();
+    quit() -> () = |p| p.quit();
+    checked_quit() -> bool = |p| p.checked_quit();
+
+    go_to(track_id: TrackID) -> () = |p| p.go_to(&track_id);
+    add_track(uri: String, after: TrackID, set_as_current: bool) -> () =
+        |p| p.add_track(&uri, &after, set_as_current);
+    add_track_at_start(uri: String, set_as_current: bool) -> () =
+        |p| p.add_track_at_start(&uri, set_as_current);
+    remove_track(track_id: TrackID) -> () = |p| p.remove_track(&track_id);
+
+    get_playlist_count() -> u32 = |p| p.get_playlist_count();
+    get_playlist_orderings() -> Vec<PlaylistOrdering> = |p| p.get_playlist_orderings();
+    get_playlists(index: u32, max_count: u32, order: PlaylistOrdering, reverse_order: bool)
+        -> Vec<Playlist> = |p| p.get_playlists(index, max_count, order, reverse_order);
+    get_active_playlist() -> Option<Playlist> = |p| p.get_active_playlist();
+    activate_playlist(playlist_id: PlaylistID) -> () = |p| p.activate_playlist(&playlist_id);
+
+    open_uri(uri: String) -> () = |p| p.open_uri(&uri);
+    can_open_uri() -> bool = |p| p.can_open_uri();
+
+    checked_play_pause() -> bool = |p| p.checked_play_pause();
+    checked_play() -> bool = |p| p.checked_play();
+    checked_pause() -> bool = |p| p.checked_pause();
+    checked_stop() -> bool = |p| p.checked_stop();
+    checked_next() -> bool = |p| p.checked_next();
+    checked_previous() -> bool = |p| p.checked_previous();
+    checked_seek(offset_in_microseconds: i64) -> bool = |p| p.checked_seek(offset_in_microseconds);
+    checked_seek_forwards(offset: Duration) -> bool = |p| p.checked_seek_forwards(&offset);
+    checked_seek_backwards(offset: Duration) -> bool = |p| p.checked_seek_backwards(&offset);
+
+    can_raise() -> bool = |p| p.can_raise();
+    can_quit() -> bool = |p| p.can_quit();
+    can_set_fullscreen() -> bool = |p| p.can_set_fullscreen();
+    can_control() -> bool = |p| p.can_control();
+    can_go_next() -> bool = |p| p.can_go_next();
+    can_go_previous() -> bool = |p| p.can_go_previous();
+    can_pause() -> bool = |p| p.can_pause();
+    can_play() -> bool = |p| p.can_play();
+    can_seek() -> bool = |p| p.can_seek();
+    can_stop() -> bool = |p| p.can_stop();
+    can_set_playback_rate() -> bool = |p| p.can_set_playback_rate();
+    can_shuffle() -> bool = |p| p.can_shuffle();
+    can_loop() -> bool = |p| p.can_loop();
+    has_playback_rate() -> bool = |p| p.has_playback_rate();
+    has_position() -> bool = |p| p.has_position();
+    has_volume() -> bool = |p| p.has_volume();
+
+    get_fullscreen() -> Option<bool> = |p| p.get_fullscreen();
+    set_fullscreen(new_state: bool) -> bool = |p| p.set_fullscreen(new_state);
+    checked_set_fullscreen(new_state: bool) -> bool = |p| p.checked_set_fullscreen(new_state);
+    get_playback_status() -> PlaybackStatus = |p| p.get_playback_status();
+    get_shuffle() -> bool = |p| p.get_shuffle();
+    checked_get_shuffle() -> Option<bool> = |p| p.checked_get_shuffle();
+    set_shuffle(state: bool) -> () = |p| p.set_shuffle(state);
+    checked_set_shuffle(state: bool) -> bool = |p| p.checked_set_shuffle(state);
+    get_loop_status() -> LoopStatus = |p| p.get_loop_status();
+    checked_get_loop_status() -> Option<LoopStatus> = |p| p.checked_get_loop_status();
+    set_loop_status(status: LoopStatus) -> () = |p| p.set_loop_status(status);
+    checked_set_loop_status(status: LoopStatus) -> bool = |p| p.checked_set_loop_status(status);
+    get_volume() -> f64 = |p| p.get_volume();
+    checked_get_volume() -> Option<f64> = |p| p.checked_get_volume();
+    set_volume(volume: Volume) -> () = |p| p.set_volume(volume);
+    checked_set_volume(volume: Volume) -> bool = |p| p.checked_set_volume(volume);
+    set_volume_checked(volume: Volume) -> bool = |p| p.set_volume_checked(volume);
+}
+
+/// A [`Stream`] of the [`PlayerEvent`]s that an [`AsyncPlayer`] emits.
+///
+/// The stream ends when the player quits.
+///
+/// **Requires the `async` feature.**
+///
+/// See [`AsyncPlayer::subscribe_events`].
+#[derive(Debug)]
+pub struct PlayerEventStream {
+    receiver: tokio_mpsc::UnboundedReceiver<PlayerEvent>,
+}
+
+impl Stream for PlayerEventStream {
+    type Item = PlayerEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+fn worker_stopped() -> DBusError {
+    worker::stopped("AsyncPlayer")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::{FakePlayer, TestBus};
+    use crate::{PlayerFinder, PlayerFinderBuilder};
+
+    // Never called; this only has to compile to prove that the futures can be spawned on a
+    // multi-threaded executor.
+    #[allow(dead_code)]
+    fn futures_are_send(player: &AsyncPlayer, finder: &PlayerFinder, list: &mut TrackList) {
+        fn assert_send<T: Send>(_: T) {}
+
+        assert_send(player.play());
+        assert_send(player.get_metadata());
+        assert_send(player.is_running());
+        assert_send(finder.find_all_async());
+        assert_send(list.reload_async(player));
+        assert_send(player.subscribe_events());
+    }
+
+    #[tokio::test]
+    async fn it_fails_to_create_players_for_missing_bus_names() {
+        let result = AsyncPlayer::new(
+            "org.mpris.MediaPlayer2.mpris_rs_missing_player",
+            "/org/mpris/MediaPlayer2",
+            100,
+        )
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_finds_players_with_the_configuration_of_the_finder() {
+        let bus = TestBus::start();
+        let _kept = FakePlayer::spawn_on(&bus, "org.mpris.MediaPlayer2.mpris_rs_test.kept");
+        let _skipped = FakePlayer::spawn_on(&bus, "org.mpris.MediaPlayer2.mpris_rs_test.skipped");
+        let finder = PlayerFinderBuilder::new()
+            .bus_address(bus.address())
+            .filter(|player| player.bus_name().ends_with(".kept"))
+            .build()
+            .expect("Could not connect to the test bus");
+
+        let players = finder.find_all_async().await.expect("Finding failed");
+
+        let bus_names: Vec<&str> = players.iter().map(AsyncPlayer::bus_name).collect();
+        assert_eq!(bus_names, vec!["org.mpris.MediaPlayer2.mpris_rs_test.kept"]);
+        // The worker thread of the player is connected to the test bus as well.
+        assert!(players[0].is_running().await);
+    }
+
+    #[tokio::test]
+    async fn it_finds_players_with_the_bus_name_prefix_of_the_finder() {
+        let bus = TestBus::start();
+        let _fake = FakePlayer::spawn_on(&bus, "com.example.mpris_rs_test.Player");
+        let finder = PlayerFinder::with_bus_name_prefix("com.example.")
+            .unwrap()
+            .bus_address(bus.address())
+            .build()
+            .expect("Could not connect to the test bus");
+
+        let players = finder.find_all_async().await.expect("Finding failed");
+
+        let bus_names: Vec<&str> = players.iter().map(AsyncPlayer::bus_name).collect();
+        assert_eq!(bus_names, vec!["com.example.mpris_rs_test.Player"]);
+    }
+
+    #[tokio::test]
+    async fn it_creates_players_on_other_buses() {
+        let bus = TestBus::start();
+        let fake = FakePlayer::spawn_on(&bus, "org.mpris.MediaPlayer2.mpris_rs_test.other_bus");
+
+        let player = AsyncPlayer::for_bus(
+            BusType::Custom(bus.address()),
+            fake.bus_name(),
+            "/org/mpris/MediaPlayer2",
+            500,
+        )
+        .await
+        .expect("Could not find the player on the test bus");
+
+        assert_eq!(player.identity(), "Fake player");
+        assert_eq!(
+            player.get_playback_status().await.unwrap(),
+            PlaybackStatus::Playing
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_invalid_bus_names() {
+        let result = AsyncPlayer::new("not a bus name", "/org/mpris/MediaPlayer2", 100).await;
+
+        match result {
+            Err(DBusError::Miscellaneous(_)) => {}
+            other => panic!("Expected a Miscellaneous error, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use dbus::arg::RefArg;
+
+use crate::player::Player;
+
+const ROOT_INTERFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// Checks a [`Player`] against the [MPRIS2 specification][spec], for authors of MPRIS players
+/// that want to test their implementation.
+///
+/// Every property of the `org.mpris.MediaPlayer2` and `org.mpris.MediaPlayer2.Player` interfaces
+/// is read and checked for its type and, where the specification limits them, its value. Methods
+/// are not called, as most of them would change the state of the player.
+///
+/// ```rust,no_run
+/// use mpris::{ComplianceChecker, PlayerFinder};
+///
+/// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// let report = ComplianceChecker::check(&player);
+/// for violation in &report.violations {
+///     println!("{:?} {}: {}", violation.severity, violation.property, violation.description);
+/// }
+/// assert!(report.is_compliant());
+/// ```
+///
+/// [spec]: https://specifications.freedesktop.org/mpris-spec/latest/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComplianceChecker;
+
+/// The result of [`ComplianceChecker::check`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ComplianceReport {
+    /// Everything that the player does differently than the specification, in the order that the
+    /// properties were checked.
+    pub violations: Vec<ComplianceViolation>,
+}
+
+/// A single way in which a [`Player`] does not follow the specification.
+///
+/// See [`ComplianceReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplianceViolation {
+    /// How bad the violation is.
+    pub severity: Severity,
+    /// The name of the property that violates the specification, like `CanSeek`.
+    pub property: String,
+    /// What is wrong with the property.
+    pub description: String,
+}
+
+/// How bad a [`ComplianceViolation`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    /// The player breaks the specification, like by missing a required property.
+    Error,
+
+    /// The player follows the specification, but clients might miss something, like an optional
+    /// property.
+    Warning,
+}
+
+struct PropertySpec {
+    interface: &'static str,
+    name: &'static str,
+    signature: &'static str,
+    required: bool,
+}
+
+const fn required(
+    interface: &'static str,
+    name: &'static str,
+    signature: &'static str,
+) -> PropertySpec {
+    PropertySpec {
+        interface,
+        name,
+        signature,
+        required: true,
+    }
+}
+
+const fn optional(
+    interface: &'static str,
+    name: &'static str,
+    signature: &'static str,
+) -> PropertySpec {
+    PropertySpec {
+        interface,
+        name,
+        signature,
+        required: false,
+    }
+}
+
+const PROPERTIES: &[PropertySpec] = &[
+    required(ROOT_INTERFACE, "CanQuit", "b"),
+    optional(ROOT_INTERFACE, "Fullscreen", "b"),
+    optional(ROOT_INTERFACE, "CanSetFullscreen", "b"),
+    required(ROOT_INTERFACE, "CanRaise", "b"),
+    required(ROOT_INTERFACE, "HasTrackList", "b"),
+    required(ROOT_INTERFACE, "Identity", "s"),
+    optional(ROOT_INTERFACE, "DesktopEntry", "s"),
+    required(ROOT_INTERFACE, "SupportedUriSchemes", "as"),
+    required(ROOT_INTERFACE, "SupportedMimeTypes", "as"),
+    required(PLAYER_INTERFACE, "PlaybackStatus", "s"),
+    optional(PLAYER_INTERFACE, "LoopStatus", "s"),
+    required(PLAYER_INTERFACE, "Rate", "d"),
+    optional(PLAYER_INTERFACE, "Shuffle", "b"),
+    required(PLAYER_INTERFACE, "Metadata", "a{sv}"),
+    required(PLAYER_INTERFACE, "Volume", "d"),
+    required(PLAYER_INTERFACE, "Position", "x"),
+    required(PLAYER_INTERFACE, "MinimumRate", "d"),
+    required(PLAYER_INTERFACE, "MaximumRate", "d"),
+    required(PLAYER_INTERFACE, "CanGoNext", "b"),
+    required(PLAYER_INTERFACE, "CanGoPrevious", "b"),
+    required(PLAYER_INTERFACE, "CanPlay", "b"),
+    required(PLAYER_INTERFACE, "CanPause", "b"),
+    required(PLAYER_INTERFACE, "CanSeek", "b"),
+    required(PLAYER_INTERFACE, "CanControl", "b"),
+];
+
+impl ComplianceChecker {
+    /// Reads every MPRIS property of the player and reports all violations of the specification.
+    ///
+    /// Required properties that cannot be read are [`Severity::Error`]s, optional ones are
+    /// [`Severity::Warning`]s.
+    pub fn check(player: &Player<'_>) -> ComplianceReport {
+        let mut report = ComplianceReport::default();
+        for spec in PROPERTIES {
+            let value = match player.get_property_raw(spec.interface, spec.name) {
+                Ok(value) => value,
+                Err(error) if spec.required => {
+                    report.push(
+                        Severity::Error,
+                        spec.name,
+                        format!("Required property could not be read: {}", error),
+                    );
+                    continue;
+                }
+                Err(error) => {
+                    report.push(
+                        Severity::Warning,
+                        spec.name,
+                        format!("Optional property could not be read: {}", error),
+                    );
+                    continue;
+                }
+            };
+
+            let signature = value.signature();
+            if &*signature != spec.signature {
+                report.push(
+                    Severity::Error,
+                    spec.name,
+                    format!("Expected type {}, but got {}", spec.signature, &*signature),
+                );
+                continue;
+            }
+
+            if let Some((severity, description)) = check_value(spec.name, &*value) {
+                report.push(severity, spec.name, description);
+            }
+        }
+        report
+    }
+}
+
+impl ComplianceReport {
+    /// Returns `true` if there are no [`Severity::Error`] violations; warnings are allowed.
+    pub fn is_compliant(&self) -> bool {
+        self.violations
+            .iter()
+            .all(|violation| violation.severity != Severity::Error)
+    }
+
+    fn push(&mut self, severity: Severity, property: &str, description: String) {
+        self.violations.push(ComplianceViolation {
+            severity,
+            property: property.to_string(),
+            description,
+        });
+    }
+}
+
+/// Checks the limits that the specification puts on the value of a correctly typed property.
+fn check_value(name: &str, value: &dyn RefArg) -> Option<(Severity, String)> {
+    match name {
+        "PlaybackStatus" => match value.as_str()? {
+            "Playing" | "Paused" | "Stopped" => None,
+            other => Some((
+                Severity::Error,
+                format!("Unknown playback status {:?}", other),
+            )),
+        },
+        "LoopStatus" => match value.as_str()? {
+            "None" | "Track" | "Playlist" => None,
+            other => Some((Severity::Error, format!("Unknown loop status {:?}", other))),
+        },
+        "Rate" => match value.as_f64()? {
+            rate if rate <= 0.0 => Some((
+                Severity::Error,
+                format!("Rate must be positive, but is {}", rate),
+            )),
+            _ => None,
+        },
+        "MinimumRate" => match value.as_f64()? {
+            rate if rate > 1.0 => Some((
+                Severity::Error,
+                format!("MinimumRate must not be above 1.0, but is {}", rate),
+            )),
+            _ => None,
+        },
+        "MaximumRate" => match value.as_f64()? {
+            rate if rate < 1.0 => Some((
+                Severity::Error,
+                format!("MaximumRate must not be below 1.0, but is {}", rate),
+            )),
+            _ => None,
+        },
+        "Volume" => match value.as_f64()? {
+            volume if volume < 0.0 => Some((
+                Severity::Warning,
+                format!("Negative volume {} is treated as 0.0", volume),
+            )),
+            _ => None,
+        },
+        "Position" => match value.as_i64()? {
+            position if position < 0 => Some((
+                Severity::Error,
+                format!("Position must not be negative, but is {}", position),
+            )),
+            _ => None,
+        },
+        "Metadata" => {
+            // Dictionaries iterate as alternating keys and values.
+            let is_empty = value.as_iter()?.next().is_none();
+            let mut keys = value.as_iter()?.step_by(2);
+            if !is_empty && !keys.any(|key| key.as_str() == Some("mpris:trackid")) {
+                Some((
+                    Severity::Error,
+                    String::from("Metadata of the current track has no mpris:trackid"),
+                ))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::FakePlayer;
+
+    fn severity_of(report: &ComplianceReport, property: &str) -> Option<Severity> {
+        report
+            .violations
+            .iter()
+            .find(|violation| violation.property == property)
+            .map(|violation| violation.severity)
+    }
+
+    #[test]
+    fn it_reports_missing_properties() {
+        let fake = FakePlayer::spawn();
+        let report = ComplianceChecker::check(&fake.find());
+
+        // The fake player leaves out some of the required properties, and most optional ones.
+        assert!(!report.is_compliant());
+        assert_eq!(severity_of(&report, "CanSeek"), Some(Severity::Error));
+        assert_eq!(severity_of(&report, "Fullscreen"), Some(Severity::Warning));
+        assert_eq!(severity_of(&report, "LoopStatus"), None);
+        assert_eq!(severity_of(&report, "Identity"), None);
+        assert_eq!(severity_of(&report, "Metadata"), None);
+        assert_eq!(severity_of(&report, "Position"), None);
+    }
+
+    #[test]
+    fn warnings_do_not_break_compliance() {
+        let mut report = ComplianceReport::default();
+        report.push(Severity::Warning, "Shuffle", String::from("Missing"));
+        assert!(report.is_compliant());
+
+        report.push(Severity::Error, "CanSeek", String::from("Missing"));
+        assert!(!report.is_compliant());
+    }
+}
@@ -152,29 +152,29 @@ impl<'a> PlayerEvents<'a> {
                 MprisEvent::TrackListPropertiesChanged => {
                     reload_track_list = true;
                 }
-                MprisEvent::TrackListReplaced { ids } => {
+                MprisEvent::TrackListReplaced { ids, current_id } => {
                     if let Some(ref mut list) = self.track_list {
-                        list.replace(ids.into_iter().map(TrackID::from).collect());
+                        list.apply_tracklist_replaced(ids, &current_id);
                     }
                     self.buffer.push(Event::TrackListReplaced);
                 }
                 MprisEvent::TrackAdded { after_id, metadata } => {
                     if let Some(id) = metadata.track_id() {
                         if let Some(ref mut list) = self.track_list {
-                            list.insert(&after_id, metadata);
+                            list.apply_track_added(metadata, &after_id);
                         }
                         self.buffer.push(Event::TrackAdded(id));
                     }
                 }
                 MprisEvent::TrackRemoved { id } => {
                     if let Some(ref mut list) = self.track_list {
-                        list.remove(&id);
+                        list.apply_track_removed(&id);
                     }
                     self.buffer.push(Event::TrackRemoved(id));
                 }
                 MprisEvent::TrackMetadataChanged { old_id, metadata } => {
                     if let Some(ref mut list) = self.track_list {
-                        if let Some(new_id) = list.replace_track_metadata(&old_id, metadata) {
+                        if let Some(new_id) = list.apply_track_metadata_changed(&old_id, metadata) {
                             self.buffer
                                 .push(Event::TrackMetadataChanged { old_id, new_id });
                         }
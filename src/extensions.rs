@@ -1,10 +1,15 @@
+use std::convert::TryFrom;
 use std::time::Duration;
 
+use failure::Fail;
+
 pub(crate) trait DurationExtensions {
     // Rust beta has a from_micros function that is unstable.
     fn from_micros_ext(_: u64) -> Duration;
     fn as_millis(&self) -> u64;
     fn as_micros(&self) -> u64;
+    // Saturates at `i64::MAX` instead of wrapping around, for D-Bus positions and offsets.
+    fn as_micros_i64(&self) -> i64;
 }
 
 impl DurationExtensions for Duration {
@@ -21,6 +26,119 @@ impl DurationExtensions for Duration {
     fn as_micros(&self) -> u64 {
         self.as_secs() * 1000 * 1000 + u64::from(self.subsec_micros())
     }
+
+    fn as_micros_i64(&self) -> i64 {
+        self.as_secs()
+            .checked_mul(1000 * 1000)
+            .and_then(|micros| micros.checked_add(u64::from(self.subsec_micros())))
+            .and_then(|micros| i64::try_from(micros).ok())
+            .unwrap_or(i64::MAX)
+    }
+}
+
+/// Converts [`Duration`]s to and from nanoseconds in a `u64`, for the nanosecond timestamps that
+/// some D-Bus APIs other than MPRIS carry.
+///
+/// ```rust
+/// use mpris::DurationNanos;
+/// use std::time::Duration;
+///
+/// assert_eq!(Duration::from_nanos_u64(1_500_000_000), Duration::new(1, 500_000_000));
+/// assert_eq!(Duration::from_secs(u64::MAX).as_nanos_u64(), u64::MAX);
+/// ```
+pub trait DurationNanos {
+    /// Creates a duration from a number of nanoseconds.
+    fn from_nanos_u64(nanos: u64) -> Self;
+
+    /// Returns the whole duration in nanoseconds, saturating at `u64::MAX`. [`Duration`] has no
+    /// precision below a nanosecond, so nothing else is cut off.
+    fn as_nanos_u64(&self) -> u64;
+}
+
+impl DurationNanos for Duration {
+    fn from_nanos_u64(nanos: u64) -> Duration {
+        let whole_seconds = nanos / 1_000_000_000;
+        let rest = (nanos - (whole_seconds * 1_000_000_000)) as u32;
+        Duration::new(whole_seconds, rest)
+    }
+
+    fn as_nanos_u64(&self) -> u64 {
+        self.as_secs()
+            .checked_mul(1_000_000_000)
+            .and_then(|nanos| nanos.checked_add(u64::from(self.subsec_nanos())))
+            .unwrap_or(u64::MAX)
+    }
+}
+
+/// Formats [`Duration`]s the way media players show positions and track lengths, and parses them
+/// back.
+///
+/// ```rust
+/// use mpris::DurationHms;
+/// use std::time::Duration;
+///
+/// assert_eq!(Duration::from_secs(75).fmt_hms(), "1:15");
+/// assert_eq!(Duration::from_secs(3675).fmt_hms(), "1:01:15");
+/// assert_eq!(Duration::parse_hms("1:15").unwrap(), Duration::from_secs(75));
+/// ```
+pub trait DurationHms: Sized {
+    /// Formats as `H:MM:SS` from one hour on, and as `M:SS` below that. Fractions of a second are
+    /// cut off.
+    fn fmt_hms(&self) -> String;
+
+    /// Parses a duration in the format of [`fmt_hms`](Self::fmt_hms).
+    ///
+    /// Minutes and seconds after the first number must have two digits and be below 60.
+    fn parse_hms(string: &str) -> Result<Self, ParseHmsError>;
+}
+
+/// The string passed to [`DurationHms::parse_hms`] was not like `H:MM:SS` or `M:SS`.
+#[derive(Fail, Debug)]
+#[fail(display = "Expected a duration like 1:02:03 or 2:03, but got {}", _0)]
+pub struct ParseHmsError(String);
+
+impl DurationHms for Duration {
+    fn fmt_hms(&self) -> String {
+        let seconds = self.as_secs();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        if hours > 0 {
+            format!("{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            format!("{}:{:02}", minutes, seconds)
+        }
+    }
+
+    fn parse_hms(string: &str) -> Result<Self, ParseHmsError> {
+        let error = || ParseHmsError(string.to_string());
+        let parts: Vec<&str> = string.split(':').collect();
+        let (leading, leading_unit, minutes, seconds) = match *parts.as_slice() {
+            [minutes, seconds] => (minutes, 60, "00", seconds),
+            [hours, minutes, seconds] => (hours, 3600, minutes, seconds),
+            _ => return Err(error()),
+        };
+
+        let minutes = sexagesimal_digits(minutes).ok_or_else(error)?;
+        let seconds = sexagesimal_digits(seconds).ok_or_else(error)?;
+        if leading.is_empty() || !leading.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(error());
+        }
+
+        leading
+            .parse::<u64>()
+            .ok()
+            .and_then(|leading| leading.checked_mul(leading_unit))
+            .and_then(|leading| leading.checked_add(minutes * 60 + seconds))
+            .map(Duration::from_secs)
+            .ok_or_else(error)
+    }
+}
+
+// Two digits that make up a number below 60, like minutes and seconds after the first number.
+fn sexagesimal_digits(digits: &str) -> Option<u64> {
+    if digits.len() != 2 || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|value| *value < 60)
 }
 
 #[cfg(test)]
@@ -45,4 +163,61 @@ mod test {
         let duration = Duration::new(5, 543_210_000);
         assert_eq!(DurationExtensions::as_micros(&duration), 5_543_210);
     }
+
+    #[test]
+    fn it_formats_hours_minutes_and_seconds() {
+        assert_eq!(Duration::new(0, 0).fmt_hms(), "0:00");
+        assert_eq!(Duration::new(9, 999_000_000).fmt_hms(), "0:09");
+        assert_eq!(Duration::from_secs(754).fmt_hms(), "12:34");
+        assert_eq!(Duration::from_secs(3600).fmt_hms(), "1:00:00");
+        assert_eq!(Duration::from_secs(36 * 3600 + 62).fmt_hms(), "36:01:02");
+
+        for seconds in &[0, 9, 754, 3600, 36 * 3600 + 62] {
+            let duration = Duration::from_secs(*seconds);
+            assert_eq!(Duration::parse_hms(&duration.fmt_hms()).unwrap(), duration);
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_hms_strings() {
+        for invalid in &[
+            "",
+            "12",
+            ":12",
+            "1:2",
+            "1:60",
+            "1:60:00",
+            "1:2:03",
+            "a:00",
+            "1:00:00:00",
+        ] {
+            assert!(
+                Duration::parse_hms(invalid).is_err(),
+                "{} was parsed",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn it_converts_nanos() {
+        let duration = Duration::from_nanos_u64(1_000_000_001);
+        assert_eq!(duration, Duration::new(1, 1));
+        assert_eq!(duration.as_nanos_u64(), 1_000_000_001);
+        assert_eq!(DurationExtensions::as_micros(&duration), 1_000_000);
+
+        let micros = Duration::from_micros_ext(5_543_210);
+        assert_eq!(micros.as_nanos_u64(), 5_543_210_000);
+        assert_eq!(Duration::from_secs(u64::MAX).as_nanos_u64(), u64::MAX);
+    }
+
+    #[test]
+    fn it_saturates_signed_micros() {
+        assert_eq!(Duration::new(5, 543_210_000).as_micros_i64(), 5_543_210);
+        assert_eq!(Duration::from_secs(u64::MAX).as_micros_i64(), i64::MAX);
+        assert_eq!(
+            Duration::from_secs(i64::MAX as u64 / 1_000_000 + 1).as_micros_i64(),
+            i64::MAX
+        );
+    }
 }
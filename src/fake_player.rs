@@ -0,0 +1,394 @@
+//! A minimal MPRIS player on the session bus, for tests that need a [`Player`] to talk to.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{self, Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use dbus::arg::{RefArg, Variant};
+use dbus::ffidisp::{ConnectionItem, NameFlag};
+use dbus::Message;
+
+use crate::find::open_connection;
+use crate::player::{MPRIS2_PATH, MPRIS2_PREFIX};
+use crate::{BusType, MaybePlaylist, Player, PlayerFinder, PlayerFinderBuilder, Playlist};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+type Properties = HashMap<&'static str, Variant<Box<dyn RefArg>>>;
+
+/// What the fake player reports about itself.
+#[derive(Clone, Copy)]
+struct State {
+    playback_status: &'static str,
+    can_control: bool,
+}
+
+impl State {
+    fn playing() -> State {
+        State {
+            playback_status: "Playing",
+            can_control: true,
+        }
+    }
+}
+
+/// Serves a fixed set of MPRIS properties from its own thread and connection until it is dropped.
+pub(crate) struct FakePlayer {
+    bus: BusType,
+    bus_name: String,
+    stop: Arc<AtomicBool>,
+    calls: Arc<Mutex<Vec<String>>>,
+    signals: mpsc::Sender<Message>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FakePlayer {
+    pub(crate) fn spawn() -> FakePlayer {
+        FakePlayer::spawn_with_status("Playing")
+    }
+
+    /// Spawns a player that reports the given `PlaybackStatus`.
+    pub(crate) fn spawn_with_status(playback_status: &'static str) -> FakePlayer {
+        let state = State {
+            playback_status,
+            ..State::playing()
+        };
+        FakePlayer::spawn_with(state, &[], BusType::SessionBus, new_bus_name())
+    }
+
+    /// Spawns a player that reports that it cannot be controlled.
+    pub(crate) fn spawn_uncontrollable() -> FakePlayer {
+        let state = State {
+            can_control: false,
+            ..State::playing()
+        };
+        FakePlayer::spawn_with(state, &[], BusType::SessionBus, new_bus_name())
+    }
+
+    /// Spawns a player that records, but never answers, calls of the given methods, so that they
+    /// time out.
+    pub(crate) fn spawn_ignoring(ignored_methods: &'static [&'static str]) -> FakePlayer {
+        FakePlayer::spawn_with(
+            State::playing(),
+            ignored_methods,
+            BusType::SessionBus,
+            new_bus_name(),
+        )
+    }
+
+    /// Spawns a player with the given bus name on a [`TestBus`], like to get back a player after
+    /// the bus was restarted.
+    pub(crate) fn spawn_on(bus: &TestBus, bus_name: &str) -> FakePlayer {
+        FakePlayer::spawn_with(
+            State::playing(),
+            &[],
+            BusType::Custom(bus.address()),
+            bus_name.to_string(),
+        )
+    }
+
+    fn spawn_with(
+        state: State,
+        ignored_methods: &'static [&'static str],
+        bus: BusType,
+        bus_name: String,
+    ) -> FakePlayer {
+        let stop = Arc::new(AtomicBool::new(false));
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (ready_sender, ready_receiver) = mpsc::channel();
+        let (signals, signal_receiver) = mpsc::channel::<Message>();
+
+        let thread = {
+            let bus_name = bus_name.clone();
+            let stop = Arc::clone(&stop);
+            let calls = Arc::clone(&calls);
+            let bus = bus.clone();
+            thread::spawn(move || {
+                let connection = open_connection(&bus).expect("Could not open a D-Bus connection");
+                connection
+                    .register_name(&bus_name, NameFlag::DoNotQueue as u32)
+                    .expect("Could not register the fake player's name");
+                connection
+                    .register_object_path(MPRIS2_PATH)
+                    .expect("Could not register object path");
+                ready_sender.send(()).unwrap();
+
+                while !stop.load(Ordering::SeqCst) && connection.is_connected() {
+                    while let Ok(signal) = signal_receiver.try_recv() {
+                        let _ = connection.send(signal);
+                    }
+                    if let Some(ConnectionItem::MethodCall(call)) = connection.iter(20).next() {
+                        let member = call.member().map(|member| member.to_string());
+                        if let Some(ref member) = member {
+                            calls.lock().unwrap().push(member.clone());
+                        }
+                        if !ignored_methods.contains(&member.as_deref().unwrap_or_default()) {
+                            let _ = connection.send(reply_to(&call, state));
+                        }
+                    }
+                }
+
+                // Release explicitly, so the name is gone by the time the player is dropped.
+                let _ = connection.release_name(&bus_name);
+            })
+        };
+
+        ready_receiver
+            .recv()
+            .expect("The fake player did not start");
+
+        FakePlayer {
+            bus,
+            bus_name,
+            stop,
+            calls,
+            signals,
+            thread: Some(thread),
+        }
+    }
+
+    pub(crate) fn bus_name(&self) -> &str {
+        &self.bus_name
+    }
+
+    /// The names of all methods that were called on the player so far, like `Pause` or `GetAll`.
+    pub(crate) fn received_calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Sends the given signal from the player's connection, so that it comes from its bus name.
+    pub(crate) fn emit(&self, signal: Message) {
+        self.signals
+            .send(signal)
+            .expect("The fake player is not running");
+    }
+
+    /// Finds this player with a new [`PlayerFinder`](crate::PlayerFinder), and thus a new connection.
+    pub(crate) fn find(&self) -> Player<'static> {
+        PlayerFinderBuilder::new()
+            .bus_type(self.bus.clone())
+            .build()
+            .expect("Could not connect to D-Bus")
+            .find_all()
+            .expect("Could not find players")
+            .into_iter()
+            .find(|player| &**player.bus_name() == self.bus_name.as_str())
+            .expect("Could not find the fake player")
+    }
+}
+
+/// A [`PlayerFinder`] on the session bus that only finds the given fake players, as other tests
+/// run their own fake players on the same bus.
+pub(crate) fn finder_for(fakes: &[&FakePlayer]) -> PlayerFinder {
+    let bus_names: Vec<String> = fakes
+        .iter()
+        .map(|fake| fake.bus_name().to_owned())
+        .collect();
+    PlayerFinderBuilder::new()
+        .filter(move |player| bus_names.iter().any(|name| name == &**player.bus_name()))
+        .build()
+        .expect("Could not connect to D-Bus")
+}
+
+impl Drop for FakePlayer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A `dbus-daemon` of its own at a fixed address, that can be restarted to test what happens when
+/// the connection to the bus is lost.
+pub(crate) struct TestBus {
+    socket: PathBuf,
+    daemon: Child,
+}
+
+impl TestBus {
+    pub(crate) fn start() -> TestBus {
+        let socket = std::env::temp_dir().join(format!(
+            "mpris_rs_test_bus.p{}_{}",
+            process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst)
+        ));
+        let daemon = start_daemon(&socket);
+        TestBus { socket, daemon }
+    }
+
+    pub(crate) fn address(&self) -> String {
+        format!("unix:path={}", self.socket.display())
+    }
+
+    /// Stops the daemon, which disconnects everything that was connected to it, and starts a new
+    /// one at the same address.
+    pub(crate) fn restart(&mut self) {
+        self.stop();
+        self.daemon = start_daemon(&self.socket);
+    }
+
+    fn stop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+impl Drop for TestBus {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn start_daemon(socket: &std::path::Path) -> Child {
+    let mut daemon = Command::new("dbus-daemon")
+        .arg("--session")
+        .arg("--nofork")
+        .arg("--print-address")
+        .arg(format!("--address=unix:path={}", socket.display()))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Could not start dbus-daemon");
+
+    // The address is printed once the daemon accepts connections.
+    let mut address = String::new();
+    BufReader::new(daemon.stdout.take().unwrap())
+        .read_line(&mut address)
+        .expect("dbus-daemon did not start");
+    daemon
+}
+
+fn new_bus_name() -> String {
+    format!(
+        "{}mpris_rs_test.p{}_{}",
+        MPRIS2_PREFIX,
+        process::id(),
+        NEXT_ID.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+fn reply_to(call: &Message, state: State) -> Message {
+    let interface = call.interface().map(|i| i.to_string()).unwrap_or_default();
+    let member = call.member().map(|m| m.to_string()).unwrap_or_default();
+
+    match (interface.as_str(), member.as_str()) {
+        ("org.freedesktop.DBus.Introspectable", "Introspect") => call.method_return().append1(
+            "<node><interface name=\"org.mpris.MediaPlayer2\"/>\
+             <interface name=\"org.mpris.MediaPlayer2.Player\">\
+             <property name=\"PlaybackStatus\" type=\"s\" access=\"read\"/>\
+             <property name=\"Volume\" type=\"d\" access=\"readwrite\"/>\
+             </interface></node>",
+        ),
+        ("org.mpris.MediaPlayer2.Playlists", "GetPlaylists") => {
+            call.method_return().append1(vec![fake_playlist()])
+        }
+        ("org.mpris.MediaPlayer2.TrackList", "GetTracksMetadata") => {
+            match call.get1::<Vec<dbus::Path<'_>>>() {
+                Some(ids) => call
+                    .method_return()
+                    .append1(ids.into_iter().map(track_metadata).collect::<Vec<_>>()),
+                None => invalid_args(call),
+            }
+        }
+        ("org.freedesktop.DBus.Properties", "GetAll") => match call.get1::<&str>() {
+            Some(interface) => call.method_return().append1(properties(interface, state)),
+            None => invalid_args(call),
+        },
+        ("org.freedesktop.DBus.Properties", "Get") => {
+            let (interface, name) = call.get2::<&str, &str>();
+            match properties(interface.unwrap_or_default(), state).remove(name.unwrap_or_default())
+            {
+                Some(value) => call.method_return().append1(value),
+                None => invalid_args(call),
+            }
+        }
+        // Accept every change without remembering it.
+        ("org.freedesktop.DBus.Properties", "Set") => call.method_return(),
+        ("org.mpris.MediaPlayer2.Player", _)
+        | ("org.mpris.MediaPlayer2.Playlists", _)
+        | ("org.mpris.MediaPlayer2", _) => call.method_return(),
+        _ => call.error(
+            &"org.freedesktop.DBus.Error.UnknownMethod".into(),
+            &std::ffi::CString::new("Unknown method").unwrap(),
+        ),
+    }
+}
+
+fn invalid_args(call: &Message) -> Message {
+    call.error(
+        &"org.freedesktop.DBus.Error.InvalidArgs".into(),
+        &std::ffi::CString::new("No such property").unwrap(),
+    )
+}
+
+fn variant<T: RefArg + 'static>(value: T) -> Variant<Box<dyn RefArg>> {
+    Variant(Box::new(value))
+}
+
+fn track_metadata(id: dbus::Path<'_>) -> HashMap<String, Variant<Box<dyn RefArg>>> {
+    let mut metadata: HashMap<String, Variant<Box<dyn RefArg>>> = HashMap::new();
+    metadata.insert(String::from("mpris:trackid"), variant(id.into_static()));
+    metadata
+}
+
+fn fake_playlist() -> (dbus::Path<'static>, String, String) {
+    (
+        dbus::Path::from("/fake/playlist/1"),
+        String::from("Fake playlist"),
+        String::new(),
+    )
+}
+
+fn properties(interface: &str, state: State) -> Properties {
+    let mut properties = Properties::new();
+    match interface {
+        "org.mpris.MediaPlayer2" => {
+            properties.insert("Identity", variant(String::from("Fake player")));
+            properties.insert("CanQuit", variant(true));
+            properties.insert("CanRaise", variant(false));
+            properties.insert("HasTrackList", variant(false));
+            properties.insert("SupportedUriSchemes", variant(vec![String::from("file")]));
+            properties.insert("SupportedMimeTypes", variant(Vec::<String>::new()));
+        }
+        "org.mpris.MediaPlayer2.Player" => {
+            let mut metadata: HashMap<String, Variant<Box<dyn RefArg>>> = HashMap::new();
+            metadata.insert(
+                String::from("mpris:trackid"),
+                variant(dbus::Path::from("/fake/track/1")),
+            );
+            metadata.insert(String::from("xesam:title"), variant(String::from("Fake")));
+
+            properties.insert(
+                "PlaybackStatus",
+                variant(String::from(state.playback_status)),
+            );
+            properties.insert("Metadata", variant(metadata));
+            properties.insert("Volume", variant(0.5));
+            properties.insert("Position", variant(1_000_000i64));
+            properties.insert("Rate", variant(1.0));
+            properties.insert("MinimumRate", variant(0.5));
+            properties.insert("MaximumRate", variant(2.0));
+            properties.insert("CanControl", variant(state.can_control));
+            properties.insert("CanPlay", variant(true));
+            properties.insert("CanPause", variant(true));
+            properties.insert("Shuffle", variant(true));
+            properties.insert("LoopStatus", variant(String::from("Track")));
+        }
+        "org.mpris.MediaPlayer2.Playlists" => {
+            properties.insert("PlaylistCount", variant(1u32));
+            properties.insert("Orderings", variant(vec![String::from("Alphabetical")]));
+            properties.insert(
+                "ActivePlaylist",
+                variant(MaybePlaylist::from(Some(Playlist::from(fake_playlist())))),
+            );
+        }
+        _ => {}
+    }
+    properties
+}
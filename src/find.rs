@@ -1,16 +1,22 @@
 use failure::Fail;
 
+use std::fmt;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
-use dbus::ffidisp::{BusType, Connection};
+use dbus::ffidisp::{BusType as DBusBusType, Connection};
+use dbus::strings::BusName;
 use dbus::{arg, Message};
 
-use super::DBusError;
+use super::{DBusError, RetryPolicy};
 use crate::player::{Player, DEFAULT_TIMEOUT_MS, MPRIS2_PATH, MPRIS2_PREFIX};
 use crate::pooled_connection::PooledConnection;
+use crate::watcher::PlayerWatcher;
 use crate::PlaybackStatus;
 
-const LIST_NAMES_TIMEOUT_MS: i32 = 500;
+#[cfg(feature = "async")]
+use crate::AsyncPlayer;
 
 /// This enum encodes possible error cases that could happen when finding players.
 #[derive(Fail, Debug)]
@@ -22,6 +28,11 @@ pub enum FindingError {
     /// Finding failed due to an underlying [`DBusError`].
     #[fail(display = "{}", _0)]
     DBusError(#[cause] DBusError),
+
+    /// More than one player was found where only one was expected. Contains the number of players
+    /// that were found.
+    #[fail(display = "Found {} players instead of one", _0)]
+    TooManyPlayers(usize),
 }
 
 impl From<dbus::Error> for FindingError {
@@ -39,9 +50,156 @@ impl From<DBusError> for FindingError {
 /// Used to find [`Player`]s running on a D-Bus connection.
 ///
 /// All find results are sorted in alphabetical order.
+///
+/// See [`PlayerFinderBuilder`] to configure timeouts, the bus and which players to find.
 #[derive(Debug)]
 pub struct PlayerFinder {
     connection: Rc<PooledConnection>,
+    retry_policy: RetryPolicy,
+    timeout_ms: i32,
+    filters: Vec<PlayerFilter>,
+    bus: BusType,
+    bus_name_prefix: String,
+}
+
+/// The D-Bus bus that a [`PlayerFinder`] looks for players on.
+///
+/// MPRIS players normally live on the session bus, but some system-wide players (like media
+/// servers and kiosk setups) register on the system bus instead.
+///
+/// See [`PlayerFinderBuilder::bus_type`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum BusType {
+    /// The session bus of the current user. This is the default.
+    #[default]
+    SessionBus,
+
+    /// The system-wide bus.
+    SystemBus,
+
+    /// The bus at the given [D-Bus address][address].
+    ///
+    /// [address]: https://dbus.freedesktop.org/doc/dbus-specification.html#addresses
+    Custom(String),
+}
+
+/// Builds a [`PlayerFinder`] with a custom configuration.
+///
+/// ```rust,no_run
+/// use mpris::PlayerFinderBuilder;
+/// use std::time::Duration;
+///
+/// let finder = PlayerFinderBuilder::new()
+///     .timeout(Duration::from_secs(5))
+///     .require_can_play(true)
+///     .build()
+///     .expect("Could not connect to D-Bus");
+/// ```
+#[derive(Debug, Default)]
+pub struct PlayerFinderBuilder {
+    timeout: Option<Duration>,
+    bus: BusType,
+    // Validated by `PlayerFinder::with_bus_name_prefix`; `None` for the MPRIS prefix.
+    bus_name_prefix: Option<String>,
+    retry_policy: RetryPolicy,
+    filters: Vec<PlayerFilter>,
+}
+
+/// A predicate that every found [`Player`] has to pass.
+#[derive(Clone)]
+struct PlayerFilter(Arc<dyn Fn(&Player<'_>) -> bool + Send + Sync>);
+
+impl fmt::Debug for PlayerFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PlayerFilter")
+    }
+}
+
+impl PlayerFinderBuilder {
+    /// Creates a new builder with the same configuration as [`PlayerFinder::new`].
+    pub fn new() -> Self {
+        PlayerFinderBuilder::default()
+    }
+
+    /// Sets the timeout of every D-Bus call made by the [`PlayerFinder`] and the [`Player`]s that
+    /// it finds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Connects to the given bus instead of the session bus.
+    pub fn bus_type(mut self, bus: BusType) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Connects to the bus at the given [D-Bus address][address] instead of the session bus.
+    ///
+    /// This is a shorthand for [`bus_type`](Self::bus_type) with [`BusType::Custom`].
+    ///
+    /// [address]: https://dbus.freedesktop.org/doc/dbus-specification.html#addresses
+    pub fn bus_address<S: Into<String>>(self, address: S) -> Self {
+        self.bus_type(BusType::Custom(address.into()))
+    }
+
+    /// Sets the [`RetryPolicy`]; see [`PlayerFinder::with_retry_policy`].
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Only find players that report being able to play, when `required` is `true`.
+    ///
+    /// See [`Player::can_play`]. Players that fail to answer are skipped.
+    pub fn require_can_play(self, required: bool) -> Self {
+        if required {
+            self.filter(|player| player.can_play().unwrap_or(false))
+        } else {
+            self
+        }
+    }
+
+    /// Only find players that can be controlled, when `required` is `true`.
+    ///
+    /// See [`Player::can_control`]. Players that fail to answer are skipped.
+    pub fn require_can_control(self, required: bool) -> Self {
+        if required {
+            self.filter(|player| player.can_control().unwrap_or(false))
+        } else {
+            self
+        }
+    }
+
+    /// Only find players for which the given predicate returns `true`.
+    ///
+    /// Every filter has to pass for a player to be found. The predicate has to be [`Send`] and
+    /// [`Sync`], as `find_all_async` runs it on a separate thread.
+    pub fn filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Player<'_>) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(PlayerFilter(Arc::new(predicate)));
+        self
+    }
+
+    /// Connects to D-Bus and creates the [`PlayerFinder`].
+    pub fn build(self) -> Result<PlayerFinder, DBusError> {
+        let connection = open_connection(&self.bus)?;
+        let connection = PooledConnection::for_bus(connection, self.bus.clone());
+
+        let mut finder =
+            PlayerFinder::for_pooled_connection(connection).with_retry_policy(self.retry_policy);
+        if let Some(timeout) = self.timeout {
+            finder.timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+        }
+        finder.filters = self.filters;
+        finder.bus = self.bus;
+        if let Some(prefix) = self.bus_name_prefix {
+            finder.bus_name_prefix = prefix;
+        }
+        Ok(finder)
+    }
 }
 
 impl PlayerFinder {
@@ -50,50 +208,203 @@ impl PlayerFinder {
     /// Use [`for_connection`](Self::for_connection) if you want to provide the D-Bus connection yourself.
     pub fn new() -> Result<Self, DBusError> {
         Ok(PlayerFinder::for_connection(Connection::get_private(
-            BusType::Session,
+            DBusBusType::Session,
         )?))
     }
 
+    /// Creates a new [`PlayerFinder`] that looks for players on the session bus.
+    ///
+    /// This is the same as [`new`](Self::new).
+    pub fn for_session_bus() -> Result<Self, DBusError> {
+        PlayerFinder::new()
+    }
+
+    /// Creates a new [`PlayerFinder`] that looks for players on the system bus.
+    pub fn for_system_bus() -> Result<Self, DBusError> {
+        PlayerFinderBuilder::new()
+            .bus_type(BusType::SystemBus)
+            .build()
+    }
+
     /// Create a new [`PlayerFinder`] with the given connection.
     ///
     /// Use [`new`](Self::new) if you want a new default connection rather than manually managing the D-Bus
     /// connection.
     pub fn for_connection(connection: Connection) -> Self {
+        PlayerFinder::for_pooled_connection(connection.into())
+    }
+
+    fn for_pooled_connection(connection: PooledConnection) -> Self {
         PlayerFinder {
-            connection: Rc::new(connection.into()),
+            connection: Rc::new(connection),
+            retry_policy: RetryPolicy::default(),
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            filters: Vec::new(),
+            bus: BusType::SessionBus,
+            bus_name_prefix: String::from(MPRIS2_PREFIX),
         }
     }
 
+    /// Returns a [`PlayerFinderBuilder`] for a finder that looks for players with bus names that
+    /// start with the given prefix, instead of `org.mpris.MediaPlayer2.`.
+    ///
+    /// This is for MPRIS-like services that do not follow the naming of the specification, like
+    /// test services or embedded players. The players still have to implement the MPRIS
+    /// interfaces at `/org/mpris/MediaPlayer2`.
+    ///
+    /// ```rust,no_run
+    /// use mpris::PlayerFinder;
+    ///
+    /// let finder = PlayerFinder::with_bus_name_prefix("com.example.Player.")
+    ///     .expect("Invalid prefix")
+    ///     .build()
+    ///     .expect("Could not connect to D-Bus");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no well-known bus name can start with the prefix, like when it is
+    /// empty, starts with a `:` or contains characters that bus names cannot have.
+    pub fn with_bus_name_prefix(prefix: &str) -> Result<PlayerFinderBuilder, DBusError> {
+        // A prefix is valid if it can be completed into a valid well-known bus name.
+        if prefix.starts_with(':') || BusName::new(format!("{}a", prefix)).is_err() {
+            return Err(DBusError::Miscellaneous(format!(
+                "{:?} is not a valid bus name prefix",
+                prefix
+            )));
+        }
+
+        Ok(PlayerFinderBuilder {
+            bus_name_prefix: Some(prefix.to_owned()),
+            ..PlayerFinderBuilder::default()
+        })
+    }
+
+    /// Returns the prefix of the bus names of the players that this finder looks for; normally
+    /// `org.mpris.MediaPlayer2.`.
+    ///
+    /// See [`with_bus_name_prefix`](Self::with_bus_name_prefix).
+    pub fn bus_name_prefix(&self) -> &str {
+        &self.bus_name_prefix
+    }
+
+    /// Retry D-Bus calls that fail with transient errors, according to the given [`RetryPolicy`].
+    ///
+    /// The policy is also used by every [`Player`] that this finder returns. By default, nothing is
+    /// retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Watch for players appearing on and disappearing from the bus.
+    ///
+    /// The returned [`PlayerWatcher`] opens a connection of its own to listen for changes; it
+    /// connects to the session bus, or to the bus given to [`PlayerFinderBuilder::bus_type`].
+    pub fn watch(&self) -> Result<PlayerWatcher<'_>, DBusError> {
+        PlayerWatcher::new(self, open_connection(&self.bus)?)
+    }
+
     /// Find all available [`Player`]s in the connection.
     pub fn find_all<'b>(&self) -> Result<Vec<Player<'b>>, FindingError> {
-        self.all_player_buses()
-            .map_err(FindingError::from)?
-            .into_iter()
-            .map(|bus_name| {
-                Player::for_pooled_connection(
-                    Rc::clone(&self.connection),
-                    bus_name.into(),
-                    MPRIS2_PATH.into(),
-                    DEFAULT_TIMEOUT_MS,
-                )
-                .map_err(FindingError::from)
-            })
-            .collect()
+        let mut players = Vec::new();
+        for bus_name in self.all_player_buses()? {
+            let player = self.player_for_bus(bus_name)?;
+            if self.matches_filters(&player) {
+                players.push(player);
+            }
+        }
+        Ok(players)
+    }
+
+    /// Find all available [`Player`]s, as [`AsyncPlayer`]s.
+    ///
+    /// The lookup happens on a separate thread with its own D-Bus connection, so the returned
+    /// future does not borrow this finder and will not block the executor. That connection and
+    /// the players use the bus, bus name prefix, timeout, filters and retry policy of this finder.
+    ///
+    /// **Requires the `async` feature.**
+    #[cfg(feature = "async")]
+    pub fn find_all_async(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<AsyncPlayer>, FindingError>> {
+        let builder = self.to_builder();
+        let bus = self.bus.clone();
+        let timeout_ms = self.timeout_ms;
+        let retry_policy = self.retry_policy;
+
+        async move {
+            let (sender, receiver) = tokio::sync::oneshot::channel();
+            std::thread::spawn(move || {
+                let busses = builder
+                    .build()
+                    .map_err(FindingError::from)
+                    .and_then(|finder| finder.find_all())
+                    .map(|players| {
+                        players
+                            .iter()
+                            .map(|player| player.bus_name().to_string())
+                            .collect::<Vec<String>>()
+                    });
+                let _ = sender.send(busses);
+            });
+
+            let busses = receiver.await.map_err(|_| {
+                DBusError::Miscellaneous(String::from("Finding thread stopped unexpectedly"))
+            })??;
+
+            let mut players = Vec::with_capacity(busses.len());
+            for bus_name in busses {
+                players.push(
+                    AsyncPlayer::start(
+                        bus.clone(),
+                        bus_name,
+                        MPRIS2_PATH,
+                        timeout_ms,
+                        retry_policy,
+                    )
+                    .await?,
+                );
+            }
+            Ok(players)
+        }
+    }
+
+    /// Returns a builder for a finder with the same configuration as this one, so that it can be
+    /// created again on another thread.
+    #[cfg(feature = "async")]
+    fn to_builder(&self) -> PlayerFinderBuilder {
+        PlayerFinderBuilder {
+            timeout: Some(Duration::from_millis(self.timeout_ms.max(0) as u64)),
+            bus: self.bus.clone(),
+            bus_name_prefix: Some(self.bus_name_prefix.clone()),
+            retry_policy: self.retry_policy,
+            filters: self.filters.clone(),
+        }
     }
 
     /// Return the first found [`Player`] regardless of state.
     pub fn find_first<'b>(&self) -> Result<Player<'b>, FindingError> {
-        let busses = self.all_player_buses()?;
-        if let Some(bus_name) = busses.into_iter().next() {
-            Player::for_pooled_connection(
-                Rc::clone(&self.connection),
-                bus_name.into(),
-                MPRIS2_PATH.into(),
-                DEFAULT_TIMEOUT_MS,
-            )
-            .map_err(FindingError::from)
-        } else {
-            Err(FindingError::NoPlayerFound)
+        for bus_name in self.all_player_buses()? {
+            let player = self.player_for_bus(bus_name)?;
+            if self.matches_filters(&player) {
+                return Ok(player);
+            }
+        }
+        Err(FindingError::NoPlayerFound)
+    }
+
+    /// Return the only found [`Player`], for when exactly one player should be running, like in a
+    /// test environment.
+    ///
+    /// Returns [`NoPlayerFound`](FindingError::NoPlayerFound) if there is no player, and
+    /// [`TooManyPlayers`](FindingError::TooManyPlayers) if there is more than one.
+    pub fn find_exactly_one<'b>(&self) -> Result<Player<'b>, FindingError> {
+        let mut players = self.find_all()?;
+        match players.len() {
+            0 => Err(FindingError::NoPlayerFound),
+            1 => Ok(players.remove(0)),
+            count => Err(FindingError::TooManyPlayers(count)),
         }
     }
 
@@ -155,8 +466,70 @@ impl PlayerFinder {
         Err(FindingError::NoPlayerFound)
     }
 
+    /// Find a [`Player`] by its MPRIS [`Identity`][identity], ignoring case. Returns
+    /// [`NoPlayerFound`](FindingError::NoPlayerFound) if no player matches.
+    ///
+    /// Unlike [`find_by_name`](Self::find_by_name), players that fail to answer are skipped instead
+    /// of failing the whole search.
+    ///
+    /// [identity]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Identity
+    pub fn find_by_identity<'b>(&self, identity: &str) -> Result<Player<'b>, FindingError> {
+        let identity = identity.to_lowercase();
+        self.find_first_where(|player| player.identity().to_lowercase() == identity)
+    }
+
+    /// Find a [`Player`] whose MPRIS [`Identity`][identity] starts with the given prefix, ignoring
+    /// case. Like [`find_by_identity`](Self::find_by_identity), players that fail to answer are
+    /// skipped.
+    ///
+    /// [identity]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Identity
+    pub fn find_by_identity_prefix<'b>(&self, prefix: &str) -> Result<Player<'b>, FindingError> {
+        let prefix = prefix.to_lowercase();
+        self.find_first_where(|player| player.identity().to_lowercase().starts_with(&prefix))
+    }
+
+    /// Returns the first player that matches the predicate, skipping players that fail to load.
+    fn find_first_where<'b, F>(&self, predicate: F) -> Result<Player<'b>, FindingError>
+    where
+        F: Fn(&Player<'b>) -> bool,
+    {
+        for bus_name in self.all_player_buses()? {
+            if let Ok(player) = self.player_for_bus(bus_name) {
+                if self.matches_filters(&player) && predicate(&player) {
+                    return Ok(player);
+                }
+            }
+        }
+        Err(FindingError::NoPlayerFound)
+    }
+
+    fn player_for_bus<'b>(&self, bus_name: String) -> Result<Player<'b>, FindingError> {
+        self.retry_policy
+            .run(|| {
+                Player::for_pooled_connection(
+                    Rc::clone(&self.connection),
+                    bus_name.clone().into(),
+                    MPRIS2_PATH.into(),
+                    self.timeout_ms,
+                )
+            })
+            .map(|player| player.with_retry_policy(self.retry_policy))
+            .map_err(FindingError::from)
+    }
+
+    /// Creates the player for a bus that just appeared, if it can be loaded and passes the filters.
+    pub(crate) fn player_for_new_bus<'b>(&self, bus_name: String) -> Option<Player<'b>> {
+        self.player_for_bus(bus_name)
+            .ok()
+            .filter(|player| self.matches_filters(player))
+    }
+
+    fn matches_filters(&self, player: &Player<'_>) -> bool {
+        self.filters.iter().all(|filter| (filter.0)(player))
+    }
+
     /// Returns all of the MPRIS DBus paths
-    fn all_player_buses(&self) -> Result<Vec<String>, DBusError> {
+    pub(crate) fn all_player_buses(&self) -> Result<Vec<String>, DBusError> {
         let list_names = Message::new_method_call(
             "org.freedesktop.DBus",
             "/",
@@ -168,15 +541,249 @@ impl PlayerFinder {
         let reply = self
             .connection
             .underlying()
-            .send_with_reply_and_block(list_names, LIST_NAMES_TIMEOUT_MS)?;
+            .send_with_reply_and_block(list_names, self.timeout_ms)?;
 
         let names: arg::Array<'_, &str, _> = reply.read1().map_err(DBusError::from)?;
 
         let mut all_busses = names
-            .filter(|name| name.starts_with(MPRIS2_PREFIX))
+            .filter(|name| name.starts_with(&self.bus_name_prefix))
             .map(|str_ref| str_ref.to_owned())
             .collect::<Vec<String>>();
         all_busses.sort_by_key(|a| a.to_lowercase());
         Ok(all_busses)
     }
 }
+
+pub(crate) fn open_connection(bus: &BusType) -> Result<Connection, DBusError> {
+    match (bus, dbus_bus_type(bus)) {
+        (_, Some(bus_type)) => Ok(Connection::get_private(bus_type)?),
+        (BusType::Custom(address), None) => {
+            let connection = Connection::open_private(address)?;
+            connection.register()?;
+            Ok(connection)
+        }
+        (_, None) => unreachable!("Only custom buses have no D-Bus bus type"),
+    }
+}
+
+/// The bus that libdbus connects to for the given [`BusType`], or [`None`] for custom addresses.
+fn dbus_bus_type(bus: &BusType) -> Option<DBusBusType> {
+    match bus {
+        BusType::SessionBus => Some(DBusBusType::Session),
+        BusType::SystemBus => Some(DBusBusType::System),
+        BusType::Custom(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::fake_player::{finder_for, FakePlayer, TestBus};
+    use crate::PlayerWatchEvent;
+
+    #[test]
+    fn it_fails_to_build_without_a_bus() {
+        let result = PlayerFinderBuilder::new()
+            .timeout(Duration::from_millis(100))
+            .bus_address("unix:path=/nonexistent/mpris-rs/bus")
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_connects_to_the_session_bus_by_default() {
+        assert_eq!(BusType::default(), BusType::SessionBus);
+        assert_eq!(
+            dbus_bus_type(&BusType::default()),
+            Some(DBusBusType::Session)
+        );
+    }
+
+    #[test]
+    fn it_connects_to_the_system_bus() {
+        assert_eq!(
+            dbus_bus_type(&BusType::SystemBus),
+            Some(DBusBusType::System)
+        );
+        assert_eq!(
+            dbus_bus_type(&BusType::Custom("unix:path=/bus".into())),
+            None
+        );
+    }
+
+    #[test]
+    fn it_connects_to_custom_bus_addresses() {
+        let bus = TestBus::start();
+        let fake = FakePlayer::spawn_on(&bus, "org.mpris.MediaPlayer2.mpris_rs_test.custom");
+
+        let connection = open_connection(&BusType::Custom(bus.address())).unwrap();
+        let player = Player::new(connection, fake.bus_name().to_string(), MPRIS2_PATH, 500)
+            .expect("Could not find the player on the test bus");
+
+        assert_eq!(player.identity(), "Fake player");
+    }
+
+    #[test]
+    fn it_validates_bus_name_prefixes() {
+        assert!(PlayerFinder::with_bus_name_prefix("com.example.Player.").is_ok());
+        assert!(PlayerFinder::with_bus_name_prefix("com.example.Player").is_ok());
+
+        assert!(PlayerFinder::with_bus_name_prefix("").is_err());
+        assert!(PlayerFinder::with_bus_name_prefix(":1.").is_err());
+        assert!(PlayerFinder::with_bus_name_prefix("com..example.").is_err());
+        assert!(PlayerFinder::with_bus_name_prefix("com.example/Player.").is_err());
+    }
+
+    #[test]
+    fn it_finds_players_by_bus_name_prefix() {
+        let fake = FakePlayer::spawn();
+        let has_fake = |prefix: &str| {
+            let finder = PlayerFinder::with_bus_name_prefix(prefix)
+                .unwrap()
+                .build()
+                .unwrap();
+            assert_eq!(finder.bus_name_prefix(), prefix);
+            finder
+                .all_player_buses()
+                .unwrap()
+                .iter()
+                .any(|name| name == fake.bus_name())
+        };
+
+        assert!(has_fake("org.mpris.MediaPlayer2.mpris_rs_test."));
+        assert!(!has_fake("org.mpris.MediaPlayer2.mpris_rs_other."));
+        assert_eq!(
+            PlayerFinder::new().unwrap().bus_name_prefix(),
+            MPRIS2_PREFIX
+        );
+    }
+
+    #[test]
+    fn players_share_the_finder_connection() {
+        let one = FakePlayer::spawn();
+        let other = FakePlayer::spawn();
+        let finder = finder_for(&[&one, &other]);
+
+        let players = finder.find_all().unwrap();
+        assert_eq!(players.len(), 2);
+        for player in &players {
+            assert!(Rc::ptr_eq(&player.connection(), &finder.connection));
+        }
+    }
+
+    #[test]
+    fn find_active_prefers_playing_then_paused() {
+        let paused = FakePlayer::spawn_with_status("Paused");
+        let stopped = FakePlayer::spawn_with_status("Stopped");
+        let playing = FakePlayer::spawn_with_status("Playing");
+
+        let finder = finder_for(&[&paused, &stopped, &playing]);
+        assert_eq!(finder.find_active().unwrap(), playing.find());
+
+        let finder = finder_for(&[&stopped, &paused]);
+        assert_eq!(finder.find_active().unwrap(), paused.find());
+
+        let finder = finder_for(&[]);
+        assert!(matches!(
+            finder.find_active(),
+            Err(FindingError::NoPlayerFound)
+        ));
+    }
+
+    #[test]
+    fn find_exactly_one_rejects_other_counts() {
+        let one = FakePlayer::spawn();
+        let other = FakePlayer::spawn_with_status("Stopped");
+
+        let finder = finder_for(&[&other]);
+        assert_eq!(finder.find_first().unwrap(), other.find());
+        assert_eq!(finder.find_exactly_one().unwrap(), other.find());
+
+        let finder = finder_for(&[&one, &other]);
+        assert!(finder.find_first().is_ok());
+        assert!(matches!(
+            finder.find_exactly_one(),
+            Err(FindingError::TooManyPlayers(2))
+        ));
+
+        let finder = finder_for(&[]);
+        assert!(matches!(
+            finder.find_first(),
+            Err(FindingError::NoPlayerFound)
+        ));
+        assert!(matches!(
+            finder.find_exactly_one(),
+            Err(FindingError::NoPlayerFound)
+        ));
+    }
+
+    #[test]
+    fn find_by_identity_ignores_case() {
+        let fake = FakePlayer::spawn();
+        let finder = finder_for(&[&fake]);
+
+        assert_eq!(finder.find_by_identity("FAKE player").unwrap(), fake.find());
+        assert_eq!(finder.find_by_identity_prefix("fake").unwrap(), fake.find());
+        assert!(matches!(
+            finder.find_by_identity("Fake"),
+            Err(FindingError::NoPlayerFound)
+        ));
+        assert!(matches!(
+            finder.find_by_identity_prefix("player"),
+            Err(FindingError::NoPlayerFound)
+        ));
+    }
+
+    #[test]
+    fn watch_reports_connected_and_disconnected_players() {
+        let finder = PlayerFinder::new().unwrap();
+        let mut watcher = finder.watch().unwrap();
+
+        let fake = FakePlayer::spawn();
+        let bus_name = fake.bus_name().to_string();
+        let connected = watcher
+            .find_map(|event| match event {
+                PlayerWatchEvent::PlayerConnected(player) if **player.bus_name() == *bus_name => {
+                    Some(player)
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(connected, fake.find());
+
+        drop(fake);
+        assert!(watcher.any(|event| matches!(
+            event,
+            PlayerWatchEvent::PlayerDisconnected { bus_name: ref name } if *name == bus_name
+        )));
+    }
+
+    #[test]
+    fn it_applies_filters() {
+        let fake = FakePlayer::spawn();
+        let bus_name = fake.find().bus_name().to_string();
+
+        let only_fake = move |player: &Player<'_>| &**player.bus_name() == bus_name.as_str();
+        let finder = PlayerFinderBuilder::new()
+            .timeout(Duration::from_millis(100))
+            .require_can_play(true)
+            .filter(only_fake.clone())
+            .build()
+            .unwrap();
+        assert_eq!(finder.timeout_ms, 100);
+        assert_eq!(finder.find_all().unwrap().len(), 1);
+
+        let finder = PlayerFinderBuilder::new()
+            .filter(only_fake)
+            .filter(|player| player.identity() != "Fake player")
+            .build()
+            .unwrap();
+        assert!(finder.find_all().unwrap().is_empty());
+        assert!(matches!(
+            finder.find_first(),
+            Err(FindingError::NoPlayerFound)
+        ));
+    }
+}
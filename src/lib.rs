@@ -25,30 +25,98 @@
 //! 1. Look at the examples under `examples/`.
 //! 2. Look at the [`PlayerFinder`] struct.
 //!
+//! ## Features
+//!
+//! * `async`: Adds `AsyncPlayer`, which has async versions of the [`Player`] methods and can be
+//!   used from a [tokio](https://tokio.rs/) executor without blocking it.
+//! * `chrono`: Adds `Metadata::content_created`, which parses the creation date of a track into a
+//!   [chrono](https://docs.rs/chrono) `DateTime`.
+//! * `json`: Adds `Metadata::to_json_value` and `Metadata::from_json_value`, which convert
+//!   [`Metadata`] to and from a [`serde_json::Value`](https://docs.rs/serde_json).
+//! * `parallel`: Makes a [`TrackList`] load missing metadata in chunks, on a few threads that each
+//!   have their own D-Bus connection. See `TrackList::set_fetch_chunk_size`.
+//! * `serde`: Implements `Serialize` and `Deserialize` for [`Metadata`], [`MetadataValue`],
+//!   [`TrackID`], [`PlaylistID`], [`PlaybackStatus`] and [`LoopStatus`].
+//! * `test_util`: Adds the `test_util` module, with a `MockPlayer` and fixtures for testing code
+//!   that uses this crate.
+//! * `no_stats`: Stops counting [`TrackList::cache_stats`], which then always returns zeroes.
+//! * `track_list_index`: Makes [`TrackList::contains`] a set lookup instead of a scan of the list,
+//!   at the cost of storing every [`TrackID`] twice.
+//! * `xspf`: Adds `TrackList::to_xspf` and `TrackList::from_xspf`, which export and import
+//!   [XSPF](https://xspf.org/) playlists.
+//!
 
 use failure::Fail;
 
 mod extensions;
+#[cfg(test)]
+mod fake_player;
 
 #[allow(unreachable_pub)]
 mod generated;
 
+#[cfg(feature = "async")]
+mod async_player;
+mod compliance;
 mod event;
 mod find;
 mod metadata;
 mod player;
+mod player_interface;
+mod playlist;
+mod pool;
 mod pooled_connection;
 mod progress;
+mod properties;
+mod rate_limit;
+mod retry;
+mod shared_player;
+mod signal;
+#[cfg(any(test, feature = "test_util"))]
+pub mod test_util;
+mod timeout;
 mod track_list;
+mod volume;
+mod watcher;
+mod worker;
 
+#[cfg(feature = "async")]
+pub use crate::async_player::{AsyncPlayer, PlayerEventStream};
+pub use crate::compliance::{ComplianceChecker, ComplianceReport, ComplianceViolation, Severity};
 pub use crate::event::{Event, EventError, PlayerEvents};
-pub use crate::find::{FindingError, PlayerFinder};
-pub use crate::metadata::Metadata;
+pub use crate::extensions::{DurationHms, DurationNanos, ParseHmsError};
+pub use crate::find::{BusType, FindingError, PlayerFinder, PlayerFinderBuilder};
+#[cfg(feature = "json")]
+pub use crate::metadata::MetadataParseError;
 pub use crate::metadata::Value as MetadataValue;
 pub use crate::metadata::ValueKind as MetadataValueKind;
+pub use crate::metadata::{
+    FromMetadataValue, Metadata, MetadataBuilder, MetadataDiff, MetadataError, MetadataField,
+};
 pub use crate::player::Player;
+pub use crate::player_interface::PlayerInterface;
+pub use crate::playlist::{
+    InvalidPlaylistOrdering, MaybePlaylist, Playlist, PlaylistID, PlaylistOrdering,
+};
+pub use crate::pool::PlayerPool;
 pub use crate::progress::{Progress, ProgressError, ProgressTick, ProgressTracker};
-pub use crate::track_list::{TrackID, TrackList, TrackListError};
+pub use crate::properties::{
+    PlayerCapabilities, PlayerProperties, PlayerPropertiesDiff, PropertiesChangedSignal,
+    RootProperties,
+};
+pub use crate::rate_limit::RateLimitPolicy;
+pub use crate::retry::RetryPolicy;
+pub use crate::shared_player::SharedPlayer;
+pub use crate::signal::{parse_seeked_signal, EventDebouncer, EventIterator, PlayerEvent};
+pub use crate::timeout::TimeoutConfig;
+#[cfg(feature = "xspf")]
+pub use crate::track_list::XspfError;
+pub use crate::track_list::{
+    CacheStats, CachedMetadataIter, M3uParseError, MetadataIter, MetadataWithIdIter, TrackID,
+    TrackIDIter, TrackList, TrackListError, TrackListSnapshot,
+};
+pub use crate::volume::{Volume, VolumeError};
+pub use crate::watcher::{PlayerWatchEvent, PlayerWatcher};
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 #[allow(missing_docs)]
@@ -83,6 +151,33 @@ pub enum LoopStatus {
     Playlist,
 }
 
+impl PlaybackStatus {
+    /// Returns [`true`] if the status is [`Playing`](Self::Playing).
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        *self == PlaybackStatus::Playing
+    }
+
+    /// Returns [`true`] if the status is [`Paused`](Self::Paused).
+    #[inline]
+    pub fn is_paused(&self) -> bool {
+        *self == PlaybackStatus::Paused
+    }
+
+    /// Returns [`true`] if the status is [`Stopped`](Self::Stopped).
+    #[inline]
+    pub fn is_stopped(&self) -> bool {
+        *self == PlaybackStatus::Stopped
+    }
+
+    /// Returns [`true`] if there is a current track, e.g. the status is either
+    /// [`Playing`](Self::Playing) or [`Paused`](Self::Paused).
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        !self.is_stopped()
+    }
+}
+
 /// [`PlaybackStatus`] had an invalid string value.
 #[derive(Fail, Debug)]
 #[fail(
@@ -128,6 +223,26 @@ impl ::std::str::FromStr for LoopStatus {
 }
 
 impl LoopStatus {
+    /// Returns the status that comes after this one when cycling through them, like a loop button
+    /// would: `None` → `Track` → `Playlist` → `None`.
+    pub fn next(&self) -> LoopStatus {
+        match self {
+            LoopStatus::None => LoopStatus::Track,
+            LoopStatus::Track => LoopStatus::Playlist,
+            LoopStatus::Playlist => LoopStatus::None,
+        }
+    }
+
+    /// Returns the status that comes before this one when cycling through them; the reverse of
+    /// [`next`](Self::next).
+    pub fn prev(&self) -> LoopStatus {
+        match self {
+            LoopStatus::None => LoopStatus::Playlist,
+            LoopStatus::Track => LoopStatus::None,
+            LoopStatus::Playlist => LoopStatus::Track,
+        }
+    }
+
     fn dbus_value(self) -> String {
         String::from(match self {
             LoopStatus::None => "None",
@@ -137,6 +252,74 @@ impl LoopStatus {
     }
 }
 
+/// A [`PlaybackStatus`] is serialized as the lowercase name of its D-Bus value: `"playing"`,
+/// `"paused"` or `"stopped"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PlaybackStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(match self {
+            PlaybackStatus::Playing => "playing",
+            PlaybackStatus::Paused => "paused",
+            PlaybackStatus::Stopped => "stopped",
+        })
+    }
+}
+
+/// Deserializing fails for anything but the values that [`PlaybackStatus`] serializes to.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PlaybackStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const VARIANTS: &[&str] = &["playing", "paused", "stopped"];
+        let status = String::deserialize(deserializer)?;
+        match status.as_str() {
+            "playing" => Ok(PlaybackStatus::Playing),
+            "paused" => Ok(PlaybackStatus::Paused),
+            "stopped" => Ok(PlaybackStatus::Stopped),
+            other => Err(serde::de::Error::unknown_variant(other, VARIANTS)),
+        }
+    }
+}
+
+/// A [`LoopStatus`] is serialized as the lowercase name of its D-Bus value: `"none"`, `"track"`
+/// or `"playlist"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for LoopStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(match self {
+            LoopStatus::None => "none",
+            LoopStatus::Track => "track",
+            LoopStatus::Playlist => "playlist",
+        })
+    }
+}
+
+/// Deserializing fails for anything but the values that [`LoopStatus`] serializes to.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LoopStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const VARIANTS: &[&str] = &["none", "track", "playlist"];
+        let status = String::deserialize(deserializer)?;
+        match status.as_str() {
+            "none" => Ok(LoopStatus::None),
+            "track" => Ok(LoopStatus::Track),
+            "playlist" => Ok(LoopStatus::Playlist),
+            other => Err(serde::de::Error::unknown_variant(other, VARIANTS)),
+        }
+    }
+}
+
 /// Something went wrong when communicating with the D-Bus. This could either be an underlying
 /// D-Bus library problem, or that the other side did not conform to the expected protocols.
 #[derive(Fail, Debug)]
@@ -155,11 +338,94 @@ pub enum DBusError {
     #[fail(display = "D-Bus call failed: {}", _0)]
     TypeMismatchError(#[cause] dbus::arg::TypeMismatchError),
 
+    /// A D-Bus message was not the one that it was expected to be, like when parsing a signal from
+    /// a message of another signal.
+    #[fail(display = "Invalid message: {}", _0)]
+    InvalidMessage(String),
+
     /// Some other unexpected error occurred.
     #[fail(display = "Unexpected error: {}", _0)]
     Miscellaneous(String),
 }
 
+/// A category of [`DBusError`], based on the D-Bus error name.
+///
+/// See [`DBusError::kind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DBusErrorKind {
+    /// Nothing owns the bus name anymore, which usually means that the [`Player`] has quit.
+    ///
+    /// Matches `org.freedesktop.DBus.Error.ServiceUnknown` and
+    /// `org.freedesktop.DBus.Error.NameHasNoOwner`.
+    ServiceUnknown,
+
+    /// The [`Player`] did not reply. Matches `org.freedesktop.DBus.Error.NoReply`.
+    NoReply,
+
+    /// The call timed out. Matches `org.freedesktop.DBus.Error.Timeout` and
+    /// `org.freedesktop.DBus.Error.TimedOut`.
+    Timeout,
+
+    /// The [`Player`] rejected the arguments of the call. Matches
+    /// `org.freedesktop.DBus.Error.InvalidArgs`.
+    InvalidArgs,
+
+    /// The call was not allowed. Matches `org.freedesktop.DBus.Error.AccessDenied`.
+    AccessDenied,
+
+    /// The call failed for an unspecified reason. Matches `org.freedesktop.DBus.Error.Failed`.
+    Failed,
+
+    /// The [`Player`] replied, but with data of an unexpected type or value. This covers
+    /// [`DBusError::TypeMismatchError`] and [`DBusError::EnumParseError`].
+    InvalidReply,
+
+    /// Any other error. Holds the D-Bus error name, or the error message if the error did not have
+    /// a name.
+    Other(String),
+}
+
+impl DBusError {
+    /// Returns the [`DBusErrorKind`] of this error, so that errors can be handled without matching
+    /// on error messages.
+    ///
+    /// ```rust,no_run
+    /// # use mpris::{DBusErrorKind, PlayerFinder};
+    /// # let player = PlayerFinder::new().unwrap().find_active().unwrap();
+    /// match player.play() {
+    ///     Ok(()) => {}
+    ///     Err(error) if error.kind() == DBusErrorKind::ServiceUnknown => {
+    ///         println!("The player has quit");
+    ///     }
+    ///     Err(error) => println!("Could not play: {}", error),
+    /// }
+    /// ```
+    pub fn kind(&self) -> DBusErrorKind {
+        match self {
+            DBusError::TransportError(error) => match error.name() {
+                Some("org.freedesktop.DBus.Error.ServiceUnknown")
+                | Some("org.freedesktop.DBus.Error.NameHasNoOwner") => {
+                    DBusErrorKind::ServiceUnknown
+                }
+                Some("org.freedesktop.DBus.Error.NoReply") => DBusErrorKind::NoReply,
+                Some("org.freedesktop.DBus.Error.Timeout")
+                | Some("org.freedesktop.DBus.Error.TimedOut") => DBusErrorKind::Timeout,
+                Some("org.freedesktop.DBus.Error.InvalidArgs") => DBusErrorKind::InvalidArgs,
+                Some("org.freedesktop.DBus.Error.AccessDenied") => DBusErrorKind::AccessDenied,
+                Some("org.freedesktop.DBus.Error.Failed") => DBusErrorKind::Failed,
+                Some(name) => DBusErrorKind::Other(name.to_string()),
+                None => DBusErrorKind::Other(error.message().unwrap_or_default().to_string()),
+            },
+            DBusError::EnumParseError(_) | DBusError::TypeMismatchError(_) => {
+                DBusErrorKind::InvalidReply
+            }
+            DBusError::InvalidMessage(message) | DBusError::Miscellaneous(message) => {
+                DBusErrorKind::Other(message.clone())
+            }
+        }
+    }
+}
+
 impl From<dbus::Error> for DBusError {
     fn from(error: dbus::Error) -> Self {
         DBusError::TransportError(error)
@@ -183,3 +449,174 @@ impl From<InvalidLoopStatus> for DBusError {
         DBusError::EnumParseError(error.to_string())
     }
 }
+
+/// A playback rate could not be set.
+///
+/// See [`Player::set_playback_rate_in_range`].
+#[derive(Fail, Debug)]
+pub enum RateError {
+    /// Something went wrong with the D-Bus communication. See the [`DBusError`] type.
+    #[fail(display = "{}", _0)]
+    DBusError(#[cause] DBusError),
+
+    /// The rate is not within the range that the [`Player`] supports.
+    #[fail(
+        display = "Rate {} is outside of the supported range {}..={}",
+        rate, min, max
+    )]
+    OutOfRange {
+        /// The rejected rate.
+        rate: f64,
+        /// The player's `MinimumRate`.
+        min: f64,
+        /// The player's `MaximumRate`.
+        max: f64,
+    },
+}
+
+impl From<DBusError> for RateError {
+    fn from(error: DBusError) -> Self {
+        RateError::DBusError(error)
+    }
+}
+
+/// A URI could not be opened.
+///
+/// See [`Player::open_uri_checked`].
+#[derive(Fail, Debug)]
+pub enum OpenUriError {
+    /// Something went wrong with the D-Bus communication. See the [`DBusError`] type.
+    #[fail(display = "{}", _0)]
+    DBusError(#[cause] DBusError),
+
+    /// The [`Player`] does not support the scheme of the URI.
+    #[fail(display = "URI scheme {:?} is not supported by the player", _0)]
+    UnsupportedScheme(String),
+}
+
+impl From<DBusError> for OpenUriError {
+    fn from(error: DBusError) -> Self {
+        OpenUriError::DBusError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn it_has_playback_status_predicates() {
+        use crate::PlaybackStatus::*;
+
+        assert!(Playing.is_playing());
+        assert!(!Playing.is_paused());
+        assert!(!Playing.is_stopped());
+        assert!(Playing.is_active());
+
+        assert!(!Paused.is_playing());
+        assert!(Paused.is_paused());
+        assert!(!Paused.is_stopped());
+        assert!(Paused.is_active());
+
+        assert!(!Stopped.is_playing());
+        assert!(!Stopped.is_paused());
+        assert!(Stopped.is_stopped());
+        assert!(!Stopped.is_active());
+    }
+
+    #[test]
+    fn it_cycles_loop_statuses() {
+        use crate::LoopStatus;
+
+        assert_eq!(LoopStatus::None.next(), LoopStatus::Track);
+        assert_eq!(LoopStatus::Track.next(), LoopStatus::Playlist);
+        assert_eq!(LoopStatus::Playlist.next(), LoopStatus::None);
+
+        for status in &[LoopStatus::None, LoopStatus::Track, LoopStatus::Playlist] {
+            assert_eq!(status.next().next().next(), *status);
+            assert_eq!(status.next().prev(), *status);
+        }
+    }
+
+    #[test]
+    fn it_converts_loop_statuses_to_and_from_dbus_values() {
+        use crate::{DBusError, LoopStatus};
+
+        for (status, value) in &[
+            (LoopStatus::None, "None"),
+            (LoopStatus::Track, "Track"),
+            (LoopStatus::Playlist, "Playlist"),
+        ] {
+            assert_eq!(status.dbus_value(), *value);
+            assert_eq!(value.parse::<LoopStatus>().unwrap(), *status);
+        }
+
+        let error = DBusError::from("Repeat".parse::<LoopStatus>().unwrap_err());
+        match error {
+            DBusError::EnumParseError(message) => assert!(message.contains("Repeat")),
+            other => panic!("Unexpected error: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_round_trips_statuses_through_serde() {
+        use crate::{LoopStatus, PlaybackStatus};
+
+        for (status, json) in &[
+            (PlaybackStatus::Playing, "\"playing\""),
+            (PlaybackStatus::Paused, "\"paused\""),
+            (PlaybackStatus::Stopped, "\"stopped\""),
+        ] {
+            assert_eq!(serde_json::to_string(status).unwrap(), *json);
+            assert_eq!(
+                serde_json::from_str::<PlaybackStatus>(json).unwrap(),
+                *status
+            );
+        }
+
+        for (status, json) in &[
+            (LoopStatus::None, "\"none\""),
+            (LoopStatus::Track, "\"track\""),
+            (LoopStatus::Playlist, "\"playlist\""),
+        ] {
+            assert_eq!(serde_json::to_string(status).unwrap(), *json);
+            assert_eq!(serde_json::from_str::<LoopStatus>(json).unwrap(), *status);
+        }
+
+        assert!(serde_json::from_str::<PlaybackStatus>("\"Playing\"").is_err());
+        assert!(serde_json::from_str::<LoopStatus>("\"loop\"").is_err());
+    }
+
+    #[test]
+    fn it_categorizes_dbus_errors() {
+        use crate::{DBusError, DBusErrorKind};
+
+        fn kind_of(name: &str) -> DBusErrorKind {
+            DBusError::from(dbus::Error::new_custom(name, "message")).kind()
+        }
+
+        assert_eq!(
+            kind_of("org.freedesktop.DBus.Error.ServiceUnknown"),
+            DBusErrorKind::ServiceUnknown
+        );
+        assert_eq!(
+            kind_of("org.freedesktop.DBus.Error.NoReply"),
+            DBusErrorKind::NoReply
+        );
+        assert_eq!(
+            kind_of("org.freedesktop.DBus.Error.Timeout"),
+            DBusErrorKind::Timeout
+        );
+        assert_eq!(
+            kind_of("org.freedesktop.DBus.Error.InvalidArgs"),
+            DBusErrorKind::InvalidArgs
+        );
+        assert_eq!(
+            kind_of("com.example.Error.Custom"),
+            DBusErrorKind::Other(String::from("com.example.Error.Custom"))
+        );
+        assert_eq!(
+            DBusError::EnumParseError(String::from("Invalid")).kind(),
+            DBusErrorKind::InvalidReply
+        );
+    }
+}
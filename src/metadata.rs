@@ -1,10 +1,30 @@
+mod builder;
+mod csv;
+mod diff;
+mod field;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "chrono")]
+mod metadata_chrono;
 mod value;
-pub use self::value::{Value, ValueKind};
+pub use self::builder::MetadataBuilder;
+pub(crate) use self::csv::csv_row;
+pub use self::diff::MetadataDiff;
+pub use self::field::MetadataField;
+#[cfg(feature = "json")]
+pub use self::json::MetadataParseError;
+pub use self::value::{FromMetadataValue, Value, ValueKind};
 use super::TrackID;
+use crate::extensions::DurationHms;
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::FromIterator;
 use std::time::Duration;
 
+use failure::Fail;
+
 /// A structured representation of the [`Player`](crate::player::Player) metadata.
 ///
 /// * [Read more about the MPRIS2 `Metadata_Map` type.][metadata_map]
@@ -17,6 +37,20 @@ pub struct Metadata {
     values: HashMap<String, Value>,
 }
 
+/// A map of fields could not be turned into [`Metadata`].
+///
+/// See [`Metadata::from_map`].
+#[derive(Fail, Debug, Clone, PartialEq)]
+pub enum MetadataError {
+    /// There was no `mpris:trackid` field.
+    #[fail(display = "mpris:trackid is missing")]
+    MissingTrackID,
+
+    /// The `mpris:trackid` field was not a string with a valid D-Bus object path.
+    #[fail(display = "mpris:trackid is not a valid track ID: {:?}", _0)]
+    InvalidTrackID(Value),
+}
+
 impl Metadata {
     /// Create a new [`Metadata`] struct with a given `track_id`.
     ///
@@ -35,6 +69,46 @@ impl Metadata {
         Metadata { values }
     }
 
+    /// Create a [`Metadata`] from a map of field names (like `"xesam:title"`) to their values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no `mpris:trackid`, or if it is not a
+    /// [`MetadataValue::String`](Value::String) with a valid D-Bus object path. Use
+    /// [`from_map_unchecked`](Self::from_map_unchecked) to skip this check.
+    ///
+    /// ```rust
+    /// # use mpris::{Metadata, MetadataError, MetadataValue};
+    /// # use std::collections::HashMap;
+    /// let mut map = HashMap::new();
+    /// map.insert(String::from("xesam:title"), MetadataValue::from("Song"));
+    /// assert_eq!(
+    ///     Metadata::from_map(map.clone()),
+    ///     Err(MetadataError::MissingTrackID)
+    /// );
+    ///
+    /// map.insert(String::from("mpris:trackid"), MetadataValue::from("/track/1"));
+    /// assert_eq!(Metadata::from_map(map).unwrap().title(), Some("Song"));
+    /// ```
+    pub fn from_map(map: HashMap<String, Value>) -> Result<Self, MetadataError> {
+        match map.get("mpris:trackid") {
+            None => Err(MetadataError::MissingTrackID),
+            Some(Value::String(id)) if TrackID::new(id.as_str()).is_ok() => {
+                Ok(Metadata::from_map_unchecked(map))
+            }
+            Some(value) => Err(MetadataError::InvalidTrackID(value.clone())),
+        }
+    }
+
+    /// Create a [`Metadata`] from a map of field names to their values, without checking the
+    /// `mpris:trackid`.
+    ///
+    /// This is the same as converting the map with [`Into::into`](std::convert::Into::into) or
+    /// collecting the pairs into a [`Metadata`].
+    pub fn from_map_unchecked(map: HashMap<String, Value>) -> Self {
+        Metadata { values: map }
+    }
+
     /// Get a value from the metadata by key name.
     ///
     /// # Examples
@@ -51,6 +125,82 @@ impl Metadata {
         self.values.get(key)
     }
 
+    /// Get a value from the metadata by its [`MetadataField`].
+    ///
+    /// ```rust
+    /// # use mpris::{Metadata, MetadataField};
+    /// # let metadata = Metadata::new(String::from("1234"));
+    /// if let Some(composer) = metadata.get_field(MetadataField::Composer) {
+    ///     println!("Composed by: {:?}", composer);
+    /// }
+    /// ```
+    pub fn get_field(&self, field: MetadataField) -> Option<&Value> {
+        self.get(field.as_key())
+    }
+
+    /// Get a value from the metadata by key name, converted to `T`.
+    ///
+    /// Returns [`None`] if the key is missing or if the value cannot be converted to `T`. See
+    /// [`FromMetadataValue`] for the supported types.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::MetadataBuilder;
+    /// let metadata = MetadataBuilder::new("/path/1")
+    ///     .set_raw("vendor:playCount", 3)
+    ///     .build();
+    ///
+    /// assert_eq!(metadata.get_as::<i64>("vendor:playCount"), Some(3));
+    /// assert_eq!(metadata.get_as::<String>("vendor:playCount"), None);
+    /// ```
+    pub fn get_as<T: FromMetadataValue>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(T::from_metadata_value)
+    }
+
+    /// Copies every field from `other` that is not already present in this [`Metadata`].
+    ///
+    /// `mpris:trackid` is never copied, as that would make this [`Metadata`] describe another
+    /// track.
+    pub fn merge_from(&mut self, other: &Metadata) {
+        for (key, value) in other.iter() {
+            if key != "mpris:trackid" && !self.values.contains_key(key) {
+                self.values.insert(key.to_owned(), value.clone());
+            }
+        }
+    }
+
+    /// Copies every field from `other`, replacing fields that are already present in this
+    /// [`Metadata`].
+    ///
+    /// `mpris:trackid` is never copied, as that would make this [`Metadata`] describe another
+    /// track.
+    pub fn overwrite_from(&mut self, other: &Metadata) {
+        for (key, value) in other.iter() {
+            if key != "mpris:trackid" {
+                self.values.insert(key.to_owned(), value.clone());
+            }
+        }
+    }
+
+    /// Compares this [`Metadata`] with a newer snapshot and returns the fields that were added,
+    /// removed or changed in `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use mpris::MetadataBuilder;
+    /// let old = MetadataBuilder::new("/path/1").title("Old title").build();
+    /// let new = MetadataBuilder::new("/path/1").title("New title").build();
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.changed.len(), 1);
+    /// assert!(diff.changed.contains_key("xesam:title"));
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a Metadata) -> MetadataDiff<'a> {
+        MetadataDiff::new(self, other)
+    }
+
     /// The track ID.
     ///
     /// If the [`TrackID`] could not be parsed as a proper [`TrackID`], [`None`] will be returned.
@@ -59,9 +209,7 @@ impl Metadata {
     /// > A unique identity for this track within the context of an MPRIS object.
     ///
     pub fn track_id(&self) -> Option<TrackID> {
-        self.get("mpris:trackid")
-            .and_then(Value::as_str)
-            .and_then(|v| TrackID::new(v).ok())
+        self.get_as("mpris:trackid")
     }
 
     /// A list of artists of the album the track appears on.
@@ -89,6 +237,22 @@ impl Metadata {
         self.get("mpris:artUrl").and_then(Value::as_str)
     }
 
+    /// The artists of the track separated by `", "`, or `"Unknown Artist"` if the track has no
+    /// artists, for showing the track to a user.
+    ///
+    /// See [`Metadata::combined_artists`].
+    pub fn artist_or_unknown(&self) -> String {
+        self.combined_artists(", ")
+            .unwrap_or_else(|| String::from("Unknown Artist"))
+    }
+
+    /// The artists of the track, separated by `", "`.
+    ///
+    /// See [`Metadata::combined_artists`].
+    pub fn artist_string(&self) -> Option<String> {
+        self.combined_artists(", ")
+    }
+
     /// A list of artists of the track.
     ///
     /// Based on `xesam:artist`
@@ -100,23 +264,53 @@ impl Metadata {
     /// Based on `xesam:autoRating`
     /// > An automatically-generated rating, based on things such as how often it has been played.
     /// > This should be in the range 0.0 to 1.0.
+    ///
+    /// Ratings outside of that range are treated as missing.
     pub fn auto_rating(&self) -> Option<f64> {
-        self.get("xesam:autoRating").and_then(Value::as_f64)
+        self.rating("xesam:autoRating")
+    }
+
+    /// The artists of the track joined into a single string, like `"Alice, Bob, Carol"` for a
+    /// `separator` of `", "`.
+    ///
+    /// Returns [`None`] if the track has no artists.
+    pub fn combined_artists(&self, separator: &str) -> Option<String> {
+        self.artists()
+            .filter(|artists| !artists.is_empty())
+            .map(|artists| artists.join(separator))
+    }
+
+    /// A list of comments about the track.
+    ///
+    /// Based on `xesam:comment`
+    /// > A (list of) freeform comment(s).
+    pub fn comment(&self) -> Option<Vec<&str>> {
+        self.get("xesam:comment").and_then(Value::as_str_array)
+    }
+
+    /// A list of composers of the track.
+    ///
+    /// Based on `xesam:composer`
+    /// > The composer(s) of the track.
+    pub fn composer(&self) -> Option<Vec<&str>> {
+        self.get("xesam:composer").and_then(Value::as_str_array)
     }
 
     /// Based on `xesam:discNumber`
     /// > The disc number on the album that this track is from.
     pub fn disc_number(&self) -> Option<i32> {
-        self.get("xesam:discNumber").and_then(Value::as_i32)
+        self.get_as("xesam:discNumber")
     }
 
     /// The duration of the track, in microseconds
     ///
     /// Based on `mpris:length`
     /// > The duration of the track in microseconds.
+    ///
+    /// Negative lengths are treated as missing.
     pub fn length_in_microseconds(&self) -> Option<u64> {
         match self.get("mpris:length") {
-            Some(Value::I64(len)) => Some(*len as u64),
+            Some(Value::I64(len)) => u64::try_from(*len).ok(),
             Some(Value::U64(len)) => Some(*len),
             Some(_) => None,
             None => None,
@@ -131,6 +325,24 @@ impl Metadata {
         self.length_in_microseconds().map(Duration::from_micros_ext)
     }
 
+    /// The raw date and time that the track was created, as an ISO 8601 string.
+    ///
+    /// Based on `xesam:contentCreated`
+    /// > When the track was created. Usually only the year component will be useful.
+    ///
+    /// With the `chrono` feature, `Metadata::content_created` parses this into a `DateTime`.
+    pub fn content_created_str(&self) -> Option<&str> {
+        self.get("xesam:contentCreated").and_then(Value::as_str)
+    }
+
+    /// A list of lyricists of the track.
+    ///
+    /// Based on `xesam:lyricist`
+    /// > The lyricist(s) of the track.
+    pub fn lyricist(&self) -> Option<Vec<&str>> {
+        self.get("xesam:lyricist").and_then(Value::as_str_array)
+    }
+
     /// The name of the track.
     ///
     /// Based on `xesam:title`
@@ -139,12 +351,20 @@ impl Metadata {
         self.get("xesam:title").and_then(Value::as_str)
     }
 
+    /// The [`title`](Self::title) of the track, or its [`url`](Self::url) if it has no title, for
+    /// showing the track to a user.
+    ///
+    /// Returns [`None`] only if the track has neither.
+    pub fn title_or_url(&self) -> Option<&str> {
+        self.title().or_else(|| self.url())
+    }
+
     /// The track number on the disc of the album the track appears on.
     ///
     /// Based on `xesam:trackNumber`
     /// > The track number on the album disc.
     pub fn track_number(&self) -> Option<i32> {
-        self.get("xesam:trackNumber").and_then(Value::as_i32)
+        self.get_as("xesam:trackNumber")
     }
 
     /// A URL to the media being played.
@@ -155,6 +375,25 @@ impl Metadata {
         self.get("xesam:url").and_then(Value::as_str)
     }
 
+    /// Based on `xesam:useCount`
+    /// > The number of times the track has been played.
+    pub fn use_count(&self) -> Option<i32> {
+        self.get_as("xesam:useCount")
+    }
+
+    /// Based on `xesam:userRating`
+    /// > A user-specified rating. This should be in the range 0.0 to 1.0.
+    ///
+    /// Ratings outside of that range are treated as missing.
+    pub fn user_rating(&self) -> Option<f64> {
+        self.rating("xesam:userRating")
+    }
+
+    fn rating(&self, key: &str) -> Option<f64> {
+        self.get_as(key)
+            .filter(|rating: &f64| (0.0..=1.0).contains(rating))
+    }
+
     /// Returns an owned [`HashMap`] of borrowed values from this [`Metadata`]. Useful if you need a
     /// mutable hash but don't have ownership of [`Metadata`] or want to consume it.
     ///
@@ -163,6 +402,19 @@ impl Metadata {
         self.iter().collect()
     }
 
+    /// Returns the raw map of field names to values, including fields that no accessor covers,
+    /// like vendor extensions.
+    pub fn as_map(&self) -> &HashMap<String, Value> {
+        &self.values
+    }
+
+    /// Consumes the [`Metadata`] and returns the raw map of field names to values.
+    ///
+    /// This is the same as converting it with [`Into::into`](std::convert::Into::into).
+    pub fn into_map(self) -> HashMap<String, Value> {
+        self.values
+    }
+
     /// Iterate all metadata keys and values.
     pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
         self.values.iter().map(|(k, v)| (k.as_str(), v))
@@ -173,6 +425,18 @@ impl Metadata {
         self.values.keys().map(String::as_str)
     }
 
+    /// Iterate the names of all fields that are present, in no particular order.
+    ///
+    /// This is the same as [`keys`](Self::keys).
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.keys()
+    }
+
+    /// Returns [`true`] if the field is present, regardless of its value.
+    pub fn has_field(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
     /// Returns [`true`] if there is no metadata
     pub fn is_empty(&self) -> bool {
         self.values.is_empty()
@@ -203,6 +467,106 @@ impl From<HashMap<String, Value>> for Metadata {
     }
 }
 
+/// Collects field names and their values into a [`Metadata`], without checking the
+/// `mpris:trackid`; see [`Metadata::from_map`] for that.
+impl FromIterator<(String, Value)> for Metadata {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        Metadata {
+            values: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Two [`Metadata`] that both have a [`track_id`](Metadata::track_id) are equal when their track
+/// IDs are, even if they have different fields otherwise. Otherwise all fields must be equal.
+impl PartialEq for Metadata {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.track_id(), other.track_id()) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ => self.values == other.values,
+        }
+    }
+}
+
+/// Shows the common fields that are present, one per line, like:
+///
+/// ```text
+/// Title: My Song
+/// Artist: Alice, Bob
+/// Album: My Album
+/// Length: 3:45
+/// ```
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        if let Some(title) = self.title() {
+            lines.push(format!("Title: {}", title));
+        }
+        if let Some(artists) = self.artist_string() {
+            lines.push(format!("Artist: {}", artists));
+        }
+        if let Some(album) = self.album_name() {
+            lines.push(format!("Album: {}", album));
+        }
+        if let Some(track_number) = self.track_number() {
+            lines.push(format!("Track: {}", track_number));
+        }
+        if let Some(disc_number) = self.disc_number() {
+            lines.push(format!("Disc: {}", disc_number));
+        }
+        if let Some(length) = self.length() {
+            lines.push(format!("Length: {}", length.fmt_hms()));
+        }
+        if let Some(url) = self.url() {
+            lines.push(format!("URL: {}", url));
+        }
+
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+/// [`Metadata`] is serialized as a flat map of field names (like `"xesam:title"`) to their values,
+/// including any fields that do not have an accessor.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Metadata {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.values.serialize(serializer)
+    }
+}
+
+/// Fields that the MPRIS metadata guidelines define as 32-bit integers are converted back to
+/// `MetadataValue::I32`, so that accessors like [`Metadata::track_number`] keep working after a
+/// round-trip.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Metadata {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut values = HashMap::<String, Value>::deserialize(deserializer)?;
+        narrow_i32_fields(&mut values);
+        Ok(Metadata { values })
+    }
+}
+
+// Serialized integers lose their exact type, so convert the fields that the MPRIS metadata
+// guidelines define as 32-bit integers back.
+#[cfg(any(feature = "serde", feature = "json"))]
+fn narrow_i32_fields(values: &mut HashMap<String, Value>) {
+    const I32_FIELDS: &[&str] = &["xesam:discNumber", "xesam:trackNumber", "xesam:useCount"];
+
+    for field in I32_FIELDS {
+        if let Some(value) = values.get_mut(*field) {
+            if let Some(val) = value.as_i64().and_then(|val| i32::try_from(val).ok()) {
+                *value = Value::I32(val);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,4 +612,337 @@ mod tests {
             assert_eq!(val, Value::I32(42));
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_round_trips_through_serde() {
+        let mut input_hash: HashMap<String, Value> = HashMap::new();
+        input_hash.insert(String::from("mpris:trackid"), Value::from("/foo/1"));
+        input_hash.insert(String::from("mpris:length"), Value::I64(1_250_000));
+        input_hash.insert(String::from("xesam:title"), Value::from("Song"));
+        input_hash.insert(String::from("xesam:trackNumber"), Value::I32(3));
+        input_hash.insert(String::from("vendor:extra"), Value::Bool(true));
+        let metadata = Metadata::from(input_hash);
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        let parsed: Metadata = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.title(), metadata.title());
+        assert_eq!(parsed.track_id(), metadata.track_id());
+        assert_eq!(parsed.length(), Some(Duration::from_micros(1_250_000)));
+        assert_eq!(parsed.track_number(), Some(3));
+        assert_eq!(parsed.get("vendor:extra"), Some(&Value::Bool(true)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_serializes_to_a_flat_map() {
+        let metadata = Metadata::new("/foo/1");
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json, serde_json::json!({ "mpris:trackid": "/foo/1" }));
+    }
+
+    #[test]
+    fn it_merges_missing_fields() {
+        let mut metadata = MetadataBuilder::new("/foo").title("Mine").build();
+        let other = MetadataBuilder::new("/bar")
+            .title("Theirs")
+            .album("Album")
+            .build();
+
+        metadata.merge_from(&other);
+
+        assert_eq!(metadata.title(), Some("Mine"));
+        assert_eq!(metadata.album_name(), Some("Album"));
+        assert_eq!(metadata.track_id(), Some(TrackID::new("/foo").unwrap()));
+    }
+
+    #[test]
+    fn it_overwrites_fields() {
+        let mut metadata = MetadataBuilder::new("/foo").title("Mine").build();
+        let other = MetadataBuilder::new("/bar")
+            .title("Theirs")
+            .album("Album")
+            .build();
+
+        metadata.overwrite_from(&other);
+
+        assert_eq!(metadata.title(), Some("Theirs"));
+        assert_eq!(metadata.album_name(), Some("Album"));
+        assert_eq!(metadata.track_id(), Some(TrackID::new("/foo").unwrap()));
+    }
+
+    #[test]
+    fn it_lists_present_fields() {
+        let metadata = MetadataBuilder::new("/p").title("T").build();
+
+        assert!(metadata.field_names().any(|name| name == "xesam:title"));
+        assert!(metadata.has_field("xesam:title"));
+        assert!(!metadata.has_field("xesam:comment"));
+    }
+
+    #[test]
+    fn it_reads_common_fields() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("Song")
+            .album("Album")
+            .artists(vec!["A", "B"])
+            .length(Duration::from_millis(2500))
+            .set_raw("mpris:artUrl", "file:///cover.png")
+            .set_raw("xesam:trackNumber", 3)
+            .set_raw("xesam:discNumber", 2)
+            .build();
+
+        assert_eq!(metadata.title(), Some("Song"));
+        assert_eq!(metadata.album_name(), Some("Album"));
+        assert_eq!(metadata.artists(), Some(vec!["A", "B"]));
+        assert_eq!(metadata.length(), Some(Duration::from_millis(2500)));
+        assert_eq!(metadata.length_in_microseconds(), Some(2_500_000));
+        assert_eq!(metadata.art_url(), Some("file:///cover.png"));
+        assert_eq!(metadata.track_number(), Some(3));
+        assert_eq!(metadata.disc_number(), Some(2));
+
+        let blank = Metadata::new("/foo");
+        assert_eq!(blank.title(), None);
+        assert_eq!(blank.length(), None);
+        assert_eq!(blank.track_number(), None);
+    }
+
+    #[test]
+    fn it_combines_artists() {
+        let artists = |artists: Vec<&str>| MetadataBuilder::new("/foo").artists(artists).build();
+
+        assert_eq!(
+            artists(vec!["Alice"]).artist_string(),
+            Some(String::from("Alice"))
+        );
+        assert_eq!(
+            artists(vec!["Alice", "Bob", "Carol"]).artist_string(),
+            Some(String::from("Alice, Bob, Carol"))
+        );
+        assert_eq!(
+            artists(vec!["Alice", "Bob"]).combined_artists(" & "),
+            Some(String::from("Alice & Bob"))
+        );
+        assert_eq!(artists(vec![]).artist_string(), None);
+        assert_eq!(Metadata::new("/foo").combined_artists(", "), None);
+    }
+
+    #[test]
+    fn it_checks_the_track_id_of_maps() {
+        let map = |id: Value| {
+            let mut map = HashMap::new();
+            map.insert(String::from("mpris:trackid"), id);
+            map
+        };
+
+        assert!(Metadata::from_map(map(Value::from("/track/1"))).is_ok());
+        assert_eq!(
+            Metadata::from_map(map(Value::from("not a path"))),
+            Err(MetadataError::InvalidTrackID(Value::from("not a path")))
+        );
+        assert_eq!(
+            Metadata::from_map(map(Value::I32(1))),
+            Err(MetadataError::InvalidTrackID(Value::I32(1)))
+        );
+        assert_eq!(
+            Metadata::from_map(HashMap::new()),
+            Err(MetadataError::MissingTrackID)
+        );
+
+        let unchecked = Metadata::from_map_unchecked(map(Value::I32(1)));
+        assert_eq!(unchecked.track_id(), None);
+    }
+
+    #[test]
+    fn it_collects_pairs() {
+        let metadata: Metadata = vec![
+            (String::from("mpris:trackid"), Value::from("/track/1")),
+            (String::from("xesam:title"), Value::from("Song")),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(metadata.track_id(), Some(TrackID::new("/track/1").unwrap()));
+        assert_eq!(metadata.title(), Some("Song"));
+    }
+
+    #[test]
+    fn it_exposes_the_raw_map() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("Song")
+            .length(Duration::from_secs(2))
+            .set_raw("vendor:mood", "Happy")
+            .build();
+        assert_eq!(
+            metadata.as_map().get("vendor:mood"),
+            Some(&Value::String(String::from("Happy")))
+        );
+
+        let map = metadata.into_map();
+        assert_eq!(map.len(), 4);
+        assert_eq!(
+            map.get("mpris:trackid"),
+            Some(&Value::String(String::from("/foo")))
+        );
+        assert_eq!(
+            map.get("xesam:title"),
+            Some(&Value::String(String::from("Song")))
+        );
+        assert_eq!(map.get("mpris:length"), Some(&Value::I64(2_000_000)));
+    }
+
+    #[test]
+    fn it_falls_back_for_display() {
+        let titled = MetadataBuilder::new("/foo")
+            .title("Song")
+            .set_raw("xesam:url", "file:///song.mp3")
+            .build();
+        let untitled = MetadataBuilder::new("/foo")
+            .set_raw("xesam:url", "file:///song.mp3")
+            .build();
+        let blank = Metadata::new("/foo");
+
+        assert_eq!(titled.title_or_url(), Some("Song"));
+        assert_eq!(untitled.title_or_url(), Some("file:///song.mp3"));
+        assert_eq!(blank.title_or_url(), None);
+
+        let artists = MetadataBuilder::new("/foo")
+            .artists(vec!["Alice", "Bob"])
+            .build();
+        assert_eq!(artists.artist_or_unknown(), "Alice, Bob");
+        assert_eq!(blank.artist_or_unknown(), "Unknown Artist");
+    }
+
+    #[test]
+    fn it_gets_fields() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("Song")
+            .set_raw("vendor:mood", "Happy")
+            .build();
+
+        assert_eq!(
+            metadata.get_field(MetadataField::Title),
+            Some(&Value::from("Song"))
+        );
+        assert_eq!(
+            metadata.get_field(MetadataField::Custom(String::from("vendor:mood"))),
+            Some(&Value::from("Happy"))
+        );
+        assert_eq!(metadata.get_field(MetadataField::Album), None);
+    }
+
+    #[test]
+    fn it_compares_by_track_id() {
+        let cached = MetadataBuilder::new("/foo").title("Cached").build();
+        let signaled = MetadataBuilder::new("/foo").title("Signaled").build();
+        assert_eq!(cached, signaled);
+        assert_ne!(cached, Metadata::new("/bar"));
+    }
+
+    #[test]
+    fn it_compares_all_fields_without_track_id() {
+        let title = |title: &str| {
+            let mut values = HashMap::new();
+            values.insert(String::from("xesam:title"), Value::from(title));
+            Metadata::from(values)
+        };
+
+        assert_eq!(title("Song"), title("Song"));
+        assert_ne!(title("Song"), title("Other song"));
+        assert_ne!(
+            title("Song"),
+            MetadataBuilder::new("/foo").title("Song").build()
+        );
+    }
+
+    #[test]
+    fn it_displays_present_fields() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("My Song")
+            .artists(vec!["Alice"])
+            .length(Duration::from_secs(225))
+            .build();
+
+        assert_eq!(
+            format!("{}", metadata),
+            "Title: My Song\nArtist: Alice\nLength: 3:45"
+        );
+        assert_eq!(format!("{}", Metadata::new("/foo")), "");
+    }
+
+    fn strings(values: &[&str]) -> Value {
+        Value::Array(values.iter().map(|value| Value::from(*value)).collect())
+    }
+
+    #[test]
+    fn it_reads_people_comments_and_ratings() {
+        let metadata = MetadataBuilder::new("/foo")
+            .set_raw("xesam:lyricist", strings(&["Lyricist"]))
+            .set_raw("xesam:composer", strings(&["A", "B"]))
+            .set_raw("xesam:comment", strings(&["Nice"]))
+            .set_raw("xesam:useCount", Value::I32(12))
+            .set_raw("xesam:userRating", Value::F64(0.8))
+            .set_raw("xesam:autoRating", Value::F64(1.0))
+            .build();
+
+        assert_eq!(metadata.lyricist(), Some(vec!["Lyricist"]));
+        assert_eq!(metadata.composer(), Some(vec!["A", "B"]));
+        assert_eq!(metadata.comment(), Some(vec!["Nice"]));
+        assert_eq!(metadata.use_count(), Some(12));
+        assert_eq!(metadata.user_rating(), Some(0.8));
+        assert_eq!(metadata.auto_rating(), Some(1.0));
+    }
+
+    #[test]
+    fn it_ignores_ratings_out_of_range() {
+        let metadata = MetadataBuilder::new("/foo")
+            .set_raw("xesam:userRating", Value::F64(5.0))
+            .set_raw("xesam:autoRating", Value::F64(-0.1))
+            .build();
+
+        assert_eq!(metadata.user_rating(), None);
+        assert_eq!(metadata.auto_rating(), None);
+    }
+
+    #[test]
+    fn it_ignores_negative_lengths() {
+        let metadata = MetadataBuilder::new("/foo")
+            .set_raw("mpris:length", -1i64)
+            .build();
+
+        assert_eq!(metadata.length_in_microseconds(), None);
+        assert_eq!(metadata.length(), None);
+    }
+
+    #[test]
+    fn it_gets_converted_values() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("Song")
+            .artists(vec!["A", "B"])
+            .length(Duration::from_secs(3))
+            .set_raw("vendor:count", 7)
+            .set_raw("vendor:bool", true)
+            .build();
+
+        assert_eq!(metadata.get_as("xesam:title"), Some(String::from("Song")));
+        assert_eq!(
+            metadata.get_as("xesam:artist"),
+            Some(vec![String::from("A"), String::from("B")])
+        );
+        assert_eq!(
+            metadata.get_as("mpris:length"),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(metadata.get_as("vendor:count"), Some(7i32));
+        assert_eq!(metadata.get_as("vendor:count"), Some(7i64));
+        assert_eq!(metadata.get_as("vendor:bool"), Some(true));
+        assert_eq!(
+            metadata.get_as("mpris:trackid"),
+            Some(TrackID::new("/foo").unwrap())
+        );
+
+        assert_eq!(metadata.get_as::<f64>("vendor:count"), None);
+        assert_eq!(metadata.get_as::<i32>("vendor:missing"), None);
+    }
 }
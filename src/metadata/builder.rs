@@ -0,0 +1,122 @@
+use super::{Metadata, Value};
+use crate::extensions::DurationExtensions;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Builds [`Metadata`] values field by field.
+///
+/// This is mostly useful for test fixtures and other places where you want to work with mock
+/// data. Fields are stored under the same keys that the accessors on [`Metadata`] read.
+///
+/// # Examples
+///
+/// ```rust
+/// use mpris::MetadataBuilder;
+/// use std::time::Duration;
+///
+/// let metadata = MetadataBuilder::new("/path/1")
+///     .title("My Song")
+///     .album("My Album")
+///     .artists(vec!["Artist"])
+///     .length(Duration::from_secs(240))
+///     .build();
+///
+/// assert_eq!(metadata.title(), Some("My Song"));
+/// assert_eq!(metadata.length(), Some(Duration::from_secs(240)));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MetadataBuilder {
+    values: HashMap<String, Value>,
+}
+
+impl MetadataBuilder {
+    /// Create a new [`MetadataBuilder`] for a track with the given `track_id`.
+    pub fn new<S>(track_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        MetadataBuilder::default().set_raw("mpris:trackid", Value::String(track_id.into()))
+    }
+
+    /// Sets `xesam:title`, read by [`Metadata::title`].
+    pub fn title<S: Into<String>>(self, title: S) -> Self {
+        self.set_raw("xesam:title", Value::String(title.into()))
+    }
+
+    /// Sets `xesam:album`, read by [`Metadata::album_name`].
+    pub fn album<S: Into<String>>(self, album: S) -> Self {
+        self.set_raw("xesam:album", Value::String(album.into()))
+    }
+
+    /// Sets `xesam:artist`, read by [`Metadata::artists`].
+    pub fn artists<I, S>(self, artists: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let artists = artists
+            .into_iter()
+            .map(|artist| Value::String(artist.into()))
+            .collect();
+        self.set_raw("xesam:artist", Value::Array(artists))
+    }
+
+    /// Sets `mpris:length`, read by [`Metadata::length`].
+    pub fn length(self, length: Duration) -> Self {
+        self.set_raw("mpris:length", Value::I64(length.as_micros_i64()))
+    }
+
+    /// Sets any field, for example a vendor-specific one that does not have its own method.
+    pub fn set_raw<V: Into<Value>>(mut self, key: &str, value: V) -> Self {
+        self.values.insert(String::from(key), value.into());
+        self
+    }
+
+    /// Returns the built [`Metadata`].
+    pub fn build(self) -> Metadata {
+        Metadata::from(self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackID;
+
+    #[test]
+    fn it_builds_metadata_readable_by_accessors() {
+        let metadata = MetadataBuilder::new("/path/1")
+            .title("My Song")
+            .album("My Album")
+            .artists(vec!["Artist", "Other Artist"])
+            .length(Duration::from_millis(240_500))
+            .build();
+
+        assert_eq!(metadata.track_id(), Some(TrackID::new("/path/1").unwrap()));
+        assert_eq!(metadata.title(), Some("My Song"));
+        assert_eq!(metadata.album_name(), Some("My Album"));
+        assert_eq!(metadata.artists(), Some(vec!["Artist", "Other Artist"]));
+        assert_eq!(metadata.length(), Some(Duration::from_millis(240_500)));
+    }
+
+    #[test]
+    fn it_saturates_lengths_that_do_not_fit() {
+        let metadata = MetadataBuilder::new("/path/1")
+            .length(Duration::from_secs(u64::MAX))
+            .build();
+
+        assert_eq!(metadata.get("mpris:length"), Some(&Value::I64(i64::MAX)));
+    }
+
+    #[test]
+    fn it_sets_raw_fields() {
+        let metadata = MetadataBuilder::new("/path/1")
+            .set_raw("vendor:playCount", 3)
+            .set_raw("xesam:title", "Raw title")
+            .build();
+
+        assert_eq!(metadata.get("vendor:playCount"), Some(&Value::I32(3)));
+        assert_eq!(metadata.title(), Some("Raw title"));
+    }
+}
@@ -0,0 +1,101 @@
+use super::{Metadata, MetadataField, Value};
+
+impl Metadata {
+    /// Formats the given fields as a single row of CSV, without a line ending.
+    ///
+    /// Fields that are missing are left empty. Lists, like the artists, are joined with `", "`,
+    /// and values that contain a comma, a quote or a line break are quoted.
+    ///
+    /// ```rust
+    /// use mpris::{MetadataBuilder, MetadataField};
+    ///
+    /// let metadata = MetadataBuilder::new("/track/1")
+    ///     .title("Song")
+    ///     .artists(vec!["Alice", "Bob"])
+    ///     .build();
+    /// let fields = [MetadataField::Title, MetadataField::Artists, MetadataField::Album];
+    /// assert_eq!(metadata.to_csv_row(&fields), "Song,\"Alice, Bob\",");
+    /// ```
+    pub fn to_csv_row(&self, fields: &[MetadataField]) -> String {
+        csv_row(
+            fields
+                .iter()
+                .map(|field| self.get(field.as_key()).map(csv_value).unwrap_or_default()),
+        )
+    }
+}
+
+/// Joins the cells into a row of CSV, quoting the ones that need it.
+pub(crate) fn csv_row<I, S>(cells: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    cells
+        .into_iter()
+        .map(|cell| {
+            let cell = cell.as_ref();
+            if cell.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::I16(number) => number.to_string(),
+        Value::I32(number) => number.to_string(),
+        Value::I64(number) => number.to_string(),
+        Value::U8(number) => number.to_string(),
+        Value::U16(number) => number.to_string(),
+        Value::U32(number) => number.to_string(),
+        Value::U64(number) => number.to_string(),
+        Value::F64(number) => number.to_string(),
+        Value::Bool(boolean) => boolean.to_string(),
+        Value::Array(values) => values.iter().map(csv_value).collect::<Vec<_>>().join(", "),
+        Value::Map(_) | Value::Unsupported => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataBuilder;
+
+    #[test]
+    fn it_quotes_cells_that_need_it() {
+        assert_eq!(
+            csv_row(vec!["a", "b,c", "say \"hi\"", "two\nlines"]),
+            "a,\"b,c\",\"say \"\"hi\"\"\",\"two\nlines\""
+        );
+    }
+
+    #[test]
+    fn it_formats_values() {
+        let metadata = MetadataBuilder::new("/track/1")
+            .set_raw("xesam:trackNumber", 3)
+            .set_raw("xesam:userRating", 0.5)
+            .set_raw(
+                "xesam:genre",
+                Value::Array(vec![Value::from("Rock"), Value::from("Pop")]),
+            )
+            .build();
+
+        assert_eq!(
+            metadata.to_csv_row(&[
+                MetadataField::TrackId,
+                MetadataField::TrackNumber,
+                MetadataField::UserRating,
+                MetadataField::Genre,
+                MetadataField::Title,
+            ]),
+            "/track/1,3,0.5,\"Rock, Pop\","
+        );
+        assert_eq!(metadata.to_csv_row(&[]), "");
+    }
+}
@@ -0,0 +1,123 @@
+use super::{Metadata, Value};
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// The differences between two [`Metadata`] snapshots.
+///
+/// See [`Metadata::diff`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MetadataDiff<'a> {
+    /// Fields that are only present in the newer [`Metadata`], with their values.
+    pub added: HashMap<&'a str, &'a Value>,
+
+    /// Fields that are only present in the older [`Metadata`], with their old values.
+    pub removed: HashMap<&'a str, &'a Value>,
+
+    /// Fields that are present in both, but with different values. The values are `(old, new)`.
+    pub changed: HashMap<&'a str, (&'a Value, &'a Value)>,
+}
+
+impl<'a> MetadataDiff<'a> {
+    pub(crate) fn new(old: &'a Metadata, new: &'a Metadata) -> Self {
+        let mut diff = MetadataDiff::default();
+
+        for (key, old_value) in old.iter() {
+            match new.get(key) {
+                Some(new_value) if new_value != old_value => {
+                    diff.changed.insert(key, (old_value, new_value));
+                }
+                Some(_) => {}
+                None => {
+                    diff.removed.insert(key, old_value);
+                }
+            }
+        }
+
+        for (key, new_value) in new.iter() {
+            if old.get(key).is_none() {
+                diff.added.insert(key, new_value);
+            }
+        }
+
+        diff
+    }
+
+    /// Returns true if both [`Metadata`] snapshots had the same fields and values.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Writes one line per field, sorted by field name. Added fields are prefixed by `+`, removed
+/// fields by `-` and changed fields by `~`.
+impl<'a> fmt::Display for MetadataDiff<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines: Vec<(&str, String)> = Vec::new();
+        lines.extend(
+            self.added
+                .iter()
+                .map(|(key, value)| (*key, format!("+ {}: {:?}", key, value))),
+        );
+        lines.extend(
+            self.removed
+                .iter()
+                .map(|(key, value)| (*key, format!("- {}: {:?}", key, value))),
+        );
+        lines.extend(
+            self.changed
+                .iter()
+                .map(|(key, (old, new))| (*key, format!("~ {}: {:?} -> {:?}", key, old, new))),
+        );
+        lines.sort();
+
+        for (_, line) in lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataBuilder;
+
+    #[test]
+    fn it_finds_changed_fields() {
+        let old = MetadataBuilder::new("/path/1").title("Old").build();
+        let new = MetadataBuilder::new("/path/1").title("New").build();
+
+        let diff = old.diff(&new);
+
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed.get("xesam:title"),
+            Some(&(&Value::from("Old"), &Value::from("New")))
+        );
+    }
+
+    #[test]
+    fn it_finds_added_and_removed_fields() {
+        let old = MetadataBuilder::new("/path/1").title("Song").build();
+        let new = MetadataBuilder::new("/path/1").album("Album").build();
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.added.get("xesam:album"), Some(&&Value::from("Album")));
+        assert_eq!(diff.removed.get("xesam:title"), Some(&&Value::from("Song")));
+        assert!(diff.changed.is_empty());
+        assert_eq!(
+            diff.to_string(),
+            "+ xesam:album: String(\"Album\")\n- xesam:title: String(\"Song\")\n"
+        );
+    }
+
+    #[test]
+    fn it_has_empty_diffs_for_equal_metadata() {
+        let metadata = MetadataBuilder::new("/path/1").title("Song").build();
+        assert!(metadata.diff(&metadata.clone()).is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+
+/// A field of [`Metadata`](crate::Metadata), for use with
+/// [`Metadata::get_field`](crate::Metadata::get_field) instead of its key as a string.
+///
+/// ```rust
+/// use mpris::MetadataField;
+///
+/// assert_eq!(MetadataField::Title.as_key(), "xesam:title");
+/// assert_eq!(MetadataField::from("xesam:title"), MetadataField::Title);
+/// assert_eq!(
+///     MetadataField::from("vendor:custom"),
+///     MetadataField::Custom(String::from("vendor:custom"))
+/// );
+/// ```
+///
+/// See: [MPRIS v2 metadata guidelines][metadata_guidelines]
+///
+/// [metadata_guidelines]: https://www.freedesktop.org/wiki/Specifications/mpris-spec/metadata/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MetadataField {
+    /// `mpris:trackid`
+    TrackId,
+    /// `mpris:length`
+    Length,
+    /// `mpris:artUrl`
+    ArtUrl,
+    /// `xesam:album`
+    Album,
+    /// `xesam:albumArtist`
+    AlbumArtists,
+    /// `xesam:artist`
+    Artists,
+    /// `xesam:autoRating`
+    AutoRating,
+    /// `xesam:comment`
+    Comment,
+    /// `xesam:composer`
+    Composer,
+    /// `xesam:contentCreated`
+    ContentCreated,
+    /// `xesam:discNumber`
+    DiscNumber,
+    /// `xesam:genre`
+    Genre,
+    /// `xesam:lyricist`
+    Lyricist,
+    /// `xesam:title`
+    Title,
+    /// `xesam:trackNumber`
+    TrackNumber,
+    /// `xesam:url`
+    Url,
+    /// `xesam:useCount`
+    UseCount,
+    /// `xesam:userRating`
+    UserRating,
+    /// Any other field, like the ones that some players add under their own prefix.
+    Custom(String),
+}
+
+impl MetadataField {
+    /// The key of the field in the metadata map, like `xesam:title`.
+    pub fn as_key(&self) -> &str {
+        match *self {
+            MetadataField::TrackId => "mpris:trackid",
+            MetadataField::Length => "mpris:length",
+            MetadataField::ArtUrl => "mpris:artUrl",
+            MetadataField::Album => "xesam:album",
+            MetadataField::AlbumArtists => "xesam:albumArtist",
+            MetadataField::Artists => "xesam:artist",
+            MetadataField::AutoRating => "xesam:autoRating",
+            MetadataField::Comment => "xesam:comment",
+            MetadataField::Composer => "xesam:composer",
+            MetadataField::ContentCreated => "xesam:contentCreated",
+            MetadataField::DiscNumber => "xesam:discNumber",
+            MetadataField::Genre => "xesam:genre",
+            MetadataField::Lyricist => "xesam:lyricist",
+            MetadataField::Title => "xesam:title",
+            MetadataField::TrackNumber => "xesam:trackNumber",
+            MetadataField::Url => "xesam:url",
+            MetadataField::UseCount => "xesam:useCount",
+            MetadataField::UserRating => "xesam:userRating",
+            MetadataField::Custom(ref key) => key,
+        }
+    }
+}
+
+/// Keys of known fields become their own variant, and all other keys become
+/// [`MetadataField::Custom`].
+impl<'a> From<&'a str> for MetadataField {
+    fn from(key: &'a str) -> Self {
+        match key {
+            "mpris:trackid" => MetadataField::TrackId,
+            "mpris:length" => MetadataField::Length,
+            "mpris:artUrl" => MetadataField::ArtUrl,
+            "xesam:album" => MetadataField::Album,
+            "xesam:albumArtist" => MetadataField::AlbumArtists,
+            "xesam:artist" => MetadataField::Artists,
+            "xesam:autoRating" => MetadataField::AutoRating,
+            "xesam:comment" => MetadataField::Comment,
+            "xesam:composer" => MetadataField::Composer,
+            "xesam:contentCreated" => MetadataField::ContentCreated,
+            "xesam:discNumber" => MetadataField::DiscNumber,
+            "xesam:genre" => MetadataField::Genre,
+            "xesam:lyricist" => MetadataField::Lyricist,
+            "xesam:title" => MetadataField::Title,
+            "xesam:trackNumber" => MetadataField::TrackNumber,
+            "xesam:url" => MetadataField::Url,
+            "xesam:useCount" => MetadataField::UseCount,
+            "xesam:userRating" => MetadataField::UserRating,
+            other => MetadataField::Custom(String::from(other)),
+        }
+    }
+}
+
+impl fmt::Display for MetadataField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_known_keys() {
+        for field in &[
+            MetadataField::TrackId,
+            MetadataField::Length,
+            MetadataField::ArtUrl,
+            MetadataField::Album,
+            MetadataField::AlbumArtists,
+            MetadataField::Artists,
+            MetadataField::AutoRating,
+            MetadataField::Comment,
+            MetadataField::Composer,
+            MetadataField::ContentCreated,
+            MetadataField::DiscNumber,
+            MetadataField::Genre,
+            MetadataField::Lyricist,
+            MetadataField::Title,
+            MetadataField::TrackNumber,
+            MetadataField::Url,
+            MetadataField::UseCount,
+            MetadataField::UserRating,
+        ] {
+            assert_eq!(MetadataField::from(field.as_key()), *field);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use failure::Fail;
+use serde_json::{Map, Number, Value as JsonValue};
+
+use std::collections::HashMap;
+
+use super::{Metadata, Value};
+use crate::TrackID;
+
+/// A [`serde_json::Value`] could not be converted into [`Metadata`].
+///
+/// See [`Metadata::from_json_value`].
+#[derive(Fail, Debug)]
+pub enum MetadataParseError {
+    /// The value was not a JSON object.
+    #[fail(display = "Expected a JSON object of metadata fields, but got {}", _0)]
+    NotAnObject(JsonValue),
+
+    /// The `mpris:trackid` field was not a string with a valid D-Bus object path.
+    #[fail(display = "mpris:trackid is not a valid track ID: {}", _0)]
+    InvalidTrackID(JsonValue),
+}
+
+impl Metadata {
+    /// Converts the metadata into a JSON object of field names (like `"xesam:title"`) to their
+    /// values, including any fields that do not have an accessor.
+    ///
+    /// Integers become numbers, so `mpris:length` stays a number of microseconds, and the track ID
+    /// becomes a string with its path. Unsupported values and floats that JSON cannot represent
+    /// become `null`.
+    ///
+    /// Only available with the `json` feature.
+    pub fn to_json_value(&self) -> JsonValue {
+        JsonValue::Object(
+            self.values
+                .iter()
+                .map(|(key, value)| (key.clone(), to_json(value)))
+                .collect(),
+        )
+    }
+
+    /// Converts a JSON object like the one from [`Metadata::to_json_value`] back into
+    /// [`Metadata`].
+    ///
+    /// JSON does not keep the exact type of integers, so they become `MetadataValue::I64`, or
+    /// `MetadataValue::U64` if they are too large for it. Fields that the MPRIS metadata
+    /// guidelines define as 32-bit integers, like `xesam:trackNumber`, become
+    /// `MetadataValue::I32` again. `null` becomes `MetadataValue::Unsupported`.
+    ///
+    /// Only available with the `json` feature.
+    pub fn from_json_value(value: JsonValue) -> Result<Metadata, MetadataParseError> {
+        let object = match value {
+            JsonValue::Object(object) => object,
+            other => return Err(MetadataParseError::NotAnObject(other)),
+        };
+
+        if let Some(track_id) = object.get("mpris:trackid") {
+            let valid = track_id
+                .as_str()
+                .is_some_and(|track_id| TrackID::new(track_id).is_ok());
+            if !valid {
+                return Err(MetadataParseError::InvalidTrackID(track_id.clone()));
+            }
+        }
+
+        let mut values: HashMap<String, Value> = object
+            .into_iter()
+            .map(|(key, value)| (key, from_json(value)))
+            .collect();
+        super::narrow_i32_fields(&mut values);
+        Ok(Metadata { values })
+    }
+}
+
+fn to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::String(val) => JsonValue::String(val.clone()),
+        Value::I16(val) => JsonValue::from(*val),
+        Value::I32(val) => JsonValue::from(*val),
+        Value::I64(val) => JsonValue::from(*val),
+        Value::U8(val) => JsonValue::from(*val),
+        Value::U16(val) => JsonValue::from(*val),
+        Value::U32(val) => JsonValue::from(*val),
+        Value::U64(val) => JsonValue::from(*val),
+        Value::F64(val) => Number::from_f64(*val).map_or(JsonValue::Null, JsonValue::Number),
+        Value::Bool(val) => JsonValue::Bool(*val),
+        Value::Array(val) => JsonValue::Array(val.iter().map(to_json).collect()),
+        Value::Map(val) => JsonValue::Object(
+            val.iter()
+                .map(|(key, value)| (key.clone(), to_json(value)))
+                .collect::<Map<String, JsonValue>>(),
+        ),
+        Value::Unsupported => JsonValue::Null,
+    }
+}
+
+fn from_json(value: JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::Unsupported,
+        JsonValue::Bool(val) => Value::Bool(val),
+        JsonValue::Number(val) => {
+            if let Some(val) = val.as_i64() {
+                Value::I64(val)
+            } else if let Some(val) = val.as_u64() {
+                Value::U64(val)
+            } else {
+                Value::F64(val.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        JsonValue::String(val) => Value::String(val),
+        JsonValue::Array(val) => Value::Array(val.into_iter().map(from_json).collect()),
+        JsonValue::Object(val) => Value::Map(
+            val.into_iter()
+                .map(|(key, value)| (key, from_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataBuilder;
+    use std::time::Duration;
+
+    #[test]
+    fn it_converts_to_json() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("Song")
+            .artists(vec!["A", "B"])
+            .length(Duration::from_secs(3))
+            .set_raw("xesam:trackNumber", 3)
+            .build();
+
+        assert_eq!(
+            metadata.to_json_value(),
+            serde_json::json!({
+                "mpris:trackid": "/foo",
+                "mpris:length": 3_000_000,
+                "xesam:title": "Song",
+                "xesam:artist": ["A", "B"],
+                "xesam:trackNumber": 3,
+            })
+        );
+    }
+
+    #[test]
+    fn it_round_trips_through_json() {
+        let metadata = MetadataBuilder::new("/foo")
+            .title("Song")
+            .length(Duration::from_secs(3))
+            .set_raw("xesam:trackNumber", 3)
+            .set_raw("xesam:userRating", 0.5)
+            .build();
+
+        let parsed = Metadata::from_json_value(metadata.to_json_value()).unwrap();
+
+        assert_eq!(parsed.track_id(), metadata.track_id());
+        assert_eq!(parsed.title(), Some("Song"));
+        assert_eq!(parsed.length(), Some(Duration::from_secs(3)));
+        assert_eq!(parsed.track_number(), Some(3));
+        assert_eq!(parsed.user_rating(), Some(0.5));
+    }
+
+    #[test]
+    fn it_rejects_invalid_json() {
+        assert!(Metadata::from_json_value(serde_json::json!(["/foo"])).is_err());
+        assert!(Metadata::from_json_value(serde_json::json!({"mpris:trackid": 3})).is_err());
+        assert!(
+            Metadata::from_json_value(serde_json::json!({"mpris:trackid": "not a path"})).is_err()
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+
+use super::Metadata;
+
+impl Metadata {
+    /// The date and time that the track was created.
+    ///
+    /// Based on `xesam:contentCreated`
+    /// > When the track was created. Usually only the year component will be useful.
+    ///
+    /// Returns [`None`] when the value is missing or is not an ISO 8601 date and time with an
+    /// offset, like `2023-04-15T12:00:00Z`. Use [`Metadata::content_created_str`] to read such
+    /// values yourself.
+    ///
+    /// Only available with the `chrono` feature.
+    pub fn content_created(&self) -> Option<DateTime<Utc>> {
+        let created = self.content_created_str()?;
+        DateTime::parse_from_rfc3339(created)
+            .ok()
+            .map(|created| created.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataBuilder;
+    use chrono::TimeZone;
+
+    fn created(value: &str) -> Metadata {
+        MetadataBuilder::new("/foo")
+            .set_raw("xesam:contentCreated", value)
+            .build()
+    }
+
+    #[test]
+    fn it_parses_content_created() {
+        assert_eq!(
+            created("2023-04-15T12:00:00Z").content_created(),
+            Some(Utc.with_ymd_and_hms(2023, 4, 15, 12, 0, 0).unwrap())
+        );
+        assert_eq!(
+            created("2023-04-15T14:00:00+02:00").content_created(),
+            Some(Utc.with_ymd_and_hms(2023, 4, 15, 12, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn it_ignores_invalid_content_created() {
+        let metadata = created("sometime in 2023");
+
+        assert_eq!(metadata.content_created(), None);
+        assert_eq!(metadata.content_created_str(), Some("sometime in 2023"));
+        assert_eq!(Metadata::new("/foo").content_created(), None);
+    }
+}
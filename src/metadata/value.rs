@@ -3,6 +3,11 @@ use derive_is_enum_variant::is_enum_variant;
 use enum_kinds::EnumKind;
 use from_variants::FromVariants;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use crate::extensions::DurationExtensions;
+use crate::TrackID;
 
 /// Holds a dynamically-typed metadata value.
 ///
@@ -330,6 +335,72 @@ impl Value {
     }
 }
 
+/// Types that can be converted from a [`Value`], used by [`Metadata::get_as`](crate::Metadata::get_as).
+///
+/// The conversions follow the `as_*` methods on [`Value`], so integers can be widened but never
+/// truncated.
+pub trait FromMetadataValue: Sized {
+    /// Converts the value, or returns [`None`] if it has an incompatible type.
+    fn from_metadata_value(value: &Value) -> Option<Self>;
+}
+
+impl FromMetadataValue for String {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value.as_string().cloned()
+    }
+}
+
+impl FromMetadataValue for i32 {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value.as_i32()
+    }
+}
+
+impl FromMetadataValue for i64 {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value.as_i64()
+    }
+}
+
+impl FromMetadataValue for f64 {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value.as_f64()
+    }
+}
+
+impl FromMetadataValue for bool {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value.as_bool()
+    }
+}
+
+/// Reads the value as a number of microseconds, like `mpris:length`. Negative values are not
+/// valid durations.
+impl FromMetadataValue for Duration {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value
+            .as_u64()
+            .or_else(|| value.as_i64().and_then(|val| u64::try_from(val).ok()))
+            .map(Duration::from_micros_ext)
+    }
+}
+
+/// Any elements of the array that are not strings are ignored, like in
+/// [`Value::as_str_array`].
+impl FromMetadataValue for Vec<String> {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value
+            .as_str_array()
+            .map(|strings| strings.into_iter().map(String::from).collect())
+    }
+}
+
+impl FromMetadataValue for TrackID {
+    fn from_metadata_value(value: &Value) -> Option<Self> {
+        value.as_str().and_then(|val| TrackID::new(val).ok())
+    }
+}
+
 impl<'a> From<&'a str> for Value {
     fn from(string: &'a str) -> Value {
         Value::String(String::from(string))
@@ -380,6 +451,115 @@ impl<'a> dbus::arg::Get<'a> for Value {
     }
 }
 
+/// Values are serialized as their plain counterparts, so a `Value::Map` becomes a map of values
+/// and `Value::Unsupported` becomes a unit/null value. The exact integer type is not part of the
+/// serialized form.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::String(val) => serializer.serialize_str(val),
+            Value::I16(val) => serializer.serialize_i16(*val),
+            Value::I32(val) => serializer.serialize_i32(*val),
+            Value::I64(val) => serializer.serialize_i64(*val),
+            Value::U8(val) => serializer.serialize_u8(*val),
+            Value::U16(val) => serializer.serialize_u16(*val),
+            Value::U32(val) => serializer.serialize_u32(*val),
+            Value::U64(val) => serializer.serialize_u64(*val),
+            Value::F64(val) => serializer.serialize_f64(*val),
+            Value::Bool(val) => serializer.serialize_bool(*val),
+            Value::Array(val) => serializer.collect_seq(val),
+            Value::Map(val) => serializer.collect_map(val),
+            Value::Unsupported => serializer.serialize_unit(),
+        }
+    }
+}
+
+/// As the serialized form does not contain the exact integer type, integers are deserialized as
+/// `Value::I64`, or as `Value::U64` if they are too large for an `i64`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{MapAccess, SeqAccess, Visitor};
+        use std::convert::TryFrom;
+        use std::fmt;
+
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a metadata value")
+            }
+
+            fn visit_bool<E>(self, val: bool) -> Result<Value, E> {
+                Ok(Value::Bool(val))
+            }
+
+            fn visit_i64<E>(self, val: i64) -> Result<Value, E> {
+                Ok(Value::I64(val))
+            }
+
+            fn visit_u64<E>(self, val: u64) -> Result<Value, E> {
+                Ok(i64::try_from(val)
+                    .map(Value::I64)
+                    .unwrap_or(Value::U64(val)))
+            }
+
+            fn visit_f64<E>(self, val: f64) -> Result<Value, E> {
+                Ok(Value::F64(val))
+            }
+
+            fn visit_str<E>(self, val: &str) -> Result<Value, E> {
+                Ok(Value::from(val))
+            }
+
+            fn visit_string<E>(self, val: String) -> Result<Value, E> {
+                Ok(Value::String(val))
+            }
+
+            fn visit_unit<E>(self) -> Result<Value, E> {
+                Ok(Value::Unsupported)
+            }
+
+            fn visit_none<E>(self) -> Result<Value, E> {
+                Ok(Value::Unsupported)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(Value::Array(values))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut values = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some((key, value)) = map.next_entry()? {
+                    values.insert(key, value);
+                }
+                Ok(Value::Map(values))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
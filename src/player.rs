@@ -1,19 +1,32 @@
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::ops::Range;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, Range};
 use std::rc::Rc;
 use std::time::Duration;
 
+use dbus::arg::{AppendAll, IterAppend, RefArg};
 use dbus::ffidisp::{ConnPath, Connection};
-use dbus::strings::{BusName, Path};
+use dbus::strings::{BusName, Interface, Member, Path};
+use dbus::Message;
 
-use super::{DBusError, LoopStatus, MetadataValue, PlaybackStatus, TrackID, TrackList};
+use super::{
+    DBusError, LoopStatus, MetadataValue, OpenUriError, PlaybackStatus, RateError, RateLimitPolicy,
+    RetryPolicy, TrackID, TrackList, Volume,
+};
 use crate::event::PlayerEvents;
 use crate::extensions::DurationExtensions;
 use crate::generated::OrgMprisMediaPlayer2;
 use crate::generated::OrgMprisMediaPlayer2Player;
 use crate::metadata::Metadata;
-use crate::pooled_connection::{MprisEvent, PooledConnection};
-use crate::progress::ProgressTracker;
+use crate::playlist::{MaybePlaylist, Playlist, PlaylistID, PlaylistOrdering};
+use crate::pooled_connection::{MprisEvent, PooledConnection, SharedConnection};
+use crate::progress::{Progress, ProgressTracker};
+use crate::properties::{PlayerCapabilities, PlayerProperties, RootProperties};
+use crate::rate_limit::RateLimiter;
+use crate::signal::{EventHistory, EventIterator, PlayerEvent};
+use crate::timeout::{self, TimeoutConfig};
 
 pub(crate) const MPRIS2_PREFIX: &str = "org.mpris.MediaPlayer2.";
 pub(crate) const MPRIS2_PATH: &str = "/org/mpris/MediaPlayer2";
@@ -21,6 +34,10 @@ pub(crate) const MPRIS2_PATH: &str = "/org/mpris/MediaPlayer2";
 /// When D-Bus connection is managed for you, use this timeout while communicating with a Player.
 pub(crate) const DEFAULT_TIMEOUT_MS: i32 = 500; // ms
 
+/// The number of threads, and so connections, that load chunks of track metadata at once.
+#[cfg(feature = "parallel")]
+const METADATA_WORKERS: usize = 4;
+
 /// A MPRIS-compatible player.
 ///
 /// You can query this player about the currently playing media, or control it.
@@ -30,13 +47,35 @@ pub(crate) const DEFAULT_TIMEOUT_MS: i32 = 500; // ms
 /// [spec]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html
 #[derive(Debug)]
 pub struct Player<'a> {
-    connection: Rc<PooledConnection>,
+    // Replaced when reconnecting; see `reconnect` and `with_auto_reconnect`.
+    connection: RefCell<Rc<PooledConnection>>,
     bus_name: BusName<'a>,
     unique_name: String,
     identity: String,
     path: Path<'a>,
-    timeout_ms: i32,
+    timeouts: Timeouts,
     has_tracklist_interface: bool,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    auto_reconnect_attempts: u32,
+    gone: Cell<bool>,
+    event_history: Option<Rc<RefCell<EventHistory>>>,
+}
+
+// The kinds of calls that have their own timeout in a `TimeoutConfig`.
+#[derive(Debug, Clone, Copy)]
+enum Call {
+    Default,
+    GetTracksMetadata,
+    Seek,
+}
+
+// A single timeout in milliseconds is kept as-is, so that a negative one still goes to libdbus
+// and is returned by `dbus_timeout_ms`.
+#[derive(Debug, Clone, Copy)]
+enum Timeouts {
+    Millis(i32),
+    Config(TimeoutConfig),
 }
 
 impl<'a> Player<'a> {
@@ -88,13 +127,18 @@ impl<'a> Player<'a> {
         };
 
         Ok(Player {
-            connection: pooled_connection,
+            connection: RefCell::new(pooled_connection),
             bus_name,
             unique_name,
             identity,
             path,
-            timeout_ms,
+            timeouts: Timeouts::Millis(timeout_ms),
             has_tracklist_interface,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+            auto_reconnect_attempts: 0,
+            gone: Cell::new(false),
+            event_history: None,
         })
     }
 
@@ -103,14 +147,145 @@ impl<'a> Player<'a> {
     /// When querying D-Bus the call should not block longer than this, and will instead fail the
     /// query if no response has been received in this time.
     ///
-    /// You can change this using [`set_dbus_timeout_ms`](Self::set_dbus_timeout_ms).
+    /// You can change this using [`set_dbus_timeout_ms`](Self::set_dbus_timeout_ms). Calls that
+    /// have their own timeout in the [`TimeoutConfig`] can use a different one.
     pub fn dbus_timeout_ms(&self) -> i32 {
-        self.timeout_ms
+        self.timeout_ms(Call::Default)
     }
 
-    /// Change the D-Bus communication timeout.
+    /// Change the D-Bus communication timeout, of every kind of call.
+    ///
+    /// Negative timeouts use the default of libdbus, which is 25 seconds.
     pub fn set_dbus_timeout_ms(&mut self, timeout_ms: i32) {
-        self.timeout_ms = timeout_ms;
+        self.timeouts = Timeouts::Millis(timeout_ms);
+    }
+
+    /// Returns the timeouts used for D-Bus calls to this player.
+    ///
+    /// A negative timeout from [`set_dbus_timeout_ms`](Self::set_dbus_timeout_ms) shows up as the
+    /// 25 seconds that libdbus uses for it.
+    pub fn timeout_config(&self) -> TimeoutConfig {
+        match self.timeouts {
+            Timeouts::Millis(timeout_ms) => TimeoutConfig::from_millis(timeout_ms),
+            Timeouts::Config(config) => config,
+        }
+    }
+
+    /// Use different timeouts for different kinds of D-Bus calls, according to the given
+    /// [`TimeoutConfig`].
+    ///
+    /// By default, every call uses the timeout of the [`PlayerFinder`](crate::PlayerFinder).
+    pub fn with_timeout_config(mut self, config: TimeoutConfig) -> Self {
+        self.timeouts = Timeouts::Config(config);
+        self
+    }
+
+    /// Returns the [`RetryPolicy`] used for D-Bus calls to this player.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Retry D-Bus calls to this player that fail with transient errors, according to the given
+    /// [`RetryPolicy`]. Calls that must not be sent twice, like [`next`](Self::next) or
+    /// [`seek`](Self::seek), are never retried.
+    ///
+    /// By default, nothing is retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Returns the [`RateLimitPolicy`] used for D-Bus calls to this player.
+    pub fn rate_limit(&self) -> RateLimitPolicy {
+        self.rate_limiter.policy()
+    }
+
+    /// Send D-Bus calls to this player no faster than the given [`RateLimitPolicy`] allows, by
+    /// sleeping before calls that would come too soon after the previous one.
+    ///
+    /// This includes the calls to the D-Bus daemon made by [`is_running`](Self::is_running) and
+    /// [`pid`](Self::pid). The calls that look up the player when it is created, like the check
+    /// for [`supports_track_lists`](Self::supports_track_lists), happen before the limit is set.
+    /// With a limit, a [`TrackList`] loads metadata one chunk at a time, even with the `parallel`
+    /// feature.
+    ///
+    /// By default, calls are not limited.
+    pub fn with_rate_limit(mut self, policy: RateLimitPolicy) -> Self {
+        self.rate_limiter = RateLimiter::new(policy);
+        self
+    }
+
+    /// Reconnect to D-Bus, like after the D-Bus daemon was restarted.
+    ///
+    /// This opens a new connection to the same bus, subscribes to the signals of the player on it
+    /// again, and looks up the player's [`unique_name`](Self::unique_name), which changes when the
+    /// daemon restarts. Other [`Player`]s of the same [`PlayerFinder`](crate::PlayerFinder) keep
+    /// using the old connection.
+    ///
+    /// A connection given to [`Player::new`] or
+    /// [`PlayerFinder::for_connection`](crate::PlayerFinder::for_connection) is assumed to be on
+    /// the session bus, as there is no way to tell which bus it is on.
+    ///
+    /// Returns an error if no connection can be opened, or if the player is not on the bus.
+    pub fn reconnect(&mut self) -> Result<(), DBusError> {
+        let new = self.connection().reopen()?;
+        let unique_name = new.determine_unique_name(&*self.bus_name).ok_or_else(|| {
+            DBusError::Miscellaneous(format!("{} is not on the bus", self.bus_name))
+        })?;
+
+        new.copy_subscriptions(&self.connection(), &self.unique_name, &unique_name);
+        *self.connection.get_mut() = Rc::new(new);
+        self.unique_name = unique_name;
+        self.gone.set(false);
+        Ok(())
+    }
+
+    /// Reconnect to D-Bus automatically when a call fails because the connection to the bus was
+    /// lost, and then try the call again. This happens up to `max_attempts` times per call, after
+    /// the [`RetryPolicy`] has run out.
+    ///
+    /// Calls that must not be sent twice, like [`next`](Self::next) or [`seek`](Self::seek), are
+    /// not sent again; they still fail, but the next call uses the new connection. Calls that time
+    /// out on a connection that still works are not a reason to reconnect either.
+    ///
+    /// Unlike [`reconnect`](Self::reconnect), this keeps the current
+    /// [`unique_name`](Self::unique_name). Calls keep working after the D-Bus daemon restarted,
+    /// but signals are sent from the new unique name, so call [`reconnect`](Self::reconnect) to
+    /// receive events again.
+    ///
+    /// By default, nothing is reconnected.
+    pub fn with_auto_reconnect(mut self, max_attempts: u32) -> Self {
+        self.auto_reconnect_attempts = max_attempts;
+        self
+    }
+
+    /// Keep the last `capacity` raw signals of the player, for debugging; see
+    /// [`event_history`](Self::event_history).
+    ///
+    /// Signals are recorded while the connection processes incoming messages, like when iterating
+    /// [`subscribe_events`](Self::subscribe_events) or [`events`](Self::events), whether or not
+    /// anything reads them. With a `capacity` of `0`, nothing is recorded.
+    pub fn with_event_history(mut self, capacity: usize) -> Self {
+        self.event_history = if capacity == 0 {
+            None
+        } else {
+            let history = Rc::new(RefCell::new(EventHistory::new(capacity)));
+            self.connection()
+                .record_history(&self.unique_name, &history);
+            Some(history)
+        };
+        self
+    }
+
+    /// Returns the recorded signals of the player, oldest first.
+    ///
+    /// This is empty unless the history was enabled with
+    /// [`with_event_history`](Self::with_event_history).
+    pub fn event_history(&self) -> Vec<PlayerEvent> {
+        self.event_history
+            .as_ref()
+            .map(|history| history.borrow().to_vec())
+            .unwrap_or_default()
     }
 
     /// Returns the player's D-Bus bus name.
@@ -140,6 +315,16 @@ impl<'a> Player<'a> {
         &self.unique_name
     }
 
+    /// Returns the ID of the process that owns the player's bus name.
+    ///
+    /// This asks the D-Bus daemon with `GetConnectionUnixProcessID`, not the player itself, so it
+    /// works for any player that is still on the bus.
+    pub fn pid(&self) -> Result<u32, DBusError> {
+        self.rate_limiter.wait();
+        self.connection()
+            .unix_process_id(&self.bus_name, self.timeout_ms(Call::Default))
+    }
+
     /// Returns the player's MPRIS [`Identity`][identity].
     ///
     /// This is usually the application's name, like `Spotify`.
@@ -160,7 +345,7 @@ impl<'a> Player<'a> {
     ///
     /// [desktop_entry]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:DesktopEntry
     pub fn get_desktop_entry(&self) -> Result<Option<String>, DBusError> {
-        handle_optional_property(self.connection_path().get_desktop_entry())
+        self.retry(|| handle_optional_property(self.connection_path().get_desktop_entry()))
     }
 
     /// Returns the player's `SupportedMimeTypes` property.
@@ -169,9 +354,11 @@ impl<'a> Player<'a> {
     ///
     /// [mime_types]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:SupportedMimeTypes
     pub fn get_supported_mime_types(&self) -> Result<Vec<String>, DBusError> {
-        self.connection_path()
-            .get_supported_mime_types()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_supported_mime_types()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Returns the player's `SupportedUriSchemes` property.
@@ -180,9 +367,11 @@ impl<'a> Player<'a> {
     ///
     /// [schemes]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:SupportedUriSchemes
     pub fn get_supported_uri_schemes(&self) -> Result<Vec<String>, DBusError> {
-        self.connection_path()
-            .get_supported_uri_schemes()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_supported_uri_schemes()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Returns the player's `HasTrackList` property.
@@ -191,9 +380,11 @@ impl<'a> Player<'a> {
     ///
     /// [track_list]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:HasTrackList
     pub fn get_has_track_list(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .get_has_track_list()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_has_track_list()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Returns the player's MPRIS `position` as a [`Duration`] since the start of the media.
@@ -217,10 +408,12 @@ impl<'a> Player<'a> {
     /// Returns the player's MPRIS `position` as a count of microseconds since the start of the
     /// media.
     pub fn get_position_in_microseconds(&self) -> Result<u64, DBusError> {
-        self.connection_path()
-            .get_position()
-            .map(|p| p as u64)
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_position()
+                .map(|p| p as u64)
+                .map_err(|e| e.into())
+        })
     }
 
     /// Sets the position of the current track to the given position (as a [`Duration`]).
@@ -231,11 +424,17 @@ impl<'a> Player<'a> {
     /// **Note:** There is currently no good way to retrieve the current [`TrackID`] through the
     /// `mpris` library. You will have to manually retrieve it through D-Bus until implemented.
     ///
+    /// Positions longer than the `i64` microseconds that D-Bus allows are cut off at the maximum.
+    ///
     /// See: [MPRIS2 specification about `SetPosition`][set_position].
     ///
     /// [set_position]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:SetPosition
     pub fn set_position(&self, track_id: TrackID, position: &Duration) -> Result<(), DBusError> {
-        self.set_position_in_microseconds(track_id, DurationExtensions::as_micros(position))
+        self.retry(|| {
+            self.call_with_timeout(Call::Seek)
+                .set_position(track_id.as_path(), position.as_micros_i64())
+                .map_err(|e| e.into())
+        })
     }
 
     /// Set the "Position" setting of the player, if the player indicates that it supports the
@@ -276,16 +475,21 @@ impl<'a> Player<'a> {
         track_id: TrackID,
         position_in_us: u64,
     ) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_position(track_id.as_path(), position_in_us as i64)
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.call_with_timeout(Call::Seek)
+                .set_position(
+                    track_id.as_path(),
+                    i64::try_from(position_in_us).unwrap_or(i64::MAX),
+                )
+                .map_err(|e| e.into())
+        })
     }
 
     /// Returns the player's MPRIS (playback) `rate` as a factor.
     ///
     /// 1.0 would mean normal rate, while 2.0 would mean twice the playback speed.
     pub fn get_playback_rate(&self) -> Result<f64, DBusError> {
-        self.connection_path().get_rate().map_err(|e| e.into())
+        self.retry(|| self.connection_path().get_rate().map_err(|e| e.into()))
     }
 
     /// Gets the "Rate" setting, if the player indicates that it supports it.
@@ -314,7 +518,28 @@ impl<'a> Player<'a> {
     ///
     /// [rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Rate
     pub fn set_playback_rate(&self, rate: f64) -> Result<(), DBusError> {
-        self.connection_path().set_rate(rate).map_err(|e| e.into())
+        self.retry(|| self.connection_path().set_rate(rate).map_err(|e| e.into()))
+    }
+
+    /// Sets the player's MPRIS (playback) `rate`, after checking that it is within the range that
+    /// the player supports.
+    ///
+    /// Returns [`RateError::OutOfRange`] without changing the rate if it is not within
+    /// [`get_minimum_playback_rate`](Self::get_minimum_playback_rate) and
+    /// [`get_maximum_playback_rate`](Self::get_maximum_playback_rate), both inclusive. The player
+    /// would otherwise reject it with an opaque D-Bus error, or ignore it.
+    ///
+    /// See: [MPRIS2 specification about `Rate`][rate].
+    ///
+    /// [rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Rate
+    pub fn set_playback_rate_in_range(&self, rate: f64) -> Result<(), RateError> {
+        let min = self.get_minimum_playback_rate()?;
+        let max = self.get_maximum_playback_rate()?;
+        if rate >= min && rate <= max {
+            Ok(self.set_playback_rate(rate)?)
+        } else {
+            Err(RateError::OutOfRange { rate, min, max })
+        }
     }
 
     /// Set the playback rate of the player, if the player indicates that supports it and that it
@@ -341,9 +566,11 @@ impl<'a> Player<'a> {
     ///
     /// [min_rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:MinimumRate
     pub fn get_minimum_playback_rate(&self) -> Result<f64, DBusError> {
-        self.connection_path()
-            .get_minimum_rate()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_minimum_rate()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Gets the maximum allowed value for playback rate.
@@ -352,9 +579,11 @@ impl<'a> Player<'a> {
     ///
     /// [max_rate]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:MaximumRate
     pub fn get_maximum_playback_rate(&self) -> Result<f64, DBusError> {
-        self.connection_path()
-            .get_maximum_rate()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_maximum_rate()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Gets the minimum-maximum allowed value range for playback rate.
@@ -366,21 +595,136 @@ impl<'a> Player<'a> {
             .and_then(|min| self.get_maximum_playback_rate().map(|max| min..max))
     }
 
+    /// Query the player for every property of the `org.mpris.MediaPlayer2.Player` interface at
+    /// once.
+    ///
+    /// This is a single D-Bus call, so it is faster than calling the separate getters when you
+    /// need more than one or two of the properties.
+    pub fn get_all_properties(&self) -> Result<PlayerProperties, DBusError> {
+        self.get_all_values("org.mpris.MediaPlayer2.Player")
+            .and_then(PlayerProperties::from_values)
+    }
+
+    /// Query the player for every property of the `org.mpris.MediaPlayer2` interface at once,
+    /// like [`get_all_properties`](Self::get_all_properties) does for the `Player` interface.
+    pub fn get_all_root_properties(&self) -> Result<RootProperties, DBusError> {
+        self.get_all_values("org.mpris.MediaPlayer2")
+            .and_then(RootProperties::from_values)
+    }
+
+    /// Query the player for all of its `can_*` properties at once.
+    ///
+    /// This loads both the `org.mpris.MediaPlayer2` and `org.mpris.MediaPlayer2.Player`
+    /// interfaces with one `GetAll` call each, so it is cheaper than calling more than two of the
+    /// separate `can_*` methods.
+    pub fn get_capabilities(&self) -> Result<PlayerCapabilities, DBusError> {
+        let player_values = self.get_all_values("org.mpris.MediaPlayer2.Player")?;
+        let root_values = self.get_all_values("org.mpris.MediaPlayer2")?;
+        Ok(PlayerCapabilities::from_values(
+            &player_values,
+            &root_values,
+        ))
+    }
+
+    fn get_all_values(&self, interface: &str) -> Result<HashMap<String, MetadataValue>, DBusError> {
+        self.retry(|| {
+            let mut reply = self.connection_path().method_call_with_args(
+                &"org.freedesktop.DBus.Properties".into(),
+                &"GetAll".into(),
+                |msg| {
+                    msg.append_items(&[interface.into()]);
+                },
+            )?;
+            reply.as_result()?;
+            reply.iter_init().read().map_err(DBusError::from)
+        })
+    }
+
+    /// Query the player for any property, including ones that are not part of MPRIS.
+    ///
+    /// This is an escape hatch for players with non-standard extensions; prefer the typed getters
+    /// for everything in the specification. It uses the same connection, timeout and
+    /// [`RetryPolicy`] as the rest of the methods.
+    pub fn get_property_raw(
+        &self,
+        interface: &str,
+        property: &str,
+    ) -> Result<Box<dyn RefArg>, DBusError> {
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+
+            Properties::get(&self.connection_path(), interface, property).map_err(DBusError::from)
+        })
+    }
+
+    /// Ask the player which properties it has on the given D-Bus interface, like
+    /// `org.mpris.MediaPlayer2.Player`.
+    ///
+    /// This reads the introspection data of the player, which takes a single call instead of one
+    /// for every property. Players are not required to list every property that they support, so
+    /// treat a missing name as a hint rather than a promise. Returns an empty list if the player
+    /// does not have the interface.
+    pub fn get_property_names(&self, interface: &str) -> Result<Vec<String>, DBusError> {
+        use dbus::ffidisp::stdintf::OrgFreedesktopDBusIntrospectable;
+
+        let xml: String =
+            self.retry(|| self.connection_path().introspect().map_err(DBusError::from))?;
+        Ok(introspected_property_names(&xml, interface))
+    }
+
+    /// Call any method on the player, including ones that are not part of MPRIS, and return the
+    /// reply.
+    ///
+    /// Like [`get_property_raw`](Self::get_property_raw), this is an escape hatch for non-standard
+    /// extensions. An error reply from the player is returned as an `Err`.
+    pub fn call_method_raw<A: AppendAll>(
+        &self,
+        interface: &str,
+        method: &str,
+        args: A,
+    ) -> Result<Message, DBusError> {
+        let interface = Interface::new(interface).map_err(invalid_args)?;
+        let method = Member::new(method).map_err(invalid_args)?;
+
+        self.call_once(|| {
+            let mut reply =
+                self.connection_path()
+                    .method_call_with_args(&interface, &method, |msg| {
+                        args.append(&mut IterAppend::new(msg));
+                    })?;
+            reply.as_result()?;
+            Ok(reply)
+        })
+    }
+
     /// Query the player for current metadata.
     ///
     /// See [`Metadata`] for more information about what is included here.
     pub fn get_metadata(&self) -> Result<Metadata, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        let connection_path = self.connection_path();
+            let connection_path = self.connection_path();
 
-        Properties::get::<HashMap<String, MetadataValue>>(
-            &connection_path,
-            "org.mpris.MediaPlayer2.Player",
-            "Metadata",
-        )
-        .map(Metadata::from)
-        .map_err(DBusError::from)
+            Properties::get::<HashMap<String, MetadataValue>>(
+                &connection_path,
+                "org.mpris.MediaPlayer2.Player",
+                "Metadata",
+            )
+            .map(Metadata::from)
+            .map_err(DBusError::from)
+        })
+    }
+
+    /// Query the player for the [`TrackID`] of the current track, from its [`Metadata`].
+    ///
+    /// Returns [`None`] if the metadata has no track ID, or if it is the
+    /// [`TrackID::no_track`] sentinel that players use when there is no current track.
+    pub fn get_current_track_id(&self) -> Result<Option<TrackID>, DBusError> {
+        Ok(self
+            .get_metadata()?
+            .track_id()
+            .filter(|id| !id.is_no_track()))
     }
 
     /// Query the player for the current tracklist.
@@ -390,17 +734,19 @@ impl<'a> Player<'a> {
     ///
     /// See [`checked_get_track_list`](Self::checked_get_track_list) to automatically detect players not supporting track lists.
     pub fn get_track_list(&self) -> Result<TrackList, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        let connection_path = self.connection_path();
+            let connection_path = self.connection_path();
 
-        Properties::get::<Vec<Path<'_>>>(
-            &connection_path,
-            "org.mpris.MediaPlayer2.TrackList",
-            "Tracks",
-        )
-        .map(TrackList::from)
-        .map_err(DBusError::from)
+            Properties::get::<Vec<Path<'_>>>(
+                &connection_path,
+                "org.mpris.MediaPlayer2.TrackList",
+                "Tracks",
+            )
+            .map(TrackList::from)
+            .map_err(DBusError::from)
+        })
     }
 
     /// Query the player for the current tracklist.
@@ -428,15 +774,17 @@ impl<'a> Player<'a> {
     ///
     /// [can_edit]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Property:CanEditTracks
     pub fn can_edit_tracks(&self) -> Result<bool, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
-        let connection_path = self.connection_path();
-
-        Properties::get::<bool>(
-            &connection_path,
-            "org.mpris.MediaPlayer2.TrackList",
-            "CanEditTracks",
-        )
-        .map_err(DBusError::from)
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+            let connection_path = self.connection_path();
+
+            Properties::get::<bool>(
+                &connection_path,
+                "org.mpris.MediaPlayer2.TrackList",
+                "CanEditTracks",
+            )
+            .map_err(DBusError::from)
+        })
     }
 
     /// Query the player to see if it allows changes to its TrackList.
@@ -465,30 +813,85 @@ impl<'a> Player<'a> {
     ///
     /// [get_meta]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Method:GetTracksMetadata
     pub fn get_tracks_metadata(&self, track_ids: &[TrackID]) -> Result<Vec<Metadata>, DBusError> {
-        use dbus::arg::IterAppend;
-        let connection_path = self.connection_path();
-
-        let mut method = connection_path.method_call_with_args(
-            &"org.mpris.MediaPlayer2.TrackList".into(),
-            &"GetTracksMetadata".into(),
-            |msg| {
-                let mut i = IterAppend::new(msg);
-                i.append(track_ids.iter().map(|id| id.as_path()).collect::<Vec<_>>());
-            },
-        )?;
-        method.as_result()?;
-        let mut i = method.iter_init();
-        let metadata: Vec<::std::collections::HashMap<String, MetadataValue>> = i.read()?;
-
-        if metadata.len() == track_ids.len() {
-            Ok(metadata.into_iter().map(Metadata::from).collect())
-        } else {
-            Err(DBusError::Miscellaneous(format!(
-                "Expected {} tracks, but got {} tracks returned.",
-                track_ids.len(),
-                metadata.len()
-            )))
-        }
+        self.retry(|| tracks_metadata(&self.call_with_timeout(Call::GetTracksMetadata), track_ids))
+    }
+
+    /// Like [`get_tracks_metadata`](Self::get_tracks_metadata), but splits the tracks into chunks
+    /// that are loaded by a few threads at once, each over its own connection to the same bus.
+    ///
+    /// The chunks are loaded one after another over the player's connection if the bus of that
+    /// connection is not known, like when it came from [`Player::new`], or if the calls are rate
+    /// limited.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn get_tracks_metadata_in_chunks(
+        &self,
+        track_ids: &[TrackID],
+        chunk_size: usize,
+    ) -> Result<Vec<Metadata>, DBusError> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let chunks: Vec<&[TrackID]> = track_ids.chunks(chunk_size.max(1)).collect();
+        let connection = self.connection();
+        let bus = match connection.bus() {
+            Some(bus) if self.rate_limit().min_interval().is_none() => bus,
+            _ => {
+                let mut metadata = Vec::with_capacity(track_ids.len());
+                for chunk in chunks {
+                    metadata.extend(self.get_tracks_metadata(chunk)?);
+                }
+                return Ok(metadata);
+            }
+        };
+        let unique_name = self.unique_name.as_str();
+        let path = &self.path;
+        let timeout_ms = self.timeout_ms(Call::GetTracksMetadata);
+        let retry_policy = self.retry_policy;
+        let next_chunk = AtomicUsize::new(0);
+
+        let loaded = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..chunks.len().min(METADATA_WORKERS))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let connection = crate::find::open_connection(bus)?;
+                        let connection_path =
+                            connection.with_path(unique_name, path.clone(), timeout_ms);
+
+                        let mut loaded = Vec::new();
+                        loop {
+                            let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                            let chunk = match chunks.get(index) {
+                                Some(chunk) => chunk,
+                                None => return Ok(loaded),
+                            };
+                            match retry_policy.run(|| tracks_metadata(&connection_path, chunk)) {
+                                Ok(metadata) => loaded.push((index, metadata)),
+                                Err(error) => {
+                                    // Let the other workers stop as well.
+                                    next_chunk.store(chunks.len(), Ordering::Relaxed);
+                                    return Err(error);
+                                }
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect::<Result<Vec<_>, DBusError>>()
+        })?;
+
+        let mut loaded: Vec<_> = loaded.into_iter().flatten().collect();
+        loaded.sort_by_key(|(index, _)| *index);
+        Ok(loaded
+            .into_iter()
+            .flat_map(|(_, metadata)| metadata)
+            .collect())
     }
 
     /// Query the player for metadata for a single [`TrackID`].
@@ -511,6 +914,15 @@ impl<'a> Player<'a> {
             })
     }
 
+    /// Query the player for a new [`Progress`] snapshot.
+    ///
+    /// The [`Progress`] can then be used to calculate the current position without talking to
+    /// the player again. See [`track_progress`](Self::track_progress) if you want to keep it up
+    /// to date.
+    pub fn get_progress(&self) -> Result<Progress, DBusError> {
+        Progress::from_player(self)
+    }
+
     /// Returns a new [`ProgressTracker`] for the player.
     ///
     /// Use this if you want to monitor a player in order to show close-to-realtime information
@@ -542,19 +954,52 @@ impl<'a> Player<'a> {
         PlayerEvents::new(self)
     }
 
+    /// Returns an [`EventIterator`] of the raw signals that the player emits.
+    ///
+    /// This iterator will block until the player emits a signal. Compared to
+    /// [`events`](Self::events), no state is loaded or compared; you get the signals as they are
+    /// sent by the player.
+    ///
+    /// See: [`events`](Self::events) for an alternative approach.
+    pub fn subscribe_events(&self) -> EventIterator<'_> {
+        EventIterator::new(self)
+    }
+
     /// Returns true if the bus of this player is still occupied in the connection, or put in
     /// another way: If there's a process still listening on messages on this bus.
     ///
     /// If the player that you are controlling / querying has shut down, then this would return
     /// false. You can use this to do graceful restarts, begin looking for another player, etc.
+    ///
+    /// This asks the D-Bus daemon using `NameHasOwner`, without talking to the player itself.
+    /// Once the daemon has said that the player is gone, this returns false right away without
+    /// asking again; use [`PlayerFinder`](crate::PlayerFinder) to find the player again if it is
+    /// restarted. If the daemon could not be asked, this returns false for this call only.
     pub fn is_running(&self) -> bool {
-        self.connection()
-            .name_has_owner(self.bus_name.to_string())
-            .unwrap_or(false)
+        if self.gone.get() {
+            return false;
+        }
+
+        self.rate_limiter.wait();
+        match self.connection().name_has_owner(self.bus_name.to_string()) {
+            Some(running) => {
+                self.gone.set(!running);
+                running
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn connection(&self) -> Rc<PooledConnection> {
+        Rc::clone(&self.connection.borrow())
     }
 
-    pub(crate) fn connection(&self) -> &PooledConnection {
-        &self.connection
+    pub(crate) fn path(&self) -> &Path<'_> {
+        &self.path
+    }
+
+    pub(crate) fn auto_reconnect_attempts(&self) -> u32 {
+        self.auto_reconnect_attempts
     }
 
     /// Send a `PlayPause` signal to the player.
@@ -563,7 +1008,31 @@ impl<'a> Player<'a> {
     ///
     /// [play_pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:PlayPause
     pub fn play_pause(&self) -> Result<(), DBusError> {
-        self.connection_path().play_pause().map_err(|e| e.into())
+        self.call_once(|| self.connection_path().play_pause().map_err(|e| e.into()))
+    }
+
+    /// Pause the player if it is playing, or play it if it is paused; a stopped player is left
+    /// alone.
+    ///
+    /// Unlike [`play_pause`](Self::play_pause), which leaves the decision to the player, this
+    /// reads the [`PlaybackStatus`] first and then sends `Play` or `Pause`, so it takes two round
+    /// trips.
+    ///
+    /// Returns an error without sending anything if the player reports that it cannot be
+    /// controlled.
+    pub fn toggle_play(&self) -> Result<(), DBusError> {
+        if !self.can_control()? {
+            return Err(DBusError::Miscellaneous(format!(
+                "{} cannot be controlled",
+                self.bus_name
+            )));
+        }
+
+        match self.get_playback_status()? {
+            PlaybackStatus::Playing => self.pause(),
+            PlaybackStatus::Paused => self.play(),
+            PlaybackStatus::Stopped => Ok(()),
+        }
     }
 
     /// Send a `Play` signal to the player.
@@ -572,7 +1041,7 @@ impl<'a> Player<'a> {
     ///
     /// [play]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Play
     pub fn play(&self) -> Result<(), DBusError> {
-        self.connection_path().play().map_err(|e| e.into())
+        self.retry(|| self.connection_path().play().map_err(|e| e.into()))
     }
 
     /// Send a `Pause` signal to the player.
@@ -581,7 +1050,7 @@ impl<'a> Player<'a> {
     ///
     /// [pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Pause
     pub fn pause(&self) -> Result<(), DBusError> {
-        self.connection_path().pause().map_err(|e| e.into())
+        self.retry(|| self.connection_path().pause().map_err(|e| e.into()))
     }
 
     /// Send a `Stop` signal to the player.
@@ -590,7 +1059,7 @@ impl<'a> Player<'a> {
     ///
     /// [stop]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Stop
     pub fn stop(&self) -> Result<(), DBusError> {
-        self.connection_path().stop().map_err(|e| e.into())
+        self.retry(|| self.connection_path().stop().map_err(|e| e.into()))
     }
 
     /// Send a `Next` signal to the player.
@@ -599,7 +1068,7 @@ impl<'a> Player<'a> {
     ///
     /// [next]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Next
     pub fn next(&self) -> Result<(), DBusError> {
-        self.connection_path().next().map_err(|e| e.into())
+        self.call_once(|| self.connection_path().next().map_err(|e| e.into()))
     }
 
     /// Send a `Previous` signal to the player.
@@ -608,7 +1077,48 @@ impl<'a> Player<'a> {
     ///
     /// [prev]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Previous
     pub fn previous(&self) -> Result<(), DBusError> {
-        self.connection_path().previous().map_err(|e| e.into())
+        self.call_once(|| self.connection_path().previous().map_err(|e| e.into()))
+    }
+
+    /// Ask the player to open and play the given URI.
+    ///
+    /// The URI is not checked against the schemes that the player supports; see
+    /// [`open_uri_checked`](Self::open_uri_checked) for that. An empty URI returns an error without
+    /// calling the player.
+    ///
+    /// See: [MPRIS2 specification about `OpenUri`][open_uri].
+    ///
+    /// [open_uri]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:OpenUri
+    pub fn open_uri(&self, uri: &str) -> Result<(), DBusError> {
+        if uri.is_empty() {
+            return Err(DBusError::Miscellaneous(String::from(
+                "Cannot open an empty URI",
+            )));
+        }
+        self.call_once(|| self.connection_path().open_uri(uri).map_err(|e| e.into()))
+    }
+
+    /// Ask the player to open and play the given URI, if the player supports its scheme.
+    ///
+    /// Returns [`OpenUriError::UnsupportedScheme`] without calling `OpenUri` if the scheme (like
+    /// `file` or `http`) is not one of the player's
+    /// [supported URI schemes](Self::get_supported_uri_schemes), ignoring case.
+    ///
+    /// See: [MPRIS2 specification about `OpenUri`][open_uri].
+    ///
+    /// [open_uri]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:OpenUri
+    pub fn open_uri_checked(&self, uri: &str) -> Result<(), OpenUriError> {
+        let scheme = uri.split(':').next().unwrap_or_default();
+        let supported = self
+            .get_supported_uri_schemes()?
+            .iter()
+            .any(|supported| supported.eq_ignore_ascii_case(scheme));
+
+        if supported && uri.contains(':') {
+            Ok(self.open_uri(uri)?)
+        } else {
+            Err(OpenUriError::UnsupportedScheme(scheme.to_string()))
+        }
     }
 
     /// Send a `Seek` signal to the player.
@@ -617,16 +1127,20 @@ impl<'a> Player<'a> {
     ///
     /// [seek]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Method:Seek
     pub fn seek(&self, offset_in_microseconds: i64) -> Result<(), DBusError> {
-        self.connection_path()
-            .seek(offset_in_microseconds)
-            .map_err(|e| e.into())
+        self.call_once(|| {
+            self.call_with_timeout(Call::Seek)
+                .seek(offset_in_microseconds)
+                .map_err(|e| e.into())
+        })
     }
 
     /// Tell the player to seek forwards.
     ///
+    /// Offsets longer than the `i64` microseconds that D-Bus allows are cut off at the maximum.
+    ///
     /// See: [`seek`](Self::seek) method.
     pub fn seek_forwards(&self, offset: &Duration) -> Result<(), DBusError> {
-        self.seek(DurationExtensions::as_micros(offset) as i64)
+        self.seek(offset.as_micros_i64())
     }
 
     /// Send a `Raise` signal to the player.
@@ -642,7 +1156,7 @@ impl<'a> Player<'a> {
     ///
     /// [raise]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Method:Raise
     pub fn raise(&self) -> Result<(), DBusError> {
-        self.connection_path().raise().map_err(|e| e.into())
+        self.retry(|| self.connection_path().raise().map_err(|e| e.into()))
     }
 
     /// Send a `Raise` signal to the player, if it supports it.
@@ -673,7 +1187,7 @@ impl<'a> Player<'a> {
     ///
     /// [quit]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Method:Quit
     pub fn quit(&self) -> Result<(), DBusError> {
-        self.connection_path().quit().map_err(|e| e.into())
+        self.retry(|| self.connection_path().quit().map_err(|e| e.into()))
     }
 
     /// Send a `Quit` signal to the player, if it supports it.
@@ -689,33 +1203,42 @@ impl<'a> Player<'a> {
 
     /// Tell the player to seek backwards.
     ///
+    /// Offsets longer than the `i64` microseconds that D-Bus allows are cut off at the maximum.
+    ///
     /// See: [`seek`](Self::seek) method.
     pub fn seek_backwards(&self, offset: &Duration) -> Result<(), DBusError> {
-        self.seek(-(DurationExtensions::as_micros(offset) as i64))
+        self.seek(-offset.as_micros_i64())
     }
 
     /// Go to a specific track on the [`Player`]'s [`TrackList`].
     ///
     /// If the given [`TrackID`] is not part of the player's [`TrackList`], it will have no effect.
     ///
-    /// Requires the player to implement the `TrackList` interface.
+    /// Requires the player to implement the `TrackList` interface; if it does not (see
+    /// [`supports_track_lists`](Self::supports_track_lists)), an error is returned without calling
+    /// the player.
     ///
     /// See: [MPRIS2 specification about `GoTo`][go_to]
     ///
     /// [go_to]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Method:GoTo
     pub fn go_to(&self, track_id: &TrackID) -> Result<(), DBusError> {
-        use crate::generated::OrgMprisMediaPlayer2TrackList;
+        self.require_track_list()?;
+        self.retry(|| {
+            use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .go_to(track_id.into())
-            .map_err(DBusError::from)
+            self.connection_path()
+                .go_to(track_id.into())
+                .map_err(DBusError::from)
+        })
     }
 
     /// Add a URI to the TrackList and optionally set it as current.
     ///
     /// It is placed after the specified [`TrackID`], if supported by the player.
     ///
-    /// Requires the player to implement the `TrackList` interface.
+    /// Requires the player to implement the `TrackList` interface; if it does not (see
+    /// [`supports_track_lists`](Self::supports_track_lists)), an error is returned without calling
+    /// the player.
     ///
     /// See: [MPRIS2 specification about `AddTrack`][add_track].
     ///
@@ -726,41 +1249,155 @@ impl<'a> Player<'a> {
         after: &TrackID,
         set_as_current: bool,
     ) -> Result<(), DBusError> {
-        use crate::generated::OrgMprisMediaPlayer2TrackList;
+        self.require_track_list()?;
+        self.call_once(|| {
+            use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .add_track(uri, after.into(), set_as_current)
-            .map_err(DBusError::from)
+            self.connection_path()
+                .add_track(uri, after.into(), set_as_current)
+                .map_err(DBusError::from)
+        })
     }
 
     /// Add a URI to the start of the TrackList and optionally set it as current.
     ///
-    /// Requires the player to implement the `TrackList` interface.
+    /// Requires the player to implement the `TrackList` interface; if it does not (see
+    /// [`supports_track_lists`](Self::supports_track_lists)), an error is returned without calling
+    /// the player.
     ///
     /// See: [MPRIS2 specification about `AddTrack`][add_track].
     ///
     /// [add_track]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Method:AddTrack
     pub fn add_track_at_start(&self, uri: &str, set_as_current: bool) -> Result<(), DBusError> {
-        use crate::generated::OrgMprisMediaPlayer2TrackList;
+        self.require_track_list()?;
+        self.call_once(|| {
+            use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .add_track(uri, crate::track_list::NO_TRACK.into(), set_as_current)
-            .map_err(DBusError::from)
+            self.connection_path()
+                .add_track(uri, crate::track_list::NO_TRACK_PATH.into(), set_as_current)
+                .map_err(DBusError::from)
+        })
     }
 
     /// Remove an item from the TrackList.
     ///
-    /// Requires the player to implement the `TrackList` interface.
+    /// Requires the player to implement the `TrackList` interface; if it does not (see
+    /// [`supports_track_lists`](Self::supports_track_lists)), an error is returned without calling
+    /// the player.
     ///
     /// See: [MPRIS2 specification about `RemoveTrack`][remove].
     ///
     /// [remove]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Method:RemoveTrack
     pub fn remove_track(&self, track_id: &TrackID) -> Result<(), DBusError> {
-        use crate::generated::OrgMprisMediaPlayer2TrackList;
+        self.require_track_list()?;
+        self.call_once(|| {
+            use crate::generated::OrgMprisMediaPlayer2TrackList;
 
-        self.connection_path()
-            .remove_track(track_id.into())
-            .map_err(DBusError::from)
+            self.connection_path()
+                .remove_track(track_id.into())
+                .map_err(DBusError::from)
+        })
+    }
+
+    /// Query the player for the number of playlists it has.
+    ///
+    /// Requires the player to implement the `Playlists` interface.
+    ///
+    /// See: [MPRIS2 specification about `PlaylistCount`][count].
+    ///
+    /// [count]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Property:PlaylistCount
+    pub fn get_playlist_count(&self) -> Result<u32, DBusError> {
+        self.retry(|| {
+            use crate::generated::OrgMprisMediaPlayer2Playlists;
+
+            self.connection_path()
+                .get_playlist_count()
+                .map_err(DBusError::from)
+        })
+    }
+
+    /// Query the player for the orderings that it supports in
+    /// [`get_playlists`](Self::get_playlists).
+    ///
+    /// Requires the player to implement the `Playlists` interface.
+    ///
+    /// See: [MPRIS2 specification about `Orderings`][orderings].
+    ///
+    /// [orderings]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Property:Orderings
+    pub fn get_playlist_orderings(&self) -> Result<Vec<PlaylistOrdering>, DBusError> {
+        self.retry(|| {
+            use crate::generated::OrgMprisMediaPlayer2Playlists;
+
+            self.connection_path()
+                .get_orderings()
+                .map_err(DBusError::from)?
+                .iter()
+                .map(|ordering| ordering.parse().map_err(DBusError::from))
+                .collect()
+        })
+    }
+
+    /// Query the player for a range of its playlists.
+    ///
+    /// Returns at most `max_count` playlists, starting at `index` in the given `order` (or the
+    /// reverse of it). The player might not support every [`PlaylistOrdering`]; see
+    /// [`get_playlist_orderings`](Self::get_playlist_orderings).
+    ///
+    /// Requires the player to implement the `Playlists` interface.
+    ///
+    /// See: [MPRIS2 specification about `GetPlaylists`][get_playlists].
+    ///
+    /// [get_playlists]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Method:GetPlaylists
+    pub fn get_playlists(
+        &self,
+        index: u32,
+        max_count: u32,
+        order: PlaylistOrdering,
+        reverse_order: bool,
+    ) -> Result<Vec<Playlist>, DBusError> {
+        self.retry(|| {
+            use crate::generated::OrgMprisMediaPlayer2Playlists;
+
+            self.connection_path()
+                .get_playlists(index, max_count, order.dbus_value(), reverse_order)
+                .map(|playlists| playlists.into_iter().map(Playlist::from).collect())
+                .map_err(DBusError::from)
+        })
+    }
+
+    /// Query the player for the currently active playlist, if there is one.
+    ///
+    /// Requires the player to implement the `Playlists` interface.
+    ///
+    /// See: [MPRIS2 specification about `ActivePlaylist`][active_playlist].
+    ///
+    /// [active_playlist]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Property:ActivePlaylist
+    pub fn get_active_playlist(&self) -> Result<Option<Playlist>, DBusError> {
+        self.retry(|| {
+            use crate::generated::OrgMprisMediaPlayer2Playlists;
+
+            self.connection_path()
+                .get_active_playlist()
+                .map(|value| MaybePlaylist::from_dbus_value(value).into_active())
+                .map_err(DBusError::from)
+        })
+    }
+
+    /// Start playing the given playlist.
+    ///
+    /// Requires the player to implement the `Playlists` interface.
+    ///
+    /// See: [MPRIS2 specification about `ActivatePlaylist`][activate].
+    ///
+    /// [activate]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Method:ActivatePlaylist
+    pub fn activate_playlist(&self, playlist_id: &PlaylistID) -> Result<(), DBusError> {
+        self.call_once(|| {
+            use crate::generated::OrgMprisMediaPlayer2Playlists;
+
+            self.connection_path()
+                .activate_playlist(playlist_id.into())
+                .map_err(DBusError::from)
+        })
     }
 
     /// Sends a `PlayPause` signal to the player, if the player indicates that it can pause.
@@ -906,7 +1543,7 @@ impl<'a> Player<'a> {
     ///
     /// [can_raise]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanRaise
     pub fn can_raise(&self) -> Result<bool, DBusError> {
-        self.connection_path().get_can_raise().map_err(|e| e.into())
+        self.retry(|| self.connection_path().get_can_raise().map_err(|e| e.into()))
     }
 
     /// Queries the player to see if it can be asked to quit.
@@ -915,7 +1552,7 @@ impl<'a> Player<'a> {
     ///
     /// [can_quit]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanQuit
     pub fn can_quit(&self) -> Result<bool, DBusError> {
-        self.connection_path().get_can_quit().map_err(|e| e.into())
+        self.retry(|| self.connection_path().get_can_quit().map_err(|e| e.into()))
     }
 
     /// Queries the player to see if it can be asked to entrer fullscreen.
@@ -929,8 +1566,10 @@ impl<'a> Player<'a> {
     ///
     /// [can_full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:CanSetFullscreen
     pub fn can_set_fullscreen(&self) -> Result<bool, DBusError> {
-        handle_optional_property(self.connection_path().get_can_set_fullscreen())
-            .map(|o| o.unwrap_or(false))
+        self.retry(|| {
+            handle_optional_property(self.connection_path().get_can_set_fullscreen())
+                .map(|o| o.unwrap_or(false))
+        })
     }
 
     /// Queries the player to see if it can be controlled or not.
@@ -939,9 +1578,11 @@ impl<'a> Player<'a> {
     ///
     /// [can_control]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanControl
     pub fn can_control(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .get_can_control()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_can_control()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Queries the player to see if it can go to next or not.
@@ -950,9 +1591,11 @@ impl<'a> Player<'a> {
     ///
     /// [can_next]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanGoNext
     pub fn can_go_next(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .get_can_go_next()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_can_go_next()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Queries the player to see if it can go to previous or not.
@@ -961,9 +1604,11 @@ impl<'a> Player<'a> {
     ///
     /// [can_prev]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanGoPrevious
     pub fn can_go_previous(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .get_can_go_previous()
-            .map_err(|e| e.into())
+        self.retry(|| {
+            self.connection_path()
+                .get_can_go_previous()
+                .map_err(|e| e.into())
+        })
     }
 
     /// Queries the player to see if it can pause.
@@ -972,7 +1617,7 @@ impl<'a> Player<'a> {
     ///
     /// [can_pause]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanPause
     pub fn can_pause(&self) -> Result<bool, DBusError> {
-        self.connection_path().get_can_pause().map_err(|e| e.into())
+        self.retry(|| self.connection_path().get_can_pause().map_err(|e| e.into()))
     }
 
     /// Queries the player to see if it can play.
@@ -981,7 +1626,13 @@ impl<'a> Player<'a> {
     ///
     /// [can_play]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanPlay
     pub fn can_play(&self) -> Result<bool, DBusError> {
-        self.connection_path().get_can_play().map_err(|e| e.into())
+        self.retry(|| self.connection_path().get_can_play().map_err(|e| e.into()))
+    }
+
+    /// Queries the player to see if [`open_uri`](Self::open_uri) is meaningful, which is the
+    /// case when it [can play](Self::can_play).
+    pub fn can_open_uri(&self) -> Result<bool, DBusError> {
+        self.can_play()
     }
 
     /// Queries the player to see if it can seek within the media.
@@ -990,7 +1641,7 @@ impl<'a> Player<'a> {
     ///
     /// [can_seek]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:CanSeek
     pub fn can_seek(&self) -> Result<bool, DBusError> {
-        self.connection_path().get_can_seek().map_err(|e| e.into())
+        self.retry(|| self.connection_path().get_can_seek().map_err(|e| e.into()))
     }
 
     /// Queries the player to see if it can stop.
@@ -1014,52 +1665,62 @@ impl<'a> Player<'a> {
 
     /// Queries the player to see if it supports the "Shuffle" setting
     pub fn can_shuffle(&self) -> Result<bool, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Shuffle"))
-            .map_err(DBusError::from)
+            self.connection_path()
+                .get_all("org.mpris.MediaPlayer2.Player")
+                .map(|props| props.contains_key("Shuffle"))
+                .map_err(DBusError::from)
+        })
     }
 
     /// Queries the player to see if it supports the "LoopStatus" setting
     pub fn can_loop(&self) -> Result<bool, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("LoopStatus"))
-            .map_err(DBusError::from)
+            self.connection_path()
+                .get_all("org.mpris.MediaPlayer2.Player")
+                .map(|props| props.contains_key("LoopStatus"))
+                .map_err(DBusError::from)
+        })
     }
 
     /// Queries the player to see if it supports the "Rate" setting
     pub fn has_playback_rate(&self) -> Result<bool, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Rate"))
-            .map_err(DBusError::from)
+            self.connection_path()
+                .get_all("org.mpris.MediaPlayer2.Player")
+                .map(|props| props.contains_key("Rate"))
+                .map_err(DBusError::from)
+        })
     }
 
     /// Queries the player to see if it supports the "Position" setting
     pub fn has_position(&self) -> Result<bool, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Position"))
-            .map_err(DBusError::from)
+            self.connection_path()
+                .get_all("org.mpris.MediaPlayer2.Player")
+                .map(|props| props.contains_key("Position"))
+                .map_err(DBusError::from)
+        })
     }
 
     /// Queries the player to see if it supports the "Volume" setting
     pub fn has_volume(&self) -> Result<bool, DBusError> {
-        use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+        self.retry(|| {
+            use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 
-        self.connection_path()
-            .get_all("org.mpris.MediaPlayer2.Player")
-            .map(|props| props.contains_key("Volume"))
-            .map_err(DBusError::from)
+            self.connection_path()
+                .get_all("org.mpris.MediaPlayer2.Player")
+                .map(|props| props.contains_key("Volume"))
+                .map_err(DBusError::from)
+        })
     }
 
     /// Query the player for current fullscreen state.
@@ -1073,7 +1734,7 @@ impl<'a> Player<'a> {
     ///
     /// [full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Fullscreen
     pub fn get_fullscreen(&self) -> Result<Option<bool>, DBusError> {
-        handle_optional_property(self.connection_path().get_fullscreen())
+        self.retry(|| handle_optional_property(self.connection_path().get_fullscreen()))
     }
 
     /// Asks the player to change fullscreen state.
@@ -1089,16 +1750,37 @@ impl<'a> Player<'a> {
     ///
     /// [full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Fullscreen
     pub fn set_fullscreen(&self, new_state: bool) -> Result<bool, DBusError> {
-        handle_optional_property(self.connection_path().set_fullscreen(new_state))
-            .map(|o| o.is_some())
+        self.retry(|| {
+            handle_optional_property(self.connection_path().set_fullscreen(new_state))
+                .map(|o| o.is_some())
+        })
+    }
+
+    /// Asks the player to change fullscreen state, if the player indicates that it
+    /// [can set fullscreen](Self::can_set_fullscreen).
+    ///
+    /// Returns a [`bool`] to show if the state was set or not. Use
+    /// [`set_fullscreen`](Self::set_fullscreen) to skip the check.
+    ///
+    /// See: [MPRIS2 specification about `Fullscreen`][full].
+    ///
+    /// [full]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Fullscreen
+    pub fn checked_set_fullscreen(&self, new_state: bool) -> Result<bool, DBusError> {
+        if self.can_set_fullscreen()? {
+            self.set_fullscreen(new_state)
+        } else {
+            Ok(false)
+        }
     }
 
     /// Query the player for current playback status.
     pub fn get_playback_status(&self) -> Result<PlaybackStatus, DBusError> {
-        self.connection_path()
-            .get_playback_status()?
-            .parse()
-            .map_err(DBusError::from)
+        self.retry(|| {
+            self.connection_path()
+                .get_playback_status()?
+                .parse()
+                .map_err(DBusError::from)
+        })
     }
 
     /// Query player for the state of the "Shuffle" setting.
@@ -1107,9 +1789,11 @@ impl<'a> Player<'a> {
     ///
     /// [shuffle]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Shuffle
     pub fn get_shuffle(&self) -> Result<bool, DBusError> {
-        self.connection_path()
-            .get_shuffle()
-            .map_err(DBusError::from)
+        self.retry(|| {
+            self.connection_path()
+                .get_shuffle()
+                .map_err(DBusError::from)
+        })
     }
 
     /// Gets the "Shuffle" setting, if the player indicates that it supports it.
@@ -1126,13 +1810,15 @@ impl<'a> Player<'a> {
 
     /// Set the "Shuffle" setting of the player.
     ///
+    /// Returns an error without sending anything if the player reports that it cannot be
+    /// controlled.
+    ///
     /// See: [MPRIS2 specification about `Shuffle`][shuffle].
     ///
     /// [shuffle]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Shuffle
     pub fn set_shuffle(&self, state: bool) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_shuffle(state)
-            .map_err(DBusError::from)
+        self.require_control()?;
+        self.write_shuffle(state)
     }
 
     /// Set the "Shuffle" setting of the player, if the player indicates that it supports the
@@ -1145,7 +1831,7 @@ impl<'a> Player<'a> {
     /// [shuffle]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Shuffle
     pub fn checked_set_shuffle(&self, state: bool) -> Result<bool, DBusError> {
         if self.can_control()? && self.can_shuffle()? {
-            self.set_shuffle(state)
+            self.write_shuffle(state)
                 .map(|_| true)
                 .map_err(DBusError::from)
         } else {
@@ -1159,10 +1845,12 @@ impl<'a> Player<'a> {
     ///
     /// [loop_status]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:LoopStatus
     pub fn get_loop_status(&self) -> Result<LoopStatus, DBusError> {
-        self.connection_path()
-            .get_loop_status()?
-            .parse()
-            .map_err(DBusError::from)
+        self.retry(|| {
+            self.connection_path()
+                .get_loop_status()?
+                .parse()
+                .map_err(DBusError::from)
+        })
     }
 
     /// Gets the "LoopStatus" setting, if the player indicates that it supports it.
@@ -1179,13 +1867,15 @@ impl<'a> Player<'a> {
 
     /// Set the loop status of the player.
     ///
+    /// Returns an error without sending anything if the player reports that it cannot be
+    /// controlled.
+    ///
     /// See: [MPRIS2 specification about  `LoopStatus`][loop_status].
     ///
     /// [loop_status]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:LoopStatus
     pub fn set_loop_status(&self, status: LoopStatus) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_loop_status(status.dbus_value())
-            .map_err(DBusError::from)
+        self.require_control()?;
+        self.write_loop_status(status)
     }
 
     /// Set the loop status of the player, if the player indicates that supports it and that it can
@@ -1198,7 +1888,7 @@ impl<'a> Player<'a> {
     /// [loop_status]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:LoopStatus
     pub fn checked_set_loop_status(&self, status: LoopStatus) -> Result<bool, DBusError> {
         if self.can_control()? && self.can_loop()? {
-            self.set_loop_status(status)
+            self.write_loop_status(status)
                 .map(|_| true)
                 .map_err(DBusError::from)
         } else {
@@ -1215,7 +1905,7 @@ impl<'a> Player<'a> {
     ///
     /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
     pub fn get_volume(&self) -> Result<f64, DBusError> {
-        self.connection_path().get_volume().map_err(DBusError::from)
+        self.retry(|| self.connection_path().get_volume().map_err(DBusError::from))
     }
 
     /// Gets the "Volume" setting, if the player indicates that it supports it.
@@ -1232,16 +1922,17 @@ impl<'a> Player<'a> {
 
     /// Set the volume of the player.
     ///
-    /// Volume should be between 0.0 and 1.0. Above 1.0 is possible, but not
-    /// recommended.
+    /// Use [`Volume::new`] or [`Volume::clamp`] to create the [`Volume`] from a number.
     ///
     /// See: [MPRIS2 specification about `Volume`][vol].
     ///
     /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
-    pub fn set_volume(&self, value: f64) -> Result<(), DBusError> {
-        self.connection_path()
-            .set_volume(value.max(0.0))
-            .map_err(DBusError::from)
+    pub fn set_volume(&self, volume: Volume) -> Result<(), DBusError> {
+        self.retry(|| {
+            self.connection_path()
+                .set_volume(volume.value())
+                .map_err(DBusError::from)
+        })
     }
 
     /// Set the "Volume" setting of the player, if the player indicates that it supports the
@@ -1252,7 +1943,7 @@ impl<'a> Player<'a> {
     /// See: [MPRIS2 specification about `Volume`][vol].
     ///
     /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
-    pub fn checked_set_volume(&self, volume: f64) -> Result<bool, DBusError> {
+    pub fn checked_set_volume(&self, volume: Volume) -> Result<bool, DBusError> {
         if self.can_control()? && self.has_volume()? {
             self.set_volume(volume)
                 .map(|_| true)
@@ -1265,23 +1956,139 @@ impl<'a> Player<'a> {
     /// Set the volume of the player, if the player indicates that it can be
     /// controlled.
     ///
-    /// Volume should be between 0.0 and 1.0. Above 1.0 is possible, but not
-    /// recommended.
-    ///
     /// See: [MPRIS2 specification about `Volume`][vol].
     ///
     /// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
-    pub fn set_volume_checked(&self, value: f64) -> Result<bool, DBusError> {
+    pub fn set_volume_checked(&self, volume: Volume) -> Result<bool, DBusError> {
         if self.can_control()? {
-            self.set_volume(value).map(|_| true)
+            self.set_volume(volume).map(|_| true)
         } else {
             Ok(false)
         }
     }
 
-    fn connection_path(&self) -> ConnPath<'_, &Connection> {
-        self.connection
-            .with_path(self.bus_name.clone(), self.path.clone(), self.timeout_ms)
+    /// Runs a call that can safely be sent more than once, like reading a property or `Play`, with
+    /// the rate limit, the retry policy and automatic reconnects.
+    fn retry<T, F>(&self, mut f: F) -> Result<T, DBusError>
+    where
+        F: FnMut() -> Result<T, DBusError>,
+    {
+        let mut f = || {
+            self.rate_limiter.wait();
+            f()
+        };
+
+        let mut reconnects = 0;
+        loop {
+            match self.retry_policy.run(&mut f) {
+                Err(error)
+                    if reconnects < self.auto_reconnect_attempts
+                        && is_connection_lost(&error, &self.connection()) =>
+                {
+                    reconnects += 1;
+                    // Report the error of the call rather than that of reconnecting.
+                    if self.replace_connection().is_err() {
+                        return Err(error);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Runs a call that must not be sent twice, like `Next` or `Seek`, with the rate limit only.
+    ///
+    /// When a call like this fails with a timeout, the player may still have acted on it, so it is
+    /// never sent again.
+    fn call_once<T, F>(&self, f: F) -> Result<T, DBusError>
+    where
+        F: FnOnce() -> Result<T, DBusError>,
+    {
+        self.rate_limiter.wait();
+        let result = f();
+        if let Err(ref error) = result {
+            if self.auto_reconnect_attempts > 0 && is_connection_lost(error, &self.connection()) {
+                // The call is not sent again, but later calls can use the new connection.
+                let _ = self.replace_connection();
+            }
+        }
+        result
+    }
+
+    /// Opens a new connection to the same bus, and moves the signal subscriptions of the player
+    /// over to it.
+    fn replace_connection(&self) -> Result<(), DBusError> {
+        let old = self.connection();
+        let new = old.reopen()?;
+        new.copy_subscriptions(&old, &self.unique_name, &self.unique_name);
+        self.connection.replace(Rc::new(new));
+        Ok(())
+    }
+
+    fn require_control(&self) -> Result<(), DBusError> {
+        if self.can_control()? {
+            Ok(())
+        } else {
+            Err(DBusError::Miscellaneous(format!(
+                "{} cannot be controlled",
+                self.bus_name
+            )))
+        }
+    }
+
+    // `set_shuffle` without checking `can_control`, for callers that have checked it already.
+    fn write_shuffle(&self, state: bool) -> Result<(), DBusError> {
+        self.retry(|| {
+            self.connection_path()
+                .set_shuffle(state)
+                .map_err(DBusError::from)
+        })
+    }
+
+    // `set_loop_status` without checking `can_control`, for callers that have checked it already.
+    fn write_loop_status(&self, status: LoopStatus) -> Result<(), DBusError> {
+        self.retry(|| {
+            self.connection_path()
+                .set_loop_status(status.dbus_value())
+                .map_err(DBusError::from)
+        })
+    }
+
+    fn require_track_list(&self) -> Result<(), DBusError> {
+        if self.supports_track_lists() {
+            Ok(())
+        } else {
+            Err(DBusError::Miscellaneous(format!(
+                "{} does not implement the org.mpris.MediaPlayer2.TrackList interface",
+                self.bus_name
+            )))
+        }
+    }
+
+    fn connection_path(&self) -> ConnPath<'_, SharedConnection> {
+        self.call_with_timeout(Call::Default)
+    }
+
+    /// Every call to the player goes through here, to get the timeout for its kind of call.
+    fn call_with_timeout(&self, call: Call) -> ConnPath<'_, SharedConnection> {
+        ConnPath {
+            conn: SharedConnection::new(self.connection()),
+            dest: self.bus_name.clone(),
+            path: self.path.clone(),
+            timeout: self.timeout_ms(call),
+        }
+    }
+
+    fn timeout_ms(&self, call: Call) -> i32 {
+        let config = match self.timeouts {
+            Timeouts::Millis(timeout_ms) => return timeout_ms,
+            Timeouts::Config(config) => config,
+        };
+        timeout::as_dbus_millis(match call {
+            Call::Default => config.default,
+            Call::GetTracksMetadata => config.get_tracks_metadata,
+            Call::Seek => config.seek,
+        })
     }
 
     /// Blocks until player gets an event on the bus.
@@ -1290,14 +2097,76 @@ impl<'a> Player<'a> {
     /// that this will block forever if player is not running. Make sure to check that the player
     /// is running before calling this method!
     pub(crate) fn process_events_blocking_until_received(&self) {
-        while !self.connection.has_pending_events(&self.unique_name) {
-            self.connection.process_events_blocking_until_received();
+        let connection = self.connection();
+        while !connection.has_pending_events(&self.unique_name) {
+            connection.process_events_blocking_until_received();
         }
     }
 
     /// Return any events that are pending (for this player) on the connection.
     pub(crate) fn pending_events(&self) -> Vec<MprisEvent> {
-        self.connection.pending_events(&self.unique_name)
+        self.connection().pending_events(&self.unique_name)
+    }
+}
+
+/// Players are equal when they are the same process on the bus, as identified by their
+/// [unique name](Player::unique_name). Two `Player`s found separately for the same player (even
+/// through different well-known names) compare equal.
+impl<'a> PartialEq for Player<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.unique_name == other.unique_name
+    }
+}
+
+impl<'a> Eq for Player<'a> {}
+
+/// Only the [unique name](Player::unique_name) is hashed, consistent with `PartialEq`.
+impl<'a> Hash for Player<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.unique_name.hash(state);
+    }
+}
+
+/// Returns `true` if a call failed because the connection to the bus was lost, rather than
+/// because the player did not answer in time.
+fn is_connection_lost(error: &DBusError, connection: &PooledConnection) -> bool {
+    let disconnected = match error {
+        DBusError::TransportError(error) => {
+            error.name() == Some("org.freedesktop.DBus.Error.Disconnected")
+        }
+        _ => false,
+    };
+    disconnected || !connection.is_connected()
+}
+
+fn invalid_args(message: String) -> DBusError {
+    dbus::Error::new_custom("org.freedesktop.DBus.Error.InvalidArgs", &message).into()
+}
+
+fn tracks_metadata<C: Deref<Target = Connection>>(
+    connection_path: &ConnPath<'_, C>,
+    track_ids: &[TrackID],
+) -> Result<Vec<Metadata>, DBusError> {
+    let mut method = connection_path.method_call_with_args(
+        &"org.mpris.MediaPlayer2.TrackList".into(),
+        &"GetTracksMetadata".into(),
+        |msg| {
+            let mut i = IterAppend::new(msg);
+            i.append(track_ids.iter().map(|id| id.as_path()).collect::<Vec<_>>());
+        },
+    )?;
+    method.as_result()?;
+    let mut i = method.iter_init();
+    let metadata: Vec<HashMap<String, MetadataValue>> = i.read()?;
+
+    if metadata.len() == track_ids.len() {
+        Ok(metadata.into_iter().map(Metadata::from).collect())
+    } else {
+        Err(DBusError::Miscellaneous(format!(
+            "Expected {} tracks, but got {} tracks returned.",
+            track_ids.len(),
+            metadata.len()
+        )))
     }
 }
 
@@ -1315,6 +2184,31 @@ fn handle_optional_property<T>(result: Result<T, dbus::Error>) -> Result<Option<
     result.map(Some).map_err(|e| e.into())
 }
 
+/// Finds the names of the properties of an interface in introspection XML.
+///
+/// Like [`has_tracklist_interface`], this looks for substrings instead of parsing the XML.
+fn introspected_property_names(xml: &str, interface: &str) -> Vec<String> {
+    let start_tag = format!("<interface name=\"{}\"", interface);
+    let body = match xml.find(&start_tag) {
+        Some(start) => {
+            let rest = &xml[start + start_tag.len()..];
+            if rest.trim_start().starts_with("/>") {
+                return Vec::new();
+            }
+            &rest[..rest.find("</interface>").unwrap_or(rest.len())]
+        }
+        None => return Vec::new(),
+    };
+
+    body.split("<property ")
+        .skip(1)
+        .filter_map(|property| {
+            let name = property.split("name=\"").nth(1)?;
+            name.split('"').next().map(String::from)
+        })
+        .collect()
+}
+
 /// Checks if the Player implements the `org.mpris.MediaPlayer2.TrackList` interface.
 fn has_tracklist_interface(connection: ConnPath<'_, &Connection>) -> Result<bool, DBusError> {
     // Get the introspection XML and look for the substring instead of parsing the XML. Yeah,
@@ -1327,3 +2221,604 @@ fn has_tracklist_interface(connection: ConnPath<'_, &Connection>) -> Result<bool
     let xml: String = connection.introspect()?;
     Ok(xml.contains("org.mpris.MediaPlayer2.TrackList"))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    use std::rc::Rc;
+
+    use crate::fake_player::{FakePlayer, TestBus};
+    use dbus::Message;
+    use std::time::Duration;
+
+    use super::{introspected_property_names, is_connection_lost, MPRIS2_PATH, MPRIS2_PREFIX};
+
+    use crate::{
+        DBusError, LoopStatus, OpenUriError, PlaylistOrdering, RateError, RateLimitPolicy,
+        RetryPolicy, TimeoutConfig, TrackID,
+    };
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn same_process_is_equal() {
+        let fake = FakePlayer::spawn();
+        let first = fake.find();
+        let second = fake.find();
+
+        assert_eq!(first, second);
+        assert_eq!(hash_of(&first), hash_of(&second));
+    }
+
+    #[test]
+    fn different_processes_are_not_equal() {
+        let one = FakePlayer::spawn();
+        let other = FakePlayer::spawn();
+
+        assert_ne!(one.find(), other.find());
+    }
+
+    #[test]
+    fn timeout_config_replaces_the_single_timeout() {
+        let fake = FakePlayer::spawn();
+        let config = TimeoutConfig {
+            get_tracks_metadata: Duration::from_secs(5),
+            ..TimeoutConfig::default_all(Duration::from_millis(300))
+        };
+        let mut player = fake.find().with_timeout_config(config);
+
+        assert_eq!(player.timeout_config(), config);
+        assert_eq!(player.dbus_timeout_ms(), 300);
+        assert!(player.can_play().unwrap());
+
+        player.set_dbus_timeout_ms(100);
+        assert_eq!(
+            player.timeout_config(),
+            TimeoutConfig::default_all(Duration::from_millis(100))
+        );
+
+        player.set_dbus_timeout_ms(-1);
+        assert_eq!(player.dbus_timeout_ms(), -1);
+        assert_eq!(
+            player.timeout_config(),
+            TimeoutConfig::default_all(Duration::from_secs(25))
+        );
+    }
+
+    #[test]
+    fn timeout_config_applies_per_kind_of_call() {
+        let fake = FakePlayer::spawn_ignoring(&["Play", "GetTracksMetadata", "Seek"]);
+        let player = fake.find().with_timeout_config(TimeoutConfig {
+            get_tracks_metadata: Duration::from_millis(400),
+            seek: Duration::from_millis(400),
+            ..TimeoutConfig::default_all(Duration::from_millis(50))
+        });
+        let ids = [TrackID::new("/path/1").unwrap()];
+        let time = |call: &dyn Fn() -> bool| {
+            let start = std::time::Instant::now();
+            assert!(call(), "The call should have timed out");
+            start.elapsed()
+        };
+
+        assert!(time(&|| player.play().is_err()) < Duration::from_millis(400));
+        assert!(time(&|| player.get_tracks_metadata(&ids).is_err()) >= Duration::from_millis(400));
+        assert!(time(&|| player.seek(1).is_err()) >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn pid_is_the_owning_process() {
+        let fake = FakePlayer::spawn();
+
+        // The fake player runs on a thread of the test process.
+        assert_eq!(fake.find().pid().unwrap(), std::process::id());
+    }
+
+    #[test]
+    fn get_capabilities_reads_both_interfaces() {
+        let fake = FakePlayer::spawn();
+        let capabilities = fake.find().get_capabilities().unwrap();
+
+        assert!(capabilities.can_play);
+        assert!(capabilities.can_quit);
+        assert!(!capabilities.can_raise);
+        assert!(!capabilities.can_seek);
+    }
+
+    #[test]
+    fn track_list_methods_require_the_interface() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let track = TrackID::new("/fake/track/1").unwrap();
+
+        assert!(!player.supports_track_lists());
+        for result in [
+            player.go_to(&track),
+            player.add_track("file:///tmp/fake.ogg", &track, false),
+            player.add_track_at_start("file:///tmp/fake.ogg", true),
+            player.remove_track(&track),
+        ] {
+            match result {
+                Err(DBusError::Miscellaneous(message)) => assert!(message.contains("TrackList")),
+                other => panic!("Expected a missing interface error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn it_reads_playlists() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert_eq!(player.get_playlist_count().unwrap(), 1);
+        assert_eq!(
+            player.get_playlist_orderings().unwrap(),
+            vec![PlaylistOrdering::Alphabetical]
+        );
+
+        let playlists = player
+            .get_playlists(0, 10, PlaylistOrdering::Alphabetical, false)
+            .unwrap();
+        assert_eq!(playlists.len(), 1);
+        assert_eq!(playlists[0].name, "Fake playlist");
+        assert_eq!(
+            player.get_active_playlist().unwrap(),
+            Some(playlists[0].clone())
+        );
+        player.activate_playlist(&playlists[0].id).unwrap();
+    }
+
+    #[test]
+    fn checked_raise_and_quit_follow_capabilities() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert!(!player.checked_raise().unwrap());
+        assert!(player.checked_quit().unwrap());
+        player.raise().unwrap();
+    }
+
+    #[test]
+    fn fullscreen_is_optional() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert!(!player.can_set_fullscreen().unwrap());
+        assert_eq!(player.get_fullscreen().unwrap(), None);
+        assert!(!player.checked_set_fullscreen(true).unwrap());
+    }
+
+    #[test]
+    fn it_reads_root_properties() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert_eq!(player.identity(), "Fake player");
+        assert_eq!(player.get_desktop_entry().unwrap(), None);
+        assert_eq!(
+            player.get_supported_uri_schemes().unwrap(),
+            vec![String::from("file")]
+        );
+        assert!(player.get_supported_mime_types().unwrap().is_empty());
+    }
+
+    #[test]
+    fn set_playback_rate_in_range_checks_the_range() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        player.set_playback_rate_in_range(1.5).unwrap();
+        player.set_playback_rate_in_range(0.5).unwrap();
+        match player.set_playback_rate_in_range(3.0) {
+            Err(RateError::OutOfRange { rate, min, max }) => {
+                assert_eq!((rate, min, max), (3.0, 0.5, 2.0))
+            }
+            other => panic!("Expected an out of range error, got {:?}", other),
+        }
+        assert!(player.set_playback_rate_in_range(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn open_uri_checked_checks_the_scheme() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert!(player.can_open_uri().unwrap());
+        assert!(player.open_uri("").is_err());
+        player.open_uri("http://example.com/stream").unwrap();
+        player.open_uri_checked("FILE:///tmp/fake.ogg").unwrap();
+        match player.open_uri_checked("http://example.com/stream") {
+            Err(OpenUriError::UnsupportedScheme(scheme)) => assert_eq!(scheme, "http"),
+            other => panic!("Expected an unsupported scheme error, got {:?}", other),
+        }
+        assert!(matches!(
+            player.open_uri_checked("no scheme"),
+            Err(OpenUriError::UnsupportedScheme(_))
+        ));
+    }
+
+    #[test]
+    fn reconnect_opens_a_new_connection() {
+        let fake = FakePlayer::spawn();
+        let mut player = fake.find().with_event_history(4);
+        let old = player.connection();
+
+        player.reconnect().unwrap();
+        assert!(!Rc::ptr_eq(&old, &player.connection()));
+        assert_eq!(player.identity(), "Fake player");
+        assert!(player.get_playback_status().is_ok());
+
+        // The history follows the player to the new connection.
+        fake.emit(
+            Message::new_signal(MPRIS2_PATH, "org.mpris.MediaPlayer2.Player", "Seeked")
+                .unwrap()
+                .append1(5_000_000i64),
+        );
+        player
+            .connection()
+            .process_events_blocking_for(Duration::from_millis(200));
+        assert_eq!(player.event_history().len(), 1);
+    }
+
+    #[test]
+    fn auto_reconnect_only_retries_lost_connections() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let error = |name| DBusError::from(dbus::Error::new_custom(name, "message"));
+
+        let connection = player.connection();
+        assert!(is_connection_lost(
+            &error("org.freedesktop.DBus.Error.Disconnected"),
+            &connection
+        ));
+        assert!(!is_connection_lost(
+            &error("org.freedesktop.DBus.Error.NoReply"),
+            &connection
+        ));
+        assert!(!is_connection_lost(
+            &error("org.freedesktop.DBus.Error.InvalidArgs"),
+            &connection
+        ));
+    }
+
+    #[test]
+    fn auto_reconnect_sends_calls_again_after_the_bus_restarted() {
+        let mut bus = TestBus::start();
+        let bus_name = format!("{}mpris_rs_test.reconnect", MPRIS2_PREFIX);
+        let fake = FakePlayer::spawn_on(&bus, &bus_name);
+        let player = fake.find().with_auto_reconnect(1);
+        let first_connection = player.connection();
+
+        drop(fake);
+        bus.restart();
+        let fake = FakePlayer::spawn_on(&bus, &bus_name);
+
+        assert_eq!(
+            player.get_playback_status().unwrap(),
+            crate::PlaybackStatus::Playing
+        );
+        assert!(!Rc::ptr_eq(&player.connection(), &first_connection));
+        assert!(fake.received_calls().iter().any(|call| call == "Get"));
+
+        drop(fake);
+        bus.restart();
+        let fake = FakePlayer::spawn_on(&bus, &bus_name);
+
+        // Next is not sent again on the new connection, but later calls use it.
+        assert!(player.next().is_err());
+        player.play().unwrap();
+        let calls = fake.received_calls();
+        assert!(!calls.iter().any(|call| call == "Next"));
+        assert!(calls.iter().any(|call| call == "Play"));
+    }
+
+    #[test]
+    fn rate_limit_spaces_out_calls() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find().with_rate_limit(RateLimitPolicy {
+            calls_per_second: 20,
+        });
+
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            player.get_playback_status().unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn rate_limit_applies_to_calls_to_the_bus() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find().with_rate_limit(RateLimitPolicy {
+            calls_per_second: 20,
+        });
+
+        let start = std::time::Instant::now();
+        assert!(player.is_running());
+        player.pid().unwrap();
+        assert!(player.is_running());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn rate_limit_applies_to_each_chunk_of_metadata() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find().with_rate_limit(RateLimitPolicy {
+            calls_per_second: 20,
+        });
+        let ids: Vec<TrackID> = (1..=3)
+            .map(|n| TrackID::new(format!("/path/{}", n)).unwrap())
+            .collect();
+
+        let start = std::time::Instant::now();
+        let metadata = player.get_tracks_metadata_in_chunks(&ids, 1).unwrap();
+
+        assert_eq!(metadata.len(), 3);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn toggle_play_depends_on_the_playback_status() {
+        for &(status, expected) in &[
+            ("Playing", Some("Pause")),
+            ("Paused", Some("Play")),
+            ("Stopped", None),
+        ] {
+            let fake = FakePlayer::spawn_with_status(status);
+            fake.find().toggle_play().unwrap();
+
+            let calls = fake.received_calls();
+            let sent = calls
+                .iter()
+                .find(|call| *call == "Play" || *call == "Pause");
+            assert_eq!(sent.map(String::as_str), expected, "{}", status);
+        }
+    }
+
+    #[test]
+    fn get_current_track_id_reads_the_metadata() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert_eq!(
+            player.get_current_track_id().unwrap(),
+            Some(TrackID::new("/fake/track/1").unwrap())
+        );
+    }
+
+    #[test]
+    fn shuffle_can_be_read_and_set() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert!(player.can_shuffle().unwrap());
+        assert!(player.get_shuffle().unwrap());
+        assert_eq!(player.checked_get_shuffle().unwrap(), Some(true));
+        assert!(player.checked_set_shuffle(false).unwrap());
+        assert!(fake.received_calls().iter().any(|call| call == "Set"));
+    }
+
+    #[test]
+    fn set_shuffle_writes_the_property() {
+        let fake = FakePlayer::spawn();
+
+        fake.find().set_shuffle(false).unwrap();
+
+        assert!(fake.received_calls().iter().any(|call| call == "Set"));
+    }
+
+    #[test]
+    fn set_shuffle_requires_control() {
+        let fake = FakePlayer::spawn_uncontrollable();
+
+        match fake.find().set_shuffle(false) {
+            Err(DBusError::Miscellaneous(message)) => {
+                assert!(message.contains("cannot be controlled"))
+            }
+            other => panic!("Expected a Miscellaneous error, got {:?}", other),
+        }
+        assert!(!fake.received_calls().iter().any(|call| call == "Set"));
+    }
+
+    #[test]
+    fn checked_set_shuffle_checks_control_once() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let before = fake.received_calls().len();
+
+        assert!(player.checked_set_shuffle(false).unwrap());
+
+        // `CanControl`, `CanShuffle` and then the `Set` itself; `CanControl` is not read twice.
+        assert_eq!(
+            fake.received_calls()[before..].to_vec(),
+            vec!["Get", "GetAll", "Set"]
+        );
+    }
+
+    #[test]
+    fn set_loop_status_requires_control() {
+        let fake = FakePlayer::spawn_uncontrollable();
+
+        match fake.find().set_loop_status(LoopStatus::None) {
+            Err(DBusError::Miscellaneous(message)) => {
+                assert!(message.contains("cannot be controlled"))
+            }
+            other => panic!("Expected a Miscellaneous error, got {:?}", other),
+        }
+        assert!(!fake.received_calls().iter().any(|call| call == "Set"));
+    }
+
+    #[test]
+    fn loop_status_can_be_read_and_set() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert_eq!(player.get_loop_status().unwrap(), LoopStatus::Track);
+        assert_eq!(
+            player.checked_get_loop_status().unwrap(),
+            Some(LoopStatus::Track)
+        );
+        assert!(player
+            .checked_set_loop_status(LoopStatus::Playlist)
+            .unwrap());
+        assert!(fake.received_calls().iter().any(|call| call == "Set"));
+    }
+
+    #[test]
+    fn get_property_names_reads_the_introspection_data() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        assert_eq!(
+            player
+                .get_property_names("org.mpris.MediaPlayer2.Player")
+                .unwrap(),
+            vec!["PlaybackStatus", "Volume"]
+        );
+        assert!(player
+            .get_property_names("org.mpris.MediaPlayer2")
+            .unwrap()
+            .is_empty());
+        assert!(player
+            .get_property_names("org.mpris.MediaPlayer2.TrackList")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn introspected_property_names_are_scoped_to_the_interface() {
+        let xml = r#"<node>
+            <interface name="org.mpris.MediaPlayer2">
+                <property name="Identity" type="s" access="read"/>
+            </interface>
+            <interface name="org.mpris.MediaPlayer2.Player">
+                <method name="Play"/>
+                <property name="Shuffle" type="b" access="readwrite">
+                    <annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="true"/>
+                </property>
+                <property name="Rate" type="d" access="readwrite"/>
+            </interface>
+        </node>"#;
+
+        assert_eq!(
+            introspected_property_names(xml, "org.mpris.MediaPlayer2.Player"),
+            vec!["Shuffle", "Rate"]
+        );
+        assert_eq!(
+            introspected_property_names(xml, "org.mpris.MediaPlayer2"),
+            vec!["Identity"]
+        );
+    }
+
+    #[test]
+    fn get_property_raw_matches_identity() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        let identity = player
+            .get_property_raw("org.mpris.MediaPlayer2", "Identity")
+            .unwrap();
+        assert_eq!(identity.as_str(), Some(player.identity()));
+    }
+
+    #[test]
+    fn call_method_raw_returns_reply() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+
+        let reply = player
+            .call_method_raw(
+                "org.freedesktop.DBus.Properties",
+                "Get",
+                ("org.mpris.MediaPlayer2", "Identity"),
+            )
+            .unwrap();
+        let identity: dbus::arg::Variant<String> = reply.read1().unwrap();
+        assert_eq!(identity.0, player.identity());
+
+        assert!(player
+            .call_method_raw("org.example.Unknown", "NoSuchMethod", ())
+            .is_err());
+        assert!(player
+            .call_method_raw("not an interface", "Get", ())
+            .is_err());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn it_loads_metadata_in_chunks_in_order() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let ids: Vec<TrackID> = (1..=20)
+            .map(|n| TrackID::new(format!("/path/{}", n)).unwrap())
+            .collect();
+
+        let metadata = player.get_tracks_metadata_in_chunks(&ids, 3).unwrap();
+
+        let loaded: Vec<TrackID> = metadata.iter().filter_map(|m| m.track_id()).collect();
+        assert_eq!(loaded, ids);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn it_loads_chunks_over_connections_of_an_unknown_bus() {
+        let bus = TestBus::start();
+        let fake = FakePlayer::spawn_on(&bus, "org.mpris.MediaPlayer2.mpris_rs_test.chunks");
+        let connection = dbus::ffidisp::Connection::open_private(&bus.address()).unwrap();
+        connection.register().unwrap();
+        // Nothing tells the player that this connection is not on the session bus.
+        let player =
+            super::Player::new(connection, fake.bus_name().to_string(), MPRIS2_PATH, 500).unwrap();
+        let ids: Vec<TrackID> = (1..=5)
+            .map(|n| TrackID::new(format!("/path/{}", n)).unwrap())
+            .collect();
+
+        let metadata = player.get_tracks_metadata_in_chunks(&ids, 2).unwrap();
+
+        assert_eq!(metadata.len(), 5);
+        let calls = fake.received_calls();
+        let count = calls
+            .iter()
+            .filter(|call| *call == "GetTracksMetadata")
+            .count();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn retry_policy_only_resends_idempotent_calls() {
+        let fake = FakePlayer::spawn_ignoring(&["Play", "Next"]);
+        let player = fake
+            .find()
+            .with_timeout_config(TimeoutConfig::default_all(Duration::from_millis(50)))
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 3,
+                delay: Duration::from_millis(0),
+            });
+
+        assert!(player.play().is_err());
+        assert!(player.next().is_err());
+
+        let calls = fake.received_calls();
+        let count = |method: &str| calls.iter().filter(|call| *call == method).count();
+        assert_eq!(count("Play"), 3);
+        assert_eq!(count("Next"), 1);
+    }
+
+    #[test]
+    fn is_running_until_name_is_released() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        assert!(player.is_running());
+
+        drop(fake);
+        assert!(!player.is_running());
+        assert!(player.gone.get());
+        assert!(!player.is_running());
+    }
+}
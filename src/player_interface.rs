@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use super::{DBusError, Metadata, PlaybackStatus, Player, Volume};
+
+/// The most common controls and queries of a [`Player`], as a trait.
+///
+/// Write code that controls a player against this trait instead of against [`Player`], and it
+/// can be tested with the `test_util::MockPlayer` of the `test_util` feature instead of a real
+/// player on D-Bus.
+///
+/// ```rust,no_run
+/// use mpris::{PlaybackStatus, PlayerFinder, PlayerInterface};
+///
+/// fn toggle<P: PlayerInterface>(player: &P) {
+///     match player.get_playback_status() {
+///         Ok(PlaybackStatus::Playing) => player.pause().unwrap(),
+///         _ => player.play().unwrap(),
+///     }
+/// }
+///
+/// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// toggle(&player);
+/// ```
+///
+/// Every method does the same as the [`Player`] method with the same name.
+pub trait PlayerInterface {
+    /// See [`Player::identity`].
+    fn identity(&self) -> &str;
+
+    /// See [`Player::play`].
+    fn play(&self) -> Result<(), DBusError>;
+
+    /// See [`Player::pause`].
+    fn pause(&self) -> Result<(), DBusError>;
+
+    /// See [`Player::play_pause`].
+    fn play_pause(&self) -> Result<(), DBusError>;
+
+    /// See [`Player::stop`].
+    fn stop(&self) -> Result<(), DBusError>;
+
+    /// See [`Player::next`].
+    fn next(&self) -> Result<(), DBusError>;
+
+    /// See [`Player::previous`].
+    fn previous(&self) -> Result<(), DBusError>;
+
+    /// See [`Player::seek`].
+    fn seek(&self, offset_in_microseconds: i64) -> Result<(), DBusError>;
+
+    /// See [`Player::get_playback_status`].
+    fn get_playback_status(&self) -> Result<PlaybackStatus, DBusError>;
+
+    /// See [`Player::get_metadata`].
+    fn get_metadata(&self) -> Result<Metadata, DBusError>;
+
+    /// See [`Player::get_position`].
+    fn get_position(&self) -> Result<Duration, DBusError>;
+
+    /// See [`Player::get_volume`].
+    fn get_volume(&self) -> Result<f64, DBusError>;
+
+    /// See [`Player::set_volume`].
+    fn set_volume(&self, volume: Volume) -> Result<(), DBusError>;
+}
+
+impl<'a> PlayerInterface for Player<'a> {
+    fn identity(&self) -> &str {
+        Player::identity(self)
+    }
+
+    fn play(&self) -> Result<(), DBusError> {
+        Player::play(self)
+    }
+
+    fn pause(&self) -> Result<(), DBusError> {
+        Player::pause(self)
+    }
+
+    fn play_pause(&self) -> Result<(), DBusError> {
+        Player::play_pause(self)
+    }
+
+    fn stop(&self) -> Result<(), DBusError> {
+        Player::stop(self)
+    }
+
+    fn next(&self) -> Result<(), DBusError> {
+        Player::next(self)
+    }
+
+    fn previous(&self) -> Result<(), DBusError> {
+        Player::previous(self)
+    }
+
+    fn seek(&self, offset_in_microseconds: i64) -> Result<(), DBusError> {
+        Player::seek(self, offset_in_microseconds)
+    }
+
+    fn get_playback_status(&self) -> Result<PlaybackStatus, DBusError> {
+        Player::get_playback_status(self)
+    }
+
+    fn get_metadata(&self) -> Result<Metadata, DBusError> {
+        Player::get_metadata(self)
+    }
+
+    fn get_position(&self) -> Result<Duration, DBusError> {
+        Player::get_position(self)
+    }
+
+    fn get_volume(&self) -> Result<f64, DBusError> {
+        Player::get_volume(self)
+    }
+
+    fn set_volume(&self, volume: Volume) -> Result<(), DBusError> {
+        Player::set_volume(self, volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::FakePlayer;
+
+    fn status_of<P: PlayerInterface>(player: &P) -> PlaybackStatus {
+        player.get_playback_status().unwrap()
+    }
+
+    #[test]
+    fn players_implement_the_interface() {
+        let fake = FakePlayer::spawn_with_status("Paused");
+        let player = fake.find();
+
+        assert_eq!(status_of(&player), PlaybackStatus::Paused);
+        PlayerInterface::play(&player).unwrap();
+        assert!(fake.received_calls().iter().any(|call| call == "Play"));
+    }
+}
@@ -0,0 +1,359 @@
+use std::any::Any;
+use std::fmt;
+
+use dbus::arg::{Append, Arg, ArgType, Get, Iter, IterAppend, RefArg};
+use dbus::Signature;
+use failure::Fail;
+
+use super::DBusError;
+
+/// Represents [the MPRIS `Playlist_Id` type][playlist_id].
+///
+/// ```rust
+/// use mpris::PlaylistID;
+/// let id = PlaylistID::new("/org/example/Playlist/1").unwrap();
+/// ```
+///
+/// Like [`TrackID`](crate::TrackID)s, PlaylistIDs must be valid D-Bus object paths according to
+/// the spec. They are ordered by their path, lexicographically.
+///
+/// # Errors
+///
+/// Trying to construct a [`PlaylistID`] from a string that is not a valid D-Bus Path will fail.
+///
+/// ```rust
+/// # use mpris::PlaylistID;
+/// let result = PlaylistID::new("invalid playlist ID");
+/// assert!(result.is_err());
+/// ```
+///
+/// [playlist_id]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Simple-Type:Playlist_Id
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlaylistID(String);
+
+/// A playlist of a [`Player`](crate::Player), as returned by its `Playlists` interface.
+///
+/// See: [MPRIS2 specification about `Playlist`][playlist].
+///
+/// [playlist]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Struct:Playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playlist {
+    /// A unique identifier for the playlist.
+    pub id: PlaylistID,
+    /// The name of the playlist, as it should be shown to the user.
+    pub name: String,
+    /// The URI of an (optional) icon; empty if the playlist has no icon.
+    pub icon: String,
+}
+
+/// The active playlist of a [`Player`](crate::Player), if there is one.
+///
+/// On D-Bus, this is the `(b(oss))` struct of the `ActivePlaylist` property: a boolean that tells
+/// if a playlist is active, followed by that playlist. When no playlist is active, a placeholder
+/// playlist with the ID `/` and an empty name and icon is sent along.
+///
+/// See: [MPRIS2 specification about `Maybe_Playlist`][maybe_playlist].
+///
+/// [maybe_playlist]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Struct:Maybe_Playlist
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MaybePlaylist(Option<Playlist>);
+
+/// Orderings that a [`Player`](crate::Player) might support for
+/// [`get_playlists`](crate::Player::get_playlists).
+///
+/// See: [MPRIS2 specification about `Playlist_Ordering`][ordering]
+///
+/// [ordering]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Enum:Playlist_Ordering
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PlaylistOrdering {
+    /// Alphabetical ordering by name, ascending.
+    Alphabetical,
+
+    /// Ordering by creation date, oldest first.
+    CreationDate,
+
+    /// Ordering by last modified date, oldest first.
+    ModifiedDate,
+
+    /// Ordering by date of last playback, oldest first.
+    LastPlayDate,
+
+    /// A user-defined ordering.
+    UserDefined,
+}
+
+/// [`PlaylistOrdering`] had an invalid string value.
+#[derive(Fail, Debug)]
+#[fail(
+    display = "PlaylistOrdering must be one of Alphabetical, Created, Modified, Played, User, but was {}",
+    _0
+)]
+pub struct InvalidPlaylistOrdering(String);
+
+pub(crate) type RawPlaylist = (dbus::Path<'static>, String, String);
+
+impl PlaylistID {
+    /// Create a new [`PlaylistID`] from a string-like entity.
+    ///
+    /// Like with [`TrackID::new`](crate::TrackID::new), this is mostly useful for tests and
+    /// comparisons as the Player decides what the IDs are.
+    pub fn new<S: Into<String>>(id: S) -> Result<Self, String> {
+        let id = id.into();
+        // Validate the ID by constructing a dbus::Path.
+        if let Err(error) = dbus::Path::new(id.as_str()) {
+            Err(error)
+        } else {
+            Ok(PlaylistID(id))
+        }
+    }
+
+    /// Returns a `&str` variant of the ID.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub(crate) fn as_path(&self) -> dbus::Path<'_> {
+        // All inputs to this class should be validated to work with [`dbus::Path`], so unwrapping
+        // should be safe here.
+        dbus::Path::new(self.as_str()).unwrap()
+    }
+}
+
+impl<'a> From<dbus::Path<'a>> for PlaylistID {
+    fn from(path: dbus::Path<'a>) -> PlaylistID {
+        PlaylistID(path.to_string())
+    }
+}
+
+impl From<PlaylistID> for String {
+    fn from(id: PlaylistID) -> String {
+        id.0
+    }
+}
+
+impl<'a> From<&'a PlaylistID> for dbus::Path<'a> {
+    fn from(id: &'a PlaylistID) -> dbus::Path<'a> {
+        id.as_path()
+    }
+}
+
+impl fmt::Display for PlaylistID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A [`PlaylistID`] is serialized as its path string.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PlaylistID {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Deserializing fails if the string is not a valid D-Bus path, just like [`PlaylistID::new`].
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PlaylistID {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let id = String::deserialize(deserializer)?;
+        PlaylistID::new(id).map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<RawPlaylist> for Playlist {
+    fn from((id, name, icon): RawPlaylist) -> Self {
+        Playlist {
+            id: PlaylistID::from(id),
+            name,
+            icon,
+        }
+    }
+}
+
+impl From<&Playlist> for RawPlaylist {
+    fn from(playlist: &Playlist) -> Self {
+        (
+            playlist.id.as_path().into_static(),
+            playlist.name.clone(),
+            playlist.icon.clone(),
+        )
+    }
+}
+
+impl MaybePlaylist {
+    /// Returns the active playlist, or [`None`] if no playlist is active.
+    pub fn active(&self) -> Option<&Playlist> {
+        self.0.as_ref()
+    }
+
+    /// Returns the active playlist, or [`None`] if no playlist is active.
+    pub fn into_active(self) -> Option<Playlist> {
+        self.0
+    }
+
+    pub(crate) fn from_dbus_value((valid, playlist): (bool, RawPlaylist)) -> Self {
+        if valid {
+            MaybePlaylist(Some(Playlist::from(playlist)))
+        } else {
+            MaybePlaylist(None)
+        }
+    }
+
+    fn dbus_value(&self) -> (bool, RawPlaylist) {
+        match self.0 {
+            Some(ref playlist) => (true, RawPlaylist::from(playlist)),
+            None => (false, (dbus::Path::from("/"), String::new(), String::new())),
+        }
+    }
+}
+
+impl From<Option<Playlist>> for MaybePlaylist {
+    fn from(playlist: Option<Playlist>) -> Self {
+        MaybePlaylist(playlist)
+    }
+}
+
+impl From<MaybePlaylist> for Option<Playlist> {
+    fn from(playlist: MaybePlaylist) -> Self {
+        playlist.0
+    }
+}
+
+impl Arg for MaybePlaylist {
+    const ARG_TYPE: ArgType = ArgType::Struct;
+
+    fn signature() -> Signature<'static> {
+        Signature::from("(b(oss))")
+    }
+}
+
+impl Append for MaybePlaylist {
+    fn append_by_ref(&self, iter: &mut IterAppend<'_>) {
+        self.dbus_value().append(iter);
+    }
+}
+
+impl<'a> Get<'a> for MaybePlaylist {
+    fn get(iter: &mut Iter<'a>) -> Option<Self> {
+        <(bool, RawPlaylist)>::get(iter).map(MaybePlaylist::from_dbus_value)
+    }
+}
+
+impl RefArg for MaybePlaylist {
+    fn arg_type(&self) -> ArgType {
+        <Self as Arg>::ARG_TYPE
+    }
+
+    fn signature(&self) -> Signature<'static> {
+        <Self as Arg>::signature()
+    }
+
+    fn append(&self, iter: &mut IterAppend<'_>) {
+        self.append_by_ref(iter);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn box_clone(&self) -> Box<dyn RefArg + 'static> {
+        Box::new(self.clone())
+    }
+}
+
+impl ::std::str::FromStr for PlaylistOrdering {
+    type Err = InvalidPlaylistOrdering;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        match string {
+            "Alphabetical" => Ok(PlaylistOrdering::Alphabetical),
+            "Created" => Ok(PlaylistOrdering::CreationDate),
+            "Modified" => Ok(PlaylistOrdering::ModifiedDate),
+            "Played" => Ok(PlaylistOrdering::LastPlayDate),
+            "User" => Ok(PlaylistOrdering::UserDefined),
+            other => Err(InvalidPlaylistOrdering(other.to_string())),
+        }
+    }
+}
+
+impl PlaylistOrdering {
+    pub(crate) fn dbus_value(self) -> &'static str {
+        match self {
+            PlaylistOrdering::Alphabetical => "Alphabetical",
+            PlaylistOrdering::CreationDate => "Created",
+            PlaylistOrdering::ModifiedDate => "Modified",
+            PlaylistOrdering::LastPlayDate => "Played",
+            PlaylistOrdering::UserDefined => "User",
+        }
+    }
+}
+
+impl From<InvalidPlaylistOrdering> for DBusError {
+    fn from(error: InvalidPlaylistOrdering) -> Self {
+        DBusError::EnumParseError(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_ids() {
+        assert!(PlaylistID::new("/org/example/Playlist/1").is_ok());
+        assert!(PlaylistID::new("not a path").is_err());
+    }
+
+    #[test]
+    fn it_round_trips_orderings() {
+        for ordering in &[
+            PlaylistOrdering::Alphabetical,
+            PlaylistOrdering::CreationDate,
+            PlaylistOrdering::ModifiedDate,
+            PlaylistOrdering::LastPlayDate,
+            PlaylistOrdering::UserDefined,
+        ] {
+            assert_eq!(
+                ordering.dbus_value().parse::<PlaylistOrdering>().unwrap(),
+                *ordering
+            );
+        }
+        assert!("Random".parse::<PlaylistOrdering>().is_err());
+    }
+
+    #[test]
+    fn it_round_trips_maybe_playlists_through_dbus() {
+        let playlist = Playlist {
+            id: PlaylistID::new("/org/example/Playlist/1").unwrap(),
+            name: String::from("Favorites"),
+            icon: String::new(),
+        };
+
+        for maybe in &[
+            MaybePlaylist::from(Some(playlist.clone())),
+            MaybePlaylist::default(),
+        ] {
+            let message = dbus::Message::new_signal("/test", "org.example.Test", "Test")
+                .unwrap()
+                .append1(maybe.clone());
+            assert_eq!(message.iter_init().signature(), Signature::from("(b(oss))"));
+            assert_eq!(message.get1::<MaybePlaylist>().as_ref(), Some(maybe));
+        }
+
+        assert_eq!(
+            MaybePlaylist::from(Some(playlist.clone())).active(),
+            Some(&playlist)
+        );
+        assert_eq!(MaybePlaylist::default().into_active(), None);
+    }
+}
@@ -0,0 +1,170 @@
+use super::{DBusError, PlaybackStatus, Player, PlayerFinder};
+
+/// All [`Player`]s that a [`PlayerFinder`] can find, for controlling them together.
+///
+/// The pool is a snapshot; call [`refresh`](Self::refresh) to pick up players that appeared and
+/// drop the ones that quit since. Players are found like [`PlayerFinder::find_all`] would,
+/// including its filters, but players that fail to load are skipped instead of failing the whole
+/// pool.
+///
+/// ```rust,no_run
+/// use mpris::{PlayerFinder, PlayerPool};
+///
+/// let finder = PlayerFinder::new().expect("Could not connect to D-Bus");
+/// let pool = PlayerPool::from_finder(&finder).expect("Could not list players");
+/// pool.broadcast_pause().expect("Could not pause every player");
+/// ```
+#[derive(Debug)]
+pub struct PlayerPool<'a> {
+    finder: &'a PlayerFinder,
+    players: Vec<Player<'static>>,
+}
+
+impl<'a> PlayerPool<'a> {
+    /// Creates a pool with every player that the finder can currently find.
+    pub fn from_finder(finder: &'a PlayerFinder) -> Result<Self, DBusError> {
+        let mut pool = PlayerPool {
+            finder,
+            players: Vec::new(),
+        };
+        pool.refresh()?;
+        Ok(pool)
+    }
+
+    /// The players in the pool, in the order they were found.
+    pub fn players(&self) -> &[Player<'static>] {
+        &self.players
+    }
+
+    /// Returns the number of players in the pool.
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    /// If the pool has no players.
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Removes players that have quit, and adds players that appeared since the pool was created
+    /// or last refreshed.
+    pub fn refresh(&mut self) -> Result<(), DBusError> {
+        let bus_names = self.finder.all_player_buses()?;
+
+        self.players.retain(|player| {
+            bus_names.iter().any(|name| name == &**player.bus_name()) && player.is_running()
+        });
+
+        for bus_name in bus_names {
+            let known = self
+                .players
+                .iter()
+                .any(|player| *bus_name == **player.bus_name());
+            if !known {
+                self.players
+                    .extend(self.finder.player_for_new_bus(bus_name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a `Play` signal to every player in the pool.
+    ///
+    /// Every player gets the signal even if some of them fail; the first error is returned.
+    pub fn broadcast_play(&self) -> Result<(), DBusError> {
+        self.broadcast(Player::play)
+    }
+
+    /// Sends a `Pause` signal to every player in the pool.
+    ///
+    /// Every player gets the signal even if some of them fail; the first error is returned.
+    pub fn broadcast_pause(&self) -> Result<(), DBusError> {
+        self.broadcast(Player::pause)
+    }
+
+    /// Sends a `Stop` signal to every player in the pool.
+    ///
+    /// Every player gets the signal even if some of them fail; the first error is returned.
+    pub fn broadcast_stop(&self) -> Result<(), DBusError> {
+        self.broadcast(Player::stop)
+    }
+
+    /// Returns the players that are currently playing. Players whose status cannot be read are
+    /// left out.
+    pub fn find_playing(&self) -> Vec<&Player<'static>> {
+        self.players
+            .iter()
+            .filter(|player| player.get_playback_status().ok() == Some(PlaybackStatus::Playing))
+            .collect()
+    }
+
+    /// Returns the first player with the given [`Identity`](Player::identity), ignoring case like
+    /// [`PlayerFinder::find_by_identity`].
+    pub fn find_by_identity(&self, identity: &str) -> Option<&Player<'static>> {
+        let identity = identity.to_lowercase();
+        self.players
+            .iter()
+            .find(|player| player.identity().to_lowercase() == identity)
+    }
+
+    fn broadcast<F>(&self, command: F) -> Result<(), DBusError>
+    where
+        F: Fn(&Player<'static>) -> Result<(), DBusError>,
+    {
+        let mut first_error = None;
+        for player in &self.players {
+            if let Err(error) = command(player) {
+                first_error.get_or_insert(error);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::{finder_for, FakePlayer};
+
+    #[test]
+    fn it_pauses_every_player() {
+        let one = FakePlayer::spawn();
+        let other = FakePlayer::spawn();
+        let finder = finder_for(&[&one, &other]);
+        let pool = PlayerPool::from_finder(&finder).unwrap();
+
+        assert_eq!(pool.len(), 2);
+        pool.broadcast_pause().unwrap();
+        assert!(one.received_calls().iter().any(|call| call == "Pause"));
+        assert!(other.received_calls().iter().any(|call| call == "Pause"));
+    }
+
+    #[test]
+    fn it_finds_players_by_status_and_identity() {
+        let playing = FakePlayer::spawn();
+        let paused = FakePlayer::spawn_with_status("Paused");
+        let finder = finder_for(&[&playing, &paused]);
+        let pool = PlayerPool::from_finder(&finder).unwrap();
+
+        let found = pool.find_playing();
+        assert_eq!(found.len(), 1);
+        assert_eq!(&**found[0].bus_name(), playing.bus_name());
+        assert!(pool.find_by_identity("fake PLAYER").is_some());
+        assert!(pool.find_by_identity("Spotify").is_none());
+    }
+
+    #[test]
+    fn it_drops_players_that_quit_on_refresh() {
+        let staying = FakePlayer::spawn();
+        let quitting = FakePlayer::spawn();
+        let finder = finder_for(&[&staying, &quitting]);
+        let mut pool = PlayerPool::from_finder(&finder).unwrap();
+        assert_eq!(pool.len(), 2);
+
+        drop(quitting);
+        pool.refresh().unwrap();
+
+        assert_eq!(pool.len(), 1);
+        assert_eq!(&**pool.players()[0].bus_name(), staying.bus_name());
+    }
+}
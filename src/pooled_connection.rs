@@ -1,5 +1,7 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Deref;
+use std::rc::{Rc, Weak};
 use std::time::{Duration, Instant};
 
 use dbus::ffidisp::{ConnPath, Connection};
@@ -7,21 +9,73 @@ use dbus::strings::{BusName, Path};
 use dbus::Message;
 
 use crate::extensions::DurationExtensions;
+use crate::find::BusType;
 use crate::metadata::{Metadata, Value};
 use crate::player::MPRIS2_PATH;
+use crate::playlist::{Playlist, RawPlaylist};
+use crate::signal::{EventHistory, PlayerEvent};
 use crate::track_list::TrackID;
+use crate::DBusError;
 
 #[derive(Debug)]
 pub(crate) struct PooledConnection {
     connection: Connection,
+    // The bus the connection was opened on; `None` for connections that were opened elsewhere,
+    // as there is no way to tell which bus those are on.
+    bus: Option<BusType>,
     events: RefCell<HashMap<String, Vec<MprisEvent>>>,
+    signals: RefCell<HashMap<String, SignalQueue>>,
+    histories: RefCell<HashMap<String, Vec<Weak<RefCell<EventHistory>>>>>,
+}
+
+/// A shared [`PooledConnection`] that dereferences to its D-Bus connection, so that a
+/// [`ConnPath`] can hold on to it without borrowing the [`Player`](crate::Player).
+#[derive(Debug, Clone)]
+pub(crate) struct SharedConnection(Rc<PooledConnection>);
+
+/// Raw signals waiting to be read by [`EventIterator`](crate::EventIterator)s of a single bus.
+#[derive(Debug, Default)]
+struct SignalQueue {
+    subscribers: usize,
+    signals: VecDeque<PlayerEvent>,
+    player_quit: bool,
+}
+
+/// Result of asking for the next raw signal of a bus.
+#[derive(Debug)]
+pub(crate) enum SignalPoll {
+    Signal(PlayerEvent),
+    Empty,
+    PlayerQuit,
 }
 
 const GET_NAME_OWNER_TIMEOUT: i32 = 100; // ms
 const NAME_HAS_OWNER_TIMEOUT: i32 = 100; // ms
 
+impl SharedConnection {
+    pub(crate) fn new(connection: Rc<PooledConnection>) -> Self {
+        SharedConnection(connection)
+    }
+}
+
+impl Deref for SharedConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.0.underlying()
+    }
+}
+
 impl PooledConnection {
     pub(crate) fn new(connection: Connection) -> Self {
+        PooledConnection::with_bus(connection, None)
+    }
+
+    pub(crate) fn for_bus(connection: Connection, bus: BusType) -> Self {
+        PooledConnection::with_bus(connection, Some(bus))
+    }
+
+    fn with_bus(connection: Connection, bus: Option<BusType>) -> Self {
         // Subscribe to events that relate to players. See [`MprisMessage`] below for details.
         let _ = connection.add_match(
             "interface='org.freedesktop.DBus.Properties',member='PropertiesChanged',path='/org/mpris/MediaPlayer2'",
@@ -32,15 +86,71 @@ impl PooledConnection {
         let _ = connection.add_match(
             "interface='org.mpris.MediaPlayer2.TrackList',path='/org/mpris/MediaPlayer2'",
         );
+        let _ = connection.add_match(
+            "interface='org.mpris.MediaPlayer2.Playlists',member='PlaylistChanged',path='/org/mpris/MediaPlayer2'",
+        );
         let _ = connection.add_match(
             "type='signal',sender='org.freedesktop.DBus',interface='org.freedesktop.DBus',member='NameOwnerChanged'",
         );
         PooledConnection {
             connection,
+            bus,
             events: RefCell::new(HashMap::new()),
+            signals: RefCell::new(HashMap::new()),
+            histories: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Opens a new connection to the same bus, with the same subscriptions to D-Bus signals.
+    /// Connections that were opened elsewhere are assumed to be on the session bus.
+    ///
+    /// Nothing of this connection is copied; see
+    /// [`copy_subscriptions`](Self::copy_subscriptions).
+    pub(crate) fn reopen(&self) -> Result<PooledConnection, DBusError> {
+        let bus = self.bus.clone().unwrap_or_default();
+        let connection = crate::find::open_connection(&bus)?;
+        Ok(PooledConnection::for_bus(connection, bus))
+    }
+
+    /// Starts recording the raw signals of bus `to_name` for the signal subscribers and histories
+    /// of bus `from_name` on the `other` connection.
+    ///
+    /// Queued signals are not copied.
+    pub(crate) fn copy_subscriptions(
+        &self,
+        other: &PooledConnection,
+        from_name: &str,
+        to_name: &str,
+    ) {
+        let subscribers = other
+            .signals
+            .borrow()
+            .get(from_name)
+            .map(|queue| queue.subscribers)
+            .unwrap_or(0);
+        if subscribers > 0 {
+            self.signals
+                .borrow_mut()
+                .entry(to_name.to_owned())
+                .or_default()
+                .subscribers += subscribers;
+        }
+
+        if let Some(histories) = other.histories.borrow().get(from_name) {
+            self.histories
+                .borrow_mut()
+                .entry(to_name.to_owned())
+                .or_default()
+                .extend(histories.iter().cloned());
         }
     }
 
+    /// The bus that the connection is on, if it is known.
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    pub(crate) fn bus(&self) -> Option<&BusType> {
+        self.bus.as_ref()
+    }
+
     pub(crate) fn with_path<'a>(
         &'a self,
         bus_name: BusName<'a>,
@@ -54,6 +164,11 @@ impl PooledConnection {
         &self.connection
     }
 
+    /// Returns `false` once the connection to the bus was lost, like when the D-Bus daemon quit.
+    pub(crate) fn is_connected(&self) -> bool {
+        self.connection.is_connected()
+    }
+
     pub(crate) fn determine_unique_name<S: Into<String>>(&self, bus_name: S) -> Option<String> {
         let get_name_owner = Message::new_method_call(
             "org.freedesktop.DBus",
@@ -70,6 +185,26 @@ impl PooledConnection {
             .and_then(|reply| reply.get1())
     }
 
+    pub(crate) fn unix_process_id(
+        &self,
+        bus_name: &str,
+        timeout_ms: i32,
+    ) -> Result<u32, DBusError> {
+        let get_process_id = Message::new_method_call(
+            "org.freedesktop.DBus",
+            "/",
+            "org.freedesktop.DBus",
+            "GetConnectionUnixProcessID",
+        )
+        .unwrap()
+        .append1(bus_name);
+
+        let reply = self
+            .connection
+            .send_with_reply_and_block(get_process_id, timeout_ms)?;
+        reply.read1().map_err(DBusError::from)
+    }
+
     pub(crate) fn name_has_owner<S: Into<String>>(&self, bus_name: S) -> Option<bool> {
         let name_has_owner = Message::new_method_call(
             "org.freedesktop.DBus",
@@ -109,6 +244,59 @@ impl PooledConnection {
             .unwrap_or_default()
     }
 
+    /// Start recording raw signals for the given bus name. Every call must be paired with a call
+    /// to [`unsubscribe_signals`](Self::unsubscribe_signals).
+    pub(crate) fn subscribe_signals(&self, bus_name: &str) {
+        if let Ok(mut signals) = self.signals.try_borrow_mut() {
+            signals.entry(bus_name.to_owned()).or_default().subscribers += 1;
+        }
+    }
+
+    /// Stop recording raw signals for the given bus name. Queued signals are dropped when the last
+    /// subscriber unsubscribes.
+    pub(crate) fn unsubscribe_signals(&self, bus_name: &str) {
+        if let Ok(mut signals) = self.signals.try_borrow_mut() {
+            let now_unused = match signals.get_mut(bus_name) {
+                Some(queue) => {
+                    queue.subscribers = queue.subscribers.saturating_sub(1);
+                    queue.subscribers == 0
+                }
+                None => false,
+            };
+
+            if now_unused {
+                signals.remove(bus_name);
+            }
+        }
+    }
+
+    /// Adds every raw signal of the given bus name to the history, for as long as it is alive.
+    pub(crate) fn record_history(&self, bus_name: &str, history: &Rc<RefCell<EventHistory>>) {
+        if let Ok(mut histories) = self.histories.try_borrow_mut() {
+            histories
+                .entry(bus_name.to_owned())
+                .or_default()
+                .push(Rc::downgrade(history));
+        }
+    }
+
+    /// Removes the oldest recorded raw signal of a bus from its queue and returns it.
+    pub(crate) fn next_signal(&self, bus_name: &str) -> SignalPoll {
+        let mut signals = match self.signals.try_borrow_mut() {
+            Ok(signals) => signals,
+            Err(_) => return SignalPoll::Empty,
+        };
+
+        match signals.get_mut(bus_name) {
+            Some(queue) => match queue.signals.pop_front() {
+                Some(signal) => SignalPoll::Signal(signal),
+                None if queue.player_quit => SignalPoll::PlayerQuit,
+                None => SignalPoll::Empty,
+            },
+            None => SignalPoll::Empty,
+        }
+    }
+
     /// Process events in a blocking fashion until the deadline/timebox [`Duration`] runs out.
     pub(crate) fn process_events_blocking_for(&self, duration: Duration) {
         let start = Instant::now();
@@ -155,6 +343,8 @@ impl PooledConnection {
     /// Takes a message and processes it appropriately. Returns the affected bus name, and a borrow
     /// to the generated [`MprisEvent`], if applicable.
     fn process_message(&self, message: MprisMessage) {
+        self.record_signals(&message);
+
         let mut events = match self.events.try_borrow_mut() {
             Ok(val) => val,
             Err(_) => {
@@ -176,7 +366,7 @@ impl PooledConnection {
                     events.insert(old_owner, vec![MprisEvent::PlayerQuit]);
                 }
             }
-            MprisMessage::PlayerPropertiesChanged { unique_name } => {
+            MprisMessage::PlayerPropertiesChanged { unique_name, .. } => {
                 events
                     .entry(unique_name)
                     .or_default()
@@ -191,20 +381,23 @@ impl PooledConnection {
                     .or_default()
                     .push(MprisEvent::Seeked { position_in_us });
             }
-            MprisMessage::TrackListPropertiesChanged { unique_name } => {
+            MprisMessage::TrackListPropertiesChanged { unique_name, .. } => {
                 events
                     .entry(unique_name)
                     .or_default()
                     .push(MprisEvent::TrackListPropertiesChanged);
             }
             MprisMessage::TrackListReplaced {
-                unique_name, ids, ..
+                unique_name,
+                ids,
+                current_id,
             } => {
                 events
                     .entry(unique_name)
                     .or_default()
                     .push(MprisEvent::TrackListReplaced {
                         ids: ids.into_iter().map(TrackID::from).collect(),
+                        current_id,
                     });
             }
             MprisMessage::TrackAdded {
@@ -239,6 +432,52 @@ impl PooledConnection {
                         metadata: Metadata::from(metadata),
                     });
             }
+            // `MprisEvent` has no playlist changes; they are only recorded as raw signals.
+            MprisMessage::PlaylistChanged { .. } => {}
+        }
+    }
+
+    /// Adds the message to the raw signal queue of the sending bus, if anyone is subscribed to it.
+    fn record_signals(&self, message: &MprisMessage) {
+        let mut signals = match self.signals.try_borrow_mut() {
+            Ok(val) => val,
+            Err(_) => return,
+        };
+        let mut histories = match self.histories.try_borrow_mut() {
+            Ok(val) => val,
+            Err(_) => return,
+        };
+
+        let bus_name = message.bus_name();
+        let queue = signals.get_mut(bus_name);
+        if queue.is_none() && !histories.contains_key(bus_name) {
+            return;
+        }
+        let events = message.to_player_events();
+
+        if let (Some(events), Some(recorders)) = (&events, histories.get_mut(bus_name)) {
+            // Histories of players that have been dropped are forgotten on the way.
+            recorders.retain(|recorder| match recorder.upgrade() {
+                Some(history) => {
+                    if let Ok(mut history) = history.try_borrow_mut() {
+                        for event in events {
+                            history.push(event.clone());
+                        }
+                    }
+                    true
+                }
+                None => false,
+            });
+            if recorders.is_empty() {
+                histories.remove(bus_name);
+            }
+        }
+
+        if let Some(queue) = queue {
+            match events {
+                Some(events) => queue.signals.extend(events),
+                None => queue.player_quit = true,
+            }
         }
     }
 }
@@ -262,6 +501,7 @@ pub(crate) enum MprisEvent {
     TrackListPropertiesChanged,
     TrackListReplaced {
         ids: Vec<TrackID>,
+        current_id: TrackID,
     },
     TrackAdded {
         after_id: TrackID,
@@ -285,6 +525,7 @@ pub(crate) enum MprisMessage {
     },
     PlayerPropertiesChanged {
         unique_name: String,
+        changed: HashMap<String, Value>,
     },
     Seeked {
         unique_name: String,
@@ -292,6 +533,7 @@ pub(crate) enum MprisMessage {
     },
     TrackListPropertiesChanged {
         unique_name: String,
+        changed: HashMap<String, Value>,
     },
     TrackListReplaced {
         unique_name: String,
@@ -312,6 +554,10 @@ pub(crate) enum MprisMessage {
         old_id: TrackID,
         metadata: HashMap<String, Value>,
     },
+    PlaylistChanged {
+        unique_name: String,
+        playlist: Playlist,
+    },
 }
 
 impl MprisMessage {
@@ -322,6 +568,83 @@ impl MprisMessage {
             .or_else(|| MprisMessage::try_parse_mpris_signal(&message))
     }
 
+    /// The bus name that the message is about; the unique name of the sender for MPRIS signals.
+    fn bus_name(&self) -> &str {
+        match self {
+            MprisMessage::NameOwnerChanged { old_owner, .. } => old_owner,
+            MprisMessage::PlayerPropertiesChanged { unique_name, .. }
+            | MprisMessage::Seeked { unique_name, .. }
+            | MprisMessage::TrackListPropertiesChanged { unique_name, .. }
+            | MprisMessage::TrackListReplaced { unique_name, .. }
+            | MprisMessage::TrackAdded { unique_name, .. }
+            | MprisMessage::TrackRemoved { unique_name, .. }
+            | MprisMessage::TrackMetadataChanged { unique_name, .. }
+            | MprisMessage::PlaylistChanged { unique_name, .. } => unique_name,
+        }
+    }
+
+    /// Converts the message into the [`PlayerEvent`]s that it represents. Returns [`None`] if the
+    /// message means that the player quit.
+    fn to_player_events(&self) -> Option<Vec<PlayerEvent>> {
+        let events = match self {
+            MprisMessage::NameOwnerChanged { new_owner, .. } => {
+                if new_owner.is_empty() {
+                    return None;
+                }
+                Vec::new()
+            }
+            MprisMessage::PlayerPropertiesChanged { changed, .. } => {
+                let stopped = changed
+                    .get("PlaybackStatus")
+                    .and_then(Value::as_str)
+                    .map(|status| status == "Stopped")
+                    .unwrap_or(false);
+
+                let mut events = vec![PlayerEvent::PropertiesChanged {
+                    changed: changed.clone(),
+                }];
+                if stopped {
+                    events.push(PlayerEvent::EndOfTrack);
+                }
+                events
+            }
+            MprisMessage::TrackListPropertiesChanged { changed, .. } => {
+                vec![PlayerEvent::PropertiesChanged {
+                    changed: changed.clone(),
+                }]
+            }
+            MprisMessage::Seeked { position_in_us, .. } => vec![PlayerEvent::Seeked {
+                position: Duration::from_micros_ext(*position_in_us),
+            }],
+            MprisMessage::TrackListReplaced {
+                ids, current_id, ..
+            } => vec![PlayerEvent::TrackListReplaced {
+                ids: ids.clone(),
+                current: current_id.clone(),
+            }],
+            MprisMessage::TrackAdded {
+                after_id, metadata, ..
+            } => vec![PlayerEvent::TrackAdded {
+                metadata: Metadata::from(metadata.clone()),
+                after: after_id.clone(),
+            }],
+            MprisMessage::TrackRemoved { id, .. } => {
+                vec![PlayerEvent::TrackRemoved { id: id.clone() }]
+            }
+            MprisMessage::TrackMetadataChanged {
+                old_id, metadata, ..
+            } => vec![PlayerEvent::TrackMetadataChanged {
+                id: old_id.clone(),
+                metadata: Metadata::from(metadata.clone()),
+            }],
+            MprisMessage::PlaylistChanged { playlist, .. } => vec![PlayerEvent::PlaylistChanged {
+                playlist: playlist.clone(),
+            }],
+        };
+
+        Some(events)
+    }
+
     /// Return a [`MprisMessage::NameOwnerChanged`] if the provided D-Bus message is a
     /// org.freedesktop.DBus NameOwnerChanged message.
     fn try_parse_name_owner_changed(message: &Message) -> Option<Self> {
@@ -360,6 +683,7 @@ impl MprisMessage {
                     "TrackAdded" => try_parse_track_added(message),
                     "TrackRemoved" => try_parse_track_removed(message),
                     "TrackMetadataChanged" => try_parse_track_metadata_changed(message),
+                    "PlaylistChanged" => try_parse_playlist_changed(message),
                     _ => None,
                 };
             }
@@ -372,21 +696,25 @@ fn try_parse_properties_changed(message: &Message) -> Option<MprisMessage> {
     let unique_name = message.sender().map(|bus_name| bus_name.to_string())?;
     let mut iter = message.iter_init();
     let interface_name: String = iter.read().ok()?;
+    // Still report the change if the values cannot be read, even if nobody will know what changed.
+    let changed: HashMap<String, Value> = iter.read().unwrap_or_default();
     match interface_name.as_ref() {
-        "org.mpris.MediaPlayer2.Player" => {
-            Some(MprisMessage::PlayerPropertiesChanged { unique_name })
-        }
-        "org.mpris.MediaPlayer2.TrackList" => {
-            Some(MprisMessage::TrackListPropertiesChanged { unique_name })
-        }
+        "org.mpris.MediaPlayer2.Player" => Some(MprisMessage::PlayerPropertiesChanged {
+            unique_name,
+            changed,
+        }),
+        "org.mpris.MediaPlayer2.TrackList" => Some(MprisMessage::TrackListPropertiesChanged {
+            unique_name,
+            changed,
+        }),
         _ => None,
     }
 }
 
 fn try_parse_seeked(message: &Message) -> Option<MprisMessage> {
     let unique_name = message.sender().map(|bus_name| bus_name.to_string())?;
-    let mut iter = message.iter_init();
-    let position_in_us: u64 = iter.read().ok()?;
+    let position = crate::signal::parse_seeked_signal(message).ok()?;
+    let position_in_us = DurationExtensions::as_micros(&position);
 
     Some(MprisMessage::Seeked {
         unique_name,
@@ -443,3 +771,92 @@ fn try_parse_track_metadata_changed(message: &Message) -> Option<MprisMessage> {
         metadata,
     })
 }
+
+fn try_parse_playlist_changed(message: &Message) -> Option<MprisMessage> {
+    let unique_name = message.sender().map(|bus_name| bus_name.to_string())?;
+    let mut iter = message.iter_init();
+    let playlist: RawPlaylist = iter.read().ok()?;
+
+    Some(MprisMessage::PlaylistChanged {
+        unique_name,
+        playlist: Playlist::from(playlist),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties_changed(status: &str) -> MprisMessage {
+        let mut changed = HashMap::new();
+        changed.insert(String::from("PlaybackStatus"), Value::from(status));
+        MprisMessage::PlayerPropertiesChanged {
+            unique_name: String::from(":1.42"),
+            changed,
+        }
+    }
+
+    #[test]
+    fn it_converts_properties_changed_to_events() {
+        let events = properties_changed("Playing").to_player_events().unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            PlayerEvent::PropertiesChanged { changed } => {
+                assert_eq!(changed["PlaybackStatus"], Value::from("Playing"));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_emits_end_of_track_when_stopped() {
+        let events = properties_changed("Stopped").to_player_events().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[1], PlayerEvent::EndOfTrack));
+    }
+
+    #[test]
+    fn it_converts_seeked_to_events() {
+        let message = MprisMessage::Seeked {
+            unique_name: String::from(":1.42"),
+            position_in_us: 1_500_000,
+        };
+        assert_eq!(message.bus_name(), ":1.42");
+        match message.to_player_events().unwrap().as_slice() {
+            [PlayerEvent::Seeked { position }] => {
+                assert_eq!(*position, Duration::from_millis(1500))
+            }
+            other => panic!("Unexpected events: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_converts_playlist_changed_to_events() {
+        let message = MprisMessage::PlaylistChanged {
+            unique_name: String::from(":1.42"),
+            playlist: Playlist::from((
+                Path::from("/org/example/Playlist/1"),
+                String::from("Favourites"),
+                String::new(),
+            )),
+        };
+        assert_eq!(message.bus_name(), ":1.42");
+        match message.to_player_events().unwrap().as_slice() {
+            [PlayerEvent::PlaylistChanged { playlist }] => {
+                assert_eq!(playlist.id.as_str(), "/org/example/Playlist/1");
+                assert_eq!(playlist.name, "Favourites");
+            }
+            other => panic!("Unexpected events: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_treats_lost_names_as_player_quit() {
+        let message = MprisMessage::NameOwnerChanged {
+            new_owner: String::new(),
+            old_owner: String::from(":1.42"),
+        };
+        assert_eq!(message.bus_name(), ":1.42");
+        assert!(message.to_player_events().is_none());
+    }
+}
@@ -6,6 +6,7 @@ use crate::extensions::DurationExtensions;
 use crate::metadata::Metadata;
 use crate::player::Player;
 use crate::pooled_connection::MprisEvent;
+use crate::properties::PropertiesChangedSignal;
 
 /// Struct containing information about current progress of a [`Player`].
 ///
@@ -37,10 +38,13 @@ pub struct ProgressTracker<'a> {
     player: &'a Player<'a>,
     track_list: Option<TrackList>,
     interval: Duration,
+    refresh_interval: Duration,
     last_tick: Instant,
     last_progress: Progress,
 }
 
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Return value of [`ProgressTracker::tick`](ProgressTracker::tick), which gives details about the latest refresh.
 #[derive(Debug)]
 pub struct ProgressTick<'a> {
@@ -106,6 +110,7 @@ impl<'a> ProgressTracker<'a> {
         Ok(ProgressTracker {
             player,
             interval: Duration::from_millis(u64::from(interval_ms)),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
             last_tick: Instant::now(),
             last_progress: Progress::from_player(player)?,
             track_list: player.checked_get_track_list()?,
@@ -121,6 +126,10 @@ impl<'a> ProgressTracker<'a> {
     /// events to determine if something changed (and potentially perform a full refresh of the
     /// data). If there is no time left, then the previous data will be reused.
     ///
+    /// If no signal caused a refresh for the [`refresh_interval`](Self::set_refresh_interval), the
+    /// [`Progress`] is refreshed anyway to correct any drift of the calculated position. Every
+    /// other tick reuses the previous data without talking to the player.
+    ///
     /// If refreshing failed for some reason the old data will be reused.
     ///
     /// It is recommended to call this inside a loop to maintain your progress display.
@@ -244,33 +253,37 @@ impl<'a> ProgressTracker<'a> {
                 MprisEvent::TrackListPropertiesChanged => {
                     track_list_changed |= self.refresh_track_list();
                 }
-                MprisEvent::TrackListReplaced { ids } => {
+                MprisEvent::TrackListReplaced { ids, current_id } => {
                     if let Some(ref mut list) = self.track_list {
-                        list.replace(ids.into_iter().collect());
+                        list.apply_tracklist_replaced(ids, &current_id);
                     }
                     track_list_changed = true;
                 }
                 MprisEvent::TrackAdded { after_id, metadata } => {
                     if let Some(ref mut list) = self.track_list {
-                        list.insert(&after_id, metadata);
+                        list.apply_track_added(metadata, &after_id);
                     }
                     track_list_changed = true;
                 }
                 MprisEvent::TrackRemoved { id } => {
                     if let Some(ref mut list) = self.track_list {
-                        list.remove(&id);
+                        list.apply_track_removed(&id);
                     }
                     track_list_changed = true;
                 }
                 MprisEvent::TrackMetadataChanged { old_id, metadata } => {
                     if let Some(ref mut list) = self.track_list {
-                        list.replace_track_metadata(&old_id, metadata);
+                        list.apply_track_metadata_changed(&old_id, metadata);
                     }
                     track_list_changed = true;
                 }
             }
         }
 
+        if !progress_changed && self.last_progress.age() >= self.refresh_interval {
+            progress_changed |= self.refresh_player_if_changed();
+        }
+
         if old_shuffle != self.last_progress.shuffle {
             // Shuffle changed, which means that the tracklist is likely to have been changed too.
             // Do a reload, even if track_list_changed was true so the correct order is loaded even
@@ -288,6 +301,15 @@ impl<'a> ProgressTracker<'a> {
         }
     }
 
+    /// Sets how long a [`Progress`] may be reused by [`tick`](Self::tick) before it is refreshed,
+    /// even if the player did not emit any signals. Defaults to 5 seconds.
+    ///
+    /// Players do not emit signals when the position drifts a little from what can be calculated,
+    /// so this keeps the [`Progress::position`] in line with the player.
+    pub fn set_refresh_interval(&mut self, refresh_interval: Duration) {
+        self.refresh_interval = refresh_interval;
+    }
+
     /// Force a refresh right now.
     ///
     /// This will ignore the interval and perform a refresh anyway. The new [`Progress`] will be
@@ -312,6 +334,18 @@ impl<'a> ProgressTracker<'a> {
         false
     }
 
+    // Like refresh_player, but only reports a change if there is one beyond the position.
+    fn refresh_player_if_changed(&mut self) -> bool {
+        match Progress::from_player(self.player) {
+            Ok(progress) => {
+                let changed = !progress.has_same_state(&self.last_progress);
+                self.last_progress = progress;
+                changed
+            }
+            Err(_) => false,
+        }
+    }
+
     fn refresh_track_list(&mut self) -> bool {
         match self.track_list {
             Some(ref mut list) => list.reload(&self.player).is_ok(),
@@ -382,7 +416,44 @@ impl Progress {
     /// [`PlaybackStatus::Playing`] and if both are `0`, then it is likely that this client does not
     /// support positions.
     pub fn position(&self) -> Duration {
-        self.position + self.elapsed()
+        self.position_at(Instant::now())
+    }
+
+    /// Returns the expected position of the current track at the given [`Instant`].
+    ///
+    /// When the player is [`Playing`](PlaybackStatus::Playing), the position is interpolated from
+    /// the [`initial_position`](Self::initial_position) using the playback rate. Otherwise the
+    /// initial position is returned as-is.
+    ///
+    /// Instants before this [`Progress`] was created will return the initial position.
+    pub fn position_at(&self, now: Instant) -> Duration {
+        self.position + self.elapsed_at(now)
+    }
+
+    /// Returns how far into the current track the [`position`](Self::position) is, as a fraction
+    /// between `0.0` and `1.0`.
+    ///
+    /// Returns [`None`] if the length of the track is unknown.
+    pub fn fraction(&self) -> Option<f64> {
+        let length = self.length()?;
+        if length == Duration::new(0, 0) {
+            return None;
+        }
+
+        let position = DurationExtensions::as_micros(&self.position()) as f64;
+        let length = DurationExtensions::as_micros(&length) as f64;
+        Some((position / length).clamp(0.0, 1.0))
+    }
+
+    /// Returns the time left until the end of the current track, as a [`Duration`].
+    ///
+    /// Returns [`None`] if the length of the track is unknown.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.length().map(|length| {
+            length
+                .checked_sub(self.position())
+                .unwrap_or_else(|| Duration::new(0, 0))
+        })
     }
 
     /// Returns the position that the current track was at when the [`Progress`] was created.
@@ -415,10 +486,76 @@ impl Progress {
         self.current_volume
     }
 
-    fn elapsed(&self) -> Duration {
+    /// Returns `true` if the [`Progress`] is [`Playing`](PlaybackStatus::Playing) and the
+    /// interpolated [`position`](Self::position) has run past the end of the track.
+    ///
+    /// The player has most likely moved on to another track by then, so the metadata can no
+    /// longer be trusted.
+    pub fn is_stale(&self) -> bool {
+        self.is_stale_at(Instant::now())
+    }
+
+    /// Updates the position after a [`Seeked`](crate::PlayerEvent::Seeked) signal, as if this
+    /// [`Progress`] was recorded at this instant.
+    pub fn apply_seeked(&mut self, new_position: Duration) {
+        self.position = new_position;
+        self.instant = Instant::now();
+    }
+
+    /// Updates the fields that appear in a `PropertiesChanged` signal, without asking the player
+    /// for anything.
+    ///
+    /// The position is carried over to the current instant first, so a change of the playback
+    /// status or rate only affects the interpolation from now on. A new `Metadata` also replaces
+    /// the [`length`](Self::length).
+    pub fn apply_properties_changed(&mut self, signal: &PropertiesChangedSignal) {
+        let now = Instant::now();
+        self.position = signal.position.unwrap_or_else(|| self.position_at(now));
+        self.instant = now;
+
+        if let Some(playback_status) = signal.playback_status {
+            self.playback_status = playback_status;
+        }
+        if let Some(ref metadata) = signal.metadata {
+            self.metadata = metadata.clone();
+        }
+        if let Some(rate) = signal.rate {
+            self.rate = rate;
+        }
+        if let Some(shuffle) = signal.shuffle {
+            self.shuffle = shuffle;
+        }
+        if let Some(loop_status) = signal.loop_status {
+            self.loop_status = loop_status;
+        }
+        if let Some(volume) = signal.volume {
+            self.current_volume = volume;
+        }
+    }
+
+    fn has_same_state(&self, other: &Progress) -> bool {
+        self.playback_status == other.playback_status
+            && self.shuffle == other.shuffle
+            && self.loop_status == other.loop_status
+            && self.rate == other.rate
+            && self.current_volume == other.current_volume
+            && self.metadata.diff(&other.metadata).is_empty()
+    }
+
+    fn is_stale_at(&self, now: Instant) -> bool {
+        match (self.playback_status, self.length()) {
+            (PlaybackStatus::Playing, Some(length)) => self.position_at(now) > length,
+            _ => false,
+        }
+    }
+
+    fn elapsed_at(&self, now: Instant) -> Duration {
         let elapsed_ms = match self.playback_status {
             PlaybackStatus::Playing => {
-                DurationExtensions::as_millis(&self.age()) as f64 * self.rate
+                let age = now
+                    .checked_duration_since(self.instant)
+                    .unwrap_or_else(|| Duration::new(0, 0));
+                DurationExtensions::as_millis(&age) as f64 * self.rate
             }
             _ => 0.0,
         };
@@ -441,6 +578,9 @@ impl From<DBusError> for ProgressError {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::fake_player::FakePlayer;
+    use crate::metadata::Value;
+    use std::collections::HashMap;
 
     #[test]
     fn it_progresses_position_when_playing_at_microseconds() {
@@ -459,6 +599,68 @@ mod test {
         assert!(progress.position() >= progress.initial_position());
     }
 
+    fn progress_with_length(playback_status: PlaybackStatus, position_secs: u64) -> Progress {
+        let mut values = HashMap::new();
+        values.insert(String::from("mpris:length"), Value::U64(100_000_000));
+
+        Progress {
+            metadata: Metadata::from(values),
+            playback_status,
+            shuffle: false,
+            loop_status: LoopStatus::None,
+            rate: 1.0,
+            position: Duration::from_secs(position_secs),
+            current_volume: 0.0,
+            instant: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn it_interpolates_position_at_instants() {
+        let mut progress = progress_with_length(PlaybackStatus::Playing, 10);
+        let later = progress.instant + Duration::from_secs(2);
+
+        assert_eq!(progress.position_at(later), Duration::from_secs(12));
+        assert_eq!(
+            progress.position_at(progress.instant - Duration::from_secs(1)),
+            Duration::from_secs(10)
+        );
+
+        progress.rate = 2.0;
+        assert_eq!(progress.position_at(later), Duration::from_secs(14));
+
+        progress.playback_status = PlaybackStatus::Paused;
+        assert_eq!(progress.position_at(later), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn it_calculates_fraction_and_remaining_time() {
+        let progress = progress_with_length(PlaybackStatus::Paused, 25);
+        assert_eq!(progress.fraction(), Some(0.25));
+        assert_eq!(progress.remaining(), Some(Duration::from_secs(75)));
+
+        let progress = progress_with_length(PlaybackStatus::Paused, 150);
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(progress.remaining(), Some(Duration::from_secs(0)));
+    }
+
+    #[test]
+    fn it_has_no_fraction_without_length() {
+        let progress = Progress {
+            metadata: Metadata::new(String::from("id")),
+            playback_status: PlaybackStatus::Paused,
+            shuffle: false,
+            loop_status: LoopStatus::None,
+            rate: 1.0,
+            position: Duration::from_secs(25),
+            current_volume: 0.0,
+            instant: Instant::now(),
+        };
+
+        assert_eq!(progress.fraction(), None);
+        assert_eq!(progress.remaining(), None);
+    }
+
     #[test]
     fn it_does_not_progress_when_paused() {
         let progress = Progress {
@@ -474,4 +676,57 @@ mod test {
 
         assert_eq!(progress.position(), progress.initial_position());
     }
+
+    #[test]
+    fn it_applies_seeked_signals() {
+        let mut progress = progress_with_length(PlaybackStatus::Playing, 10);
+        progress.instant -= Duration::from_secs(200);
+        assert!(progress.is_stale());
+
+        progress.apply_seeked(Duration::from_secs(30));
+        assert!(!progress.is_stale());
+        assert_eq!(progress.initial_position(), Duration::from_secs(30));
+        assert_eq!(
+            progress.position_at(progress.instant + Duration::from_secs(2)),
+            Duration::from_secs(32)
+        );
+    }
+
+    #[test]
+    fn it_applies_properties_changed_signals() {
+        let mut progress = progress_with_length(PlaybackStatus::Paused, 10);
+        let mut values = HashMap::new();
+        values.insert(String::from("mpris:length"), Value::U64(20_000_000));
+
+        progress.apply_properties_changed(&PropertiesChangedSignal {
+            playback_status: Some(PlaybackStatus::Playing),
+            metadata: Some(Metadata::from(values)),
+            rate: Some(2.0),
+            ..Default::default()
+        });
+
+        assert!(!progress.is_stale());
+        assert_eq!(progress.playback_status(), PlaybackStatus::Playing);
+        assert_eq!(progress.playback_rate(), 2.0);
+        assert_eq!(progress.length(), Some(Duration::from_secs(20)));
+        assert_eq!(progress.initial_position(), Duration::from_secs(10));
+        assert!(progress.is_stale_at(progress.instant + Duration::from_secs(6)));
+    }
+
+    #[test]
+    fn it_reuses_progress_between_refresh_intervals() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let mut tracker = player.track_progress(100).unwrap();
+
+        let first = *tracker.tick().progress.created_at();
+        let tick = tracker.tick();
+        assert!(!tick.progress_changed);
+        assert_eq!(*tick.progress.created_at(), first);
+
+        tracker.set_refresh_interval(Duration::from_millis(0));
+        let tick = tracker.tick();
+        assert!(!tick.progress_changed);
+        assert!(*tick.progress.created_at() > first);
+    }
 }
@@ -0,0 +1,608 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use dbus::Message;
+
+use super::{DBusError, LoopStatus, Metadata, MetadataValue, PlaybackStatus};
+use crate::extensions::DurationExtensions;
+
+/// A snapshot of every property of the `org.mpris.MediaPlayer2.Player` interface, loaded in a
+/// single D-Bus call.
+///
+/// Properties that the player did not report are [`None`], or [`false`] for the capabilities.
+///
+/// See [`Player::get_all_properties`](crate::Player::get_all_properties) and the
+/// [MPRIS2 Player interface specification][spec].
+///
+/// [spec]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html
+#[derive(Debug, Clone)]
+pub struct PlayerProperties {
+    /// `PlaybackStatus`
+    pub playback_status: PlaybackStatus,
+    /// `LoopStatus`
+    pub loop_status: Option<LoopStatus>,
+    /// `Rate`
+    pub rate: Option<f64>,
+    /// `Shuffle`
+    pub shuffle: Option<bool>,
+    /// `Metadata`; empty if the player did not report any.
+    pub metadata: Metadata,
+    /// `Volume`
+    pub volume: Option<f64>,
+    /// `Position`
+    pub position: Option<Duration>,
+    /// `MinimumRate`
+    pub minimum_rate: Option<f64>,
+    /// `MaximumRate`
+    pub maximum_rate: Option<f64>,
+    /// `CanGoNext`
+    pub can_go_next: bool,
+    /// `CanGoPrevious`
+    pub can_go_previous: bool,
+    /// `CanPlay`
+    pub can_play: bool,
+    /// `CanPause`
+    pub can_pause: bool,
+    /// `CanSeek`
+    pub can_seek: bool,
+    /// `CanControl`
+    pub can_control: bool,
+}
+
+/// The properties that changed between two [`PlayerProperties`] snapshots, with their new values.
+///
+/// Properties that did not change are [`None`]. So are properties that the newer snapshot no
+/// longer reports, as there is no new value to show for them.
+///
+/// See [`PlayerProperties::diff`].
+#[derive(Debug, Clone, Default)]
+pub struct PlayerPropertiesDiff {
+    /// `PlaybackStatus`
+    pub playback_status: Option<PlaybackStatus>,
+    /// `LoopStatus`
+    pub loop_status: Option<LoopStatus>,
+    /// `Rate`
+    pub rate: Option<f64>,
+    /// `Shuffle`
+    pub shuffle: Option<bool>,
+    /// `Metadata`
+    pub metadata: Option<Metadata>,
+    /// `Volume`
+    pub volume: Option<f64>,
+    /// `Position`
+    pub position: Option<Duration>,
+    /// `MinimumRate`
+    pub minimum_rate: Option<f64>,
+    /// `MaximumRate`
+    pub maximum_rate: Option<f64>,
+    /// `CanGoNext`
+    pub can_go_next: Option<bool>,
+    /// `CanGoPrevious`
+    pub can_go_previous: Option<bool>,
+    /// `CanPlay`
+    pub can_play: Option<bool>,
+    /// `CanPause`
+    pub can_pause: Option<bool>,
+    /// `CanSeek`
+    pub can_seek: Option<bool>,
+    /// `CanControl`
+    pub can_control: Option<bool>,
+}
+
+/// A snapshot of every property of the `org.mpris.MediaPlayer2` interface, loaded in a single
+/// D-Bus call.
+///
+/// Properties that the player did not report are [`None`], [`false`] or empty.
+///
+/// See [`Player::get_all_root_properties`](crate::Player::get_all_root_properties) and the
+/// [MPRIS2 MediaPlayer2 interface specification][spec].
+///
+/// [spec]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootProperties {
+    /// `Identity`
+    pub identity: String,
+    /// `CanQuit`
+    pub can_quit: bool,
+    /// `Fullscreen`
+    pub fullscreen: Option<bool>,
+    /// `CanSetFullscreen`
+    pub can_set_fullscreen: Option<bool>,
+    /// `CanRaise`
+    pub can_raise: bool,
+    /// `HasTrackList`
+    pub has_track_list: bool,
+    /// `DesktopEntry`
+    pub desktop_entry: Option<String>,
+    /// `SupportedUriSchemes`
+    pub supported_uri_schemes: Vec<String>,
+    /// `SupportedMimeTypes`
+    pub supported_mime_types: Vec<String>,
+}
+
+/// Which operations a player supports, from the `can_*` properties of both the
+/// `org.mpris.MediaPlayer2` and `org.mpris.MediaPlayer2.Player` interfaces.
+///
+/// Capabilities that the player did not report are `false`.
+///
+/// See [`Player::get_capabilities`](crate::Player::get_capabilities).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PlayerCapabilities {
+    /// `CanPlay`
+    pub can_play: bool,
+    /// `CanPause`
+    pub can_pause: bool,
+    /// `CanSeek`
+    pub can_seek: bool,
+    /// `CanGoNext`
+    pub can_go_next: bool,
+    /// `CanGoPrevious`
+    pub can_go_previous: bool,
+    /// `CanControl`
+    pub can_control: bool,
+    /// `CanRaise`
+    pub can_raise: bool,
+    /// `CanQuit`
+    pub can_quit: bool,
+    /// `CanSetFullscreen`
+    pub can_set_fullscreen: bool,
+}
+
+/// The changes of a `PropertiesChanged` signal for the `org.mpris.MediaPlayer2.Player` interface,
+/// with every standard property converted to its type.
+///
+/// Properties that did not change are [`None`]; properties that are not part of MPRIS are ignored.
+///
+/// See [`PlayerEvent::PropertiesChanged`](crate::PlayerEvent::PropertiesChanged) for the raw map.
+#[derive(Debug, Clone, Default)]
+pub struct PropertiesChangedSignal {
+    /// `PlaybackStatus`
+    pub playback_status: Option<PlaybackStatus>,
+    /// `Metadata`
+    pub metadata: Option<Metadata>,
+    /// `Volume`
+    pub volume: Option<f64>,
+    /// `LoopStatus`
+    pub loop_status: Option<LoopStatus>,
+    /// `Shuffle`
+    pub shuffle: Option<bool>,
+    /// `Rate`
+    pub rate: Option<f64>,
+    /// `Position`
+    pub position: Option<Duration>,
+}
+
+impl PropertiesChangedSignal {
+    /// Parses a `org.freedesktop.DBus.Properties.PropertiesChanged` signal.
+    ///
+    /// Fails if the message is another signal, is about another interface than
+    /// `org.mpris.MediaPlayer2.Player`, or if a property has a value that is not valid for it.
+    pub fn parse(message: &Message) -> Result<Self, DBusError> {
+        match message.member() {
+            Some(ref member) if &**member == "PropertiesChanged" => {}
+            _ => {
+                return Err(DBusError::InvalidMessage(String::from(
+                    "Message is not a PropertiesChanged signal",
+                )))
+            }
+        }
+
+        let (interface, changed) = message.read2::<String, HashMap<String, MetadataValue>>()?;
+        if interface != "org.mpris.MediaPlayer2.Player" {
+            return Err(DBusError::InvalidMessage(format!(
+                "PropertiesChanged is about {}, not org.mpris.MediaPlayer2.Player",
+                interface
+            )));
+        }
+        PropertiesChangedSignal::from_changed(&changed)
+    }
+
+    /// Converts the changed properties of a
+    /// [`PlayerEvent::PropertiesChanged`](crate::PlayerEvent::PropertiesChanged).
+    pub fn from_changed(changed: &HashMap<String, MetadataValue>) -> Result<Self, DBusError> {
+        Ok(PropertiesChangedSignal {
+            playback_status: match string(changed, "PlaybackStatus") {
+                Some(status) => Some(status.parse()?),
+                None => None,
+            },
+            metadata: match changed.get("Metadata") {
+                Some(MetadataValue::Map(map)) => Some(Metadata::from(map.clone())),
+                _ => None,
+            },
+            volume: float(changed, "Volume"),
+            loop_status: match string(changed, "LoopStatus") {
+                Some(status) => Some(status.parse()?),
+                None => None,
+            },
+            shuffle: boolean(changed, "Shuffle"),
+            rate: float(changed, "Rate"),
+            position: position(changed),
+        })
+    }
+}
+
+/// Lists the properties that changed, like `PlaybackStatus=Paused, Volume=0.5`.
+impl fmt::Display for PropertiesChangedSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = Vec::new();
+        if let Some(status) = self.playback_status {
+            fields.push(format!("PlaybackStatus={:?}", status));
+        }
+        if let Some(ref metadata) = self.metadata {
+            match metadata.track_id() {
+                Some(id) => fields.push(format!("Metadata={}", id)),
+                None => fields.push(String::from("Metadata")),
+            }
+        }
+        if let Some(volume) = self.volume {
+            fields.push(format!("Volume={}", volume));
+        }
+        if let Some(status) = self.loop_status {
+            fields.push(format!("LoopStatus={:?}", status));
+        }
+        if let Some(shuffle) = self.shuffle {
+            fields.push(format!("Shuffle={}", shuffle));
+        }
+        if let Some(rate) = self.rate {
+            fields.push(format!("Rate={}", rate));
+        }
+        if let Some(position) = self.position {
+            fields.push(format!("Position={:?}", position));
+        }
+
+        if fields.is_empty() {
+            f.write_str("(nothing)")
+        } else {
+            f.write_str(&fields.join(", "))
+        }
+    }
+}
+
+impl PlayerProperties {
+    pub(crate) fn from_values(
+        mut values: HashMap<String, MetadataValue>,
+    ) -> Result<Self, DBusError> {
+        let playback_status = string(&values, "PlaybackStatus")
+            .ok_or_else(|| missing("PlaybackStatus"))?
+            .parse()?;
+        let loop_status = match string(&values, "LoopStatus") {
+            Some(status) => Some(status.parse()?),
+            None => None,
+        };
+        let metadata = match values.remove("Metadata") {
+            Some(MetadataValue::Map(map)) => Metadata::from(map),
+            _ => Metadata::default(),
+        };
+
+        Ok(PlayerProperties {
+            playback_status,
+            loop_status,
+            rate: float(&values, "Rate"),
+            shuffle: boolean(&values, "Shuffle"),
+            metadata,
+            volume: float(&values, "Volume"),
+            position: position(&values),
+            minimum_rate: float(&values, "MinimumRate"),
+            maximum_rate: float(&values, "MaximumRate"),
+            can_go_next: boolean(&values, "CanGoNext").unwrap_or(false),
+            can_go_previous: boolean(&values, "CanGoPrevious").unwrap_or(false),
+            can_play: boolean(&values, "CanPlay").unwrap_or(false),
+            can_pause: boolean(&values, "CanPause").unwrap_or(false),
+            can_seek: boolean(&values, "CanSeek").unwrap_or(false),
+            can_control: boolean(&values, "CanControl").unwrap_or(false),
+        })
+    }
+
+    /// Compares these properties with a newer snapshot and returns the ones that changed in
+    /// `other`, e.g. to only redraw the parts of a UI that need it.
+    pub fn diff(&self, other: &PlayerProperties) -> PlayerPropertiesDiff {
+        let metadata = if self.metadata.diff(&other.metadata).is_empty() {
+            None
+        } else {
+            Some(other.metadata.clone())
+        };
+
+        PlayerPropertiesDiff {
+            playback_status: changed(self.playback_status, other.playback_status),
+            loop_status: changed_option(self.loop_status, other.loop_status),
+            rate: changed_option(self.rate, other.rate),
+            shuffle: changed_option(self.shuffle, other.shuffle),
+            metadata,
+            volume: changed_option(self.volume, other.volume),
+            position: changed_option(self.position, other.position),
+            minimum_rate: changed_option(self.minimum_rate, other.minimum_rate),
+            maximum_rate: changed_option(self.maximum_rate, other.maximum_rate),
+            can_go_next: changed(self.can_go_next, other.can_go_next),
+            can_go_previous: changed(self.can_go_previous, other.can_go_previous),
+            can_play: changed(self.can_play, other.can_play),
+            can_pause: changed(self.can_pause, other.can_pause),
+            can_seek: changed(self.can_seek, other.can_seek),
+            can_control: changed(self.can_control, other.can_control),
+        }
+    }
+}
+
+impl PlayerPropertiesDiff {
+    /// Returns `true` if no property changed.
+    pub fn is_empty(&self) -> bool {
+        self.playback_status.is_none()
+            && self.loop_status.is_none()
+            && self.rate.is_none()
+            && self.shuffle.is_none()
+            && self.metadata.is_none()
+            && self.volume.is_none()
+            && self.position.is_none()
+            && self.minimum_rate.is_none()
+            && self.maximum_rate.is_none()
+            && self.can_go_next.is_none()
+            && self.can_go_previous.is_none()
+            && self.can_play.is_none()
+            && self.can_pause.is_none()
+            && self.can_seek.is_none()
+            && self.can_control.is_none()
+    }
+}
+
+impl RootProperties {
+    pub(crate) fn from_values(values: HashMap<String, MetadataValue>) -> Result<Self, DBusError> {
+        Ok(RootProperties {
+            identity: string(&values, "Identity")
+                .ok_or_else(|| missing("Identity"))?
+                .to_string(),
+            can_quit: boolean(&values, "CanQuit").unwrap_or(false),
+            fullscreen: boolean(&values, "Fullscreen"),
+            can_set_fullscreen: boolean(&values, "CanSetFullscreen"),
+            can_raise: boolean(&values, "CanRaise").unwrap_or(false),
+            has_track_list: boolean(&values, "HasTrackList").unwrap_or(false),
+            desktop_entry: string(&values, "DesktopEntry").map(String::from),
+            supported_uri_schemes: strings(&values, "SupportedUriSchemes"),
+            supported_mime_types: strings(&values, "SupportedMimeTypes"),
+        })
+    }
+}
+
+impl PlayerCapabilities {
+    pub(crate) fn from_values(
+        player_values: &HashMap<String, MetadataValue>,
+        root_values: &HashMap<String, MetadataValue>,
+    ) -> Self {
+        PlayerCapabilities {
+            can_play: boolean(player_values, "CanPlay").unwrap_or(false),
+            can_pause: boolean(player_values, "CanPause").unwrap_or(false),
+            can_seek: boolean(player_values, "CanSeek").unwrap_or(false),
+            can_go_next: boolean(player_values, "CanGoNext").unwrap_or(false),
+            can_go_previous: boolean(player_values, "CanGoPrevious").unwrap_or(false),
+            can_control: boolean(player_values, "CanControl").unwrap_or(false),
+            can_raise: boolean(root_values, "CanRaise").unwrap_or(false),
+            can_quit: boolean(root_values, "CanQuit").unwrap_or(false),
+            can_set_fullscreen: boolean(root_values, "CanSetFullscreen").unwrap_or(false),
+        }
+    }
+}
+
+fn position(values: &HashMap<String, MetadataValue>) -> Option<Duration> {
+    values
+        .get("Position")
+        .and_then(MetadataValue::as_i64)
+        .map(|position| Duration::from_micros_ext(position.max(0) as u64))
+}
+
+fn changed<T: PartialEq>(old: T, new: T) -> Option<T> {
+    if old == new {
+        None
+    } else {
+        Some(new)
+    }
+}
+
+fn changed_option<T: PartialEq>(old: Option<T>, new: Option<T>) -> Option<T> {
+    if old == new {
+        None
+    } else {
+        new
+    }
+}
+
+fn missing(name: &str) -> DBusError {
+    DBusError::Miscellaneous(format!("Player did not report the {} property", name))
+}
+
+fn string<'a>(values: &'a HashMap<String, MetadataValue>, name: &str) -> Option<&'a str> {
+    values.get(name).and_then(MetadataValue::as_str)
+}
+
+fn strings(values: &HashMap<String, MetadataValue>, name: &str) -> Vec<String> {
+    values
+        .get(name)
+        .and_then(MetadataValue::as_str_array)
+        .map(|strings| strings.into_iter().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+fn float(values: &HashMap<String, MetadataValue>, name: &str) -> Option<f64> {
+    values.get(name).and_then(MetadataValue::as_f64)
+}
+
+fn boolean(values: &HashMap<String, MetadataValue>, name: &str) -> Option<bool> {
+    values.get(name).and_then(MetadataValue::as_bool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: Vec<(&str, MetadataValue)>) -> HashMap<String, MetadataValue> {
+        pairs
+            .into_iter()
+            .map(|(key, value)| (String::from(key), value))
+            .collect()
+    }
+
+    #[test]
+    fn it_parses_player_properties() {
+        let mut metadata = HashMap::new();
+        metadata.insert(String::from("xesam:title"), MetadataValue::from("Song"));
+
+        let properties = PlayerProperties::from_values(values(vec![
+            ("PlaybackStatus", MetadataValue::from("Paused")),
+            ("LoopStatus", MetadataValue::from("Track")),
+            ("Metadata", MetadataValue::Map(metadata)),
+            ("Position", MetadataValue::I64(2_500_000)),
+            ("Volume", MetadataValue::F64(0.5)),
+            ("CanPlay", MetadataValue::Bool(true)),
+        ]))
+        .unwrap();
+
+        assert_eq!(properties.playback_status, PlaybackStatus::Paused);
+        assert_eq!(properties.loop_status, Some(LoopStatus::Track));
+        assert_eq!(properties.metadata.title(), Some("Song"));
+        assert_eq!(properties.position, Some(Duration::from_millis(2500)));
+        assert_eq!(properties.volume, Some(0.5));
+        assert_eq!(properties.shuffle, None);
+        assert!(properties.can_play);
+        assert!(!properties.can_pause);
+    }
+
+    #[test]
+    fn it_diffs_player_properties() {
+        let old = PlayerProperties::from_values(values(vec![
+            ("PlaybackStatus", MetadataValue::from("Playing")),
+            ("Volume", MetadataValue::F64(0.5)),
+            ("Rate", MetadataValue::F64(1.0)),
+            ("CanPlay", MetadataValue::Bool(true)),
+        ]))
+        .unwrap();
+        assert!(old.diff(&old).is_empty());
+
+        let mut metadata = HashMap::new();
+        metadata.insert(String::from("xesam:title"), MetadataValue::from("Song"));
+        let new = PlayerProperties::from_values(values(vec![
+            ("PlaybackStatus", MetadataValue::from("Paused")),
+            ("Volume", MetadataValue::F64(0.5)),
+            ("Metadata", MetadataValue::Map(metadata)),
+            ("CanPlay", MetadataValue::Bool(true)),
+            ("CanPause", MetadataValue::Bool(true)),
+        ]))
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.playback_status, Some(PlaybackStatus::Paused));
+        assert_eq!(diff.metadata.unwrap().title(), Some("Song"));
+        assert_eq!(diff.can_pause, Some(true));
+        assert_eq!(diff.volume, None);
+        assert_eq!(diff.rate, None);
+        assert_eq!(diff.can_play, None);
+    }
+
+    #[test]
+    fn it_requires_a_playback_status() {
+        assert!(PlayerProperties::from_values(HashMap::new()).is_err());
+        assert!(PlayerProperties::from_values(values(vec![(
+            "PlaybackStatus",
+            MetadataValue::from("Invalid")
+        )]))
+        .is_err());
+    }
+
+    #[test]
+    fn it_parses_root_properties() {
+        let properties = RootProperties::from_values(values(vec![
+            ("Identity", MetadataValue::from("Player")),
+            ("CanRaise", MetadataValue::Bool(true)),
+            (
+                "SupportedUriSchemes",
+                MetadataValue::Array(vec![MetadataValue::from("file")]),
+            ),
+        ]))
+        .unwrap();
+
+        assert_eq!(properties.identity, "Player");
+        assert!(properties.can_raise);
+        assert!(!properties.can_quit);
+        assert_eq!(properties.fullscreen, None);
+        assert_eq!(properties.supported_uri_schemes, vec![String::from("file")]);
+        assert!(properties.supported_mime_types.is_empty());
+    }
+
+    #[test]
+    fn it_reads_capabilities_from_both_interfaces() {
+        let capabilities = PlayerCapabilities::from_values(
+            &values(vec![
+                ("CanPlay", MetadataValue::Bool(true)),
+                ("CanSeek", MetadataValue::Bool(false)),
+                ("CanRaise", MetadataValue::Bool(true)),
+            ]),
+            &values(vec![
+                ("CanQuit", MetadataValue::Bool(true)),
+                ("CanSetFullscreen", MetadataValue::Bool(true)),
+            ]),
+        );
+
+        assert_eq!(
+            capabilities,
+            PlayerCapabilities {
+                can_play: true,
+                can_quit: true,
+                can_set_fullscreen: true,
+                ..PlayerCapabilities::default()
+            }
+        );
+    }
+
+    #[test]
+    fn it_parses_properties_changed_signals() {
+        use dbus::arg::{RefArg, Variant};
+
+        let mut changed: HashMap<&str, Variant<Box<dyn RefArg>>> = HashMap::new();
+        changed.insert("PlaybackStatus", Variant(Box::new(String::from("Paused"))));
+        changed.insert("Volume", Variant(Box::new(0.5)));
+        changed.insert("Position", Variant(Box::new(2_000_000i64)));
+        changed.insert("SomethingElse", Variant(Box::new(true)));
+
+        let message = Message::new_signal(
+            "/org/mpris/MediaPlayer2",
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+        )
+        .unwrap()
+        .append3(
+            "org.mpris.MediaPlayer2.Player",
+            changed,
+            Vec::<String>::new(),
+        );
+        let signal = PropertiesChangedSignal::parse(&message).unwrap();
+
+        assert_eq!(signal.playback_status, Some(PlaybackStatus::Paused));
+        assert_eq!(signal.volume, Some(0.5));
+        assert_eq!(signal.position, Some(Duration::from_secs(2)));
+        assert!(signal.metadata.is_none());
+        assert!(signal.shuffle.is_none());
+        assert_eq!(
+            signal.to_string(),
+            "PlaybackStatus=Paused, Volume=0.5, Position=2s"
+        );
+    }
+
+    #[test]
+    fn it_rejects_other_signals() {
+        let message = Message::new_signal(
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player",
+            "Seeked",
+        )
+        .unwrap()
+        .append1(0i64);
+        assert!(PropertiesChangedSignal::parse(&message).is_err());
+
+        let invalid = values(vec![("LoopStatus", MetadataValue::from("Forever"))]);
+        assert!(PropertiesChangedSignal::from_changed(&invalid).is_err());
+        assert_eq!(
+            PropertiesChangedSignal::from_changed(&HashMap::new())
+                .unwrap()
+                .to_string(),
+            "(nothing)"
+        );
+    }
+}
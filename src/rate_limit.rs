@@ -0,0 +1,116 @@
+use std::cell::Cell;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Limits how often a [`Player`](crate::Player) sends D-Bus calls, for players that cannot keep
+/// up with many calls in a row.
+///
+/// Calls that come too soon after the previous one sleep until enough time has passed. Every
+/// attempt of a call counts, including the ones made by a [`RetryPolicy`](crate::RetryPolicy).
+///
+/// The default policy does not limit anything.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::{PlayerFinder, RateLimitPolicy};
+/// let player = PlayerFinder::new()
+///     .expect("Could not connect to D-Bus")
+///     .find_active()
+///     .expect("Could not find a player")
+///     .with_rate_limit(RateLimitPolicy {
+///         calls_per_second: 10,
+///     });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RateLimitPolicy {
+    /// The maximum number of calls per second. 0 means that there is no limit.
+    pub calls_per_second: u32,
+}
+
+impl RateLimitPolicy {
+    /// Returns the shortest time between the start of two calls, or [`None`] if there is no limit.
+    pub fn min_interval(&self) -> Option<Duration> {
+        match self.calls_per_second {
+            0 => None,
+            calls => Some(Duration::from_secs(1) / calls),
+        }
+    }
+}
+
+/// Keeps track of the last call, to enforce a [`RateLimitPolicy`].
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    policy: RateLimitPolicy,
+    last_call: Cell<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(policy: RateLimitPolicy) -> Self {
+        RateLimiter {
+            policy,
+            last_call: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn policy(&self) -> RateLimitPolicy {
+        self.policy
+    }
+
+    /// Sleeps until the next call is allowed, and marks it as made.
+    pub(crate) fn wait(&self) {
+        let interval = match self.policy.min_interval() {
+            Some(interval) => interval,
+            None => return,
+        };
+
+        if let Some(last_call) = self.last_call.get() {
+            if let Some(left) = interval.checked_sub(last_call.elapsed()) {
+                thread::sleep(left);
+            }
+        }
+        self.last_call.set(Some(Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_calculates_the_interval() {
+        let policy = |calls_per_second| RateLimitPolicy { calls_per_second };
+
+        assert_eq!(policy(0).min_interval(), None);
+        assert_eq!(policy(1).min_interval(), Some(Duration::from_secs(1)));
+        assert_eq!(policy(4).min_interval(), Some(Duration::from_millis(250)));
+        assert_eq!(RateLimitPolicy::default().min_interval(), None);
+    }
+
+    #[test]
+    fn it_spaces_out_calls() {
+        let limiter = RateLimiter::new(RateLimitPolicy {
+            calls_per_second: 20,
+        });
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.wait();
+        }
+
+        // The first call goes through right away.
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn it_does_not_wait_without_a_limit() {
+        let limiter = RateLimiter::default();
+
+        let start = Instant::now();
+        for _ in 0..100 {
+            limiter.wait();
+        }
+
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
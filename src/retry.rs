@@ -0,0 +1,140 @@
+use std::thread;
+use std::time::Duration;
+
+use super::{DBusError, DBusErrorKind};
+
+/// Controls how [`Player`](crate::Player) calls are retried when they fail with a transient
+/// error.
+///
+/// Only errors of the kinds [`NoReply`](DBusErrorKind::NoReply) and
+/// [`Timeout`](DBusErrorKind::Timeout) are retried, as other errors would just fail again.
+///
+/// Only calls that can safely be sent more than once are retried: reading and setting properties,
+/// and methods like `Play`, `Stop` or `SetPosition`. A call that timed out may still have reached
+/// the player, so calls like `Next`, `Seek`, `OpenUri` or `AddTrack` are sent only once.
+///
+/// The default policy does not retry.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::{PlayerFinder, RetryPolicy};
+/// # use std::time::Duration;
+/// let finder = PlayerFinder::new()
+///     .expect("Could not connect to D-Bus")
+///     .with_retry_policy(RetryPolicy {
+///         max_attempts: 3,
+///         delay: Duration::from_millis(50),
+///     });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The total number of attempts, including the first one. Both 0 and 1 means that nothing is
+    /// retried.
+    pub max_attempts: u32,
+
+    /// How long to wait before retrying.
+    pub delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            delay: Duration::from_millis(0),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `f` until it succeeds, fails with a non-transient error, or runs out of attempts.
+    pub(crate) fn run<T, F>(&self, mut f: F) -> Result<T, DBusError>
+    where
+        F: FnMut() -> Result<T, DBusError>,
+    {
+        let mut attempt = 1;
+        loop {
+            match f() {
+                Err(ref error) if attempt < self.max_attempts && is_transient(error) => {
+                    thread::sleep(self.delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+fn is_transient(error: &DBusError) -> bool {
+    matches!(
+        error.kind(),
+        DBusErrorKind::NoReply | DBusErrorKind::Timeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(name: &str) -> DBusError {
+        DBusError::from(dbus::Error::new_custom(name, "message"))
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            delay: Duration::from_millis(0),
+        }
+    }
+
+    #[test]
+    fn it_retries_transient_errors() {
+        let mut calls = 0;
+        let result = policy(3).run(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(error("org.freedesktop.DBus.Error.NoReply"))
+            } else {
+                Ok(calls)
+            }
+        });
+
+        assert_eq!(result.ok(), Some(3));
+    }
+
+    #[test]
+    fn it_gives_up_after_max_attempts() {
+        let mut calls = 0;
+        let result: Result<(), DBusError> = policy(2).run(|| {
+            calls += 1;
+            Err(error("org.freedesktop.DBus.Error.Timeout"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn it_does_not_retry_other_errors() {
+        let mut calls = 0;
+        let result: Result<(), DBusError> = policy(3).run(|| {
+            calls += 1;
+            Err(error("org.freedesktop.DBus.Error.InvalidArgs"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn it_does_not_retry_by_default() {
+        let mut calls = 0;
+        let result: Result<(), DBusError> = RetryPolicy::default().run(|| {
+            calls += 1;
+            Err(error("org.freedesktop.DBus.Error.NoReply"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
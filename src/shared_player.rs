@@ -0,0 +1,310 @@
+use std::ops::Range;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use super::{
+    BusType, DBusError, LoopStatus, Metadata, PlaybackStatus, Player, PlayerCapabilities,
+    PlayerProperties, Playlist, PlaylistID, PlaylistOrdering, Progress, RootProperties, TrackID,
+    TrackList, Volume,
+};
+use crate::worker::{self, forward_to_player, Job, WorkerConfig};
+
+/// A handle to a MPRIS-compatible player that can be shared between threads.
+///
+/// The D-Bus connection used by [`Player`] cannot be sent to another thread, so a
+/// [`SharedPlayer`] owns a worker thread with its own connection and a [`Player`]. Every method
+/// call is sent to that thread, behind a lock, and blocks until the reply comes back.
+///
+/// The methods mirror the ones on [`Player`] and return the same [`DBusError`]s. Arguments that
+/// [`Player`] borrows are taken by value here, as they have to be sent to the worker thread.
+///
+/// Cloning a [`SharedPlayer`] gives you another handle to the same worker thread. The thread
+/// shuts down when the last handle is dropped.
+///
+/// ```rust,no_run
+/// use mpris::{PlayerFinder, SharedPlayer};
+/// use std::thread;
+///
+/// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// let shared = SharedPlayer::from(player);
+///
+/// let background = shared.clone();
+/// thread::spawn(move || background.pause().unwrap())
+///     .join()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedPlayer {
+    sender: Arc<Mutex<mpsc::Sender<Job>>>,
+    bus_name: String,
+    unique_name: String,
+    identity: String,
+    has_tracklist_interface: bool,
+}
+
+impl SharedPlayer {
+    /// Create a new [`SharedPlayer`] for the given bus name and path, with a new D-Bus session
+    /// connection.
+    ///
+    /// If no player is running on this bus name an [`Err`] will be returned.
+    pub fn new<B, P>(bus_name: B, path: P, timeout_ms: i32) -> Result<Self, DBusError>
+    where
+        B: Into<String>,
+        P: Into<String>,
+    {
+        let config = WorkerConfig::new(
+            BusType::SessionBus,
+            bus_name.into(),
+            path.into(),
+            timeout_ms,
+        )?;
+        let bus_name = config.bus_name.clone();
+
+        let (details_sender, details_receiver) = mpsc::channel();
+        let sender = worker::spawn(config, move |details| details_sender.send(details).is_ok());
+
+        let details = details_receiver.recv().map_err(|_| worker_stopped())??;
+
+        Ok(SharedPlayer {
+            sender: Arc::new(Mutex::new(sender)),
+            bus_name,
+            unique_name: details.unique_name,
+            identity: details.identity,
+            has_tracklist_interface: details.has_tracklist_interface,
+        })
+    }
+
+    /// Run a closure with the underlying [`Player`] on the worker thread and return its result.
+    ///
+    /// This can be used to reach [`Player`] methods that do not have a version here.
+    pub fn call<T, F>(&self, f: F) -> Result<T, DBusError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Player<'_>) -> Result<T, DBusError> + Send + 'static,
+    {
+        let (reply_sender, reply_receiver) = mpsc::channel();
+
+        self.sender
+            .lock()
+            .map_err(|_| worker_stopped())?
+            .send(Box::new(move |player| {
+                let _ = reply_sender.send(f(player));
+            }))
+            .map_err(|_| worker_stopped())?;
+
+        reply_receiver.recv().map_err(|_| worker_stopped())?
+    }
+
+    /// Returns the player's D-Bus bus name.
+    pub fn bus_name(&self) -> &str {
+        &self.bus_name
+    }
+
+    /// Returns the player's unique D-Bus bus name (usually something like `:1.1337`).
+    pub fn unique_name(&self) -> &str {
+        &self.unique_name
+    }
+
+    /// Returns the player's MPRIS [`Identity`][identity].
+    ///
+    /// [identity]: https://specifications.freedesktop.org/mpris-spec/latest/Media_Player.html#Property:Identity
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// Checks if the Player implements the `org.mpris.MediaPlayer2.TrackList` interface.
+    pub fn supports_track_lists(&self) -> bool {
+        self.has_tracklist_interface
+    }
+
+    /// Shared version of [`Player::is_running`].
+    ///
+    /// Returns [`false`] if the worker thread has stopped.
+    pub fn is_running(&self) -> bool {
+        self.call(|player| Ok(player.is_running())).unwrap_or(false)
+    }
+
+    /// Shared version of [`Player::checked_can_edit_tracks`].
+    pub fn checked_can_edit_tracks(&self) -> bool {
+        self.call(|player| Ok(player.checked_can_edit_tracks()))
+            .unwrap_or(false)
+    }
+}
+
+impl<'a> From<Player<'a>> for SharedPlayer {
+    /// Moves the player to a worker thread, with a new connection to the same bus and the same
+    /// timeouts, retry policy, rate limit and automatic reconnects.
+    ///
+    /// If the worker thread cannot reach the player, every call returns an error.
+    fn from(player: Player<'a>) -> Self {
+        // The details of the player are known already.
+        let sender = worker::spawn(WorkerConfig::of(&player), |_| true);
+
+        SharedPlayer {
+            sender: Arc::new(Mutex::new(sender)),
+            bus_name: player.bus_name().to_string(),
+            unique_name: player.unique_name().to_owned(),
+            identity: player.identity().to_owned(),
+            has_tracklist_interface: player.supports_track_lists(),
+        }
+    }
+}
+
+forward_to_player! {
+    impl SharedPlayer as "Shared";
+
+    get_desktop_entry() -> Option<String> = |p| p.get_desktop_entry();
+    get_supported_mime_types() -> Vec<String> = |p| p.get_supported_mime_types();
+    get_supported_uri_schemes() -> Vec<String> = |p| p.get_supported_uri_schemes();
+    get_has_track_list() -> bool = |p| p.get_has_track_list();
+
+    get_position() -> Duration = |p| p.get_position();
+    checked_get_position() -> Option<Duration> = |p| p.checked_get_position();
+    get_position_in_microseconds() -> u64 = |p| p.get_position_in_microseconds();
+    set_position(track_id: TrackID, position: Duration) -> () =
+        |p| p.set_position(track_id, &position);
+    checked_set_position(track_id: TrackID, position: Duration) -> bool =
+        |p| p.checked_set_position(track_id, &position);
+    set_position_in_microseconds(track_id: TrackID, position_in_us: u64) -> () =
+        |p| p.set_position_in_microseconds(track_id, position_in_us);
+
+    get_playback_rate() -> f64 = |p| p.get_playback_rate();
+    checked_get_playback_rate() -> Option<f64> = |p| p.checked_get_playback_rate();
+    set_playback_rate(rate: f64) -> () = |p| p.set_playback_rate(rate);
+    checked_set_playback_rate(rate: f64) -> bool = |p| p.checked_set_playback_rate(rate);
+    get_minimum_playback_rate() -> f64 = |p| p.get_minimum_playback_rate();
+    get_maximum_playback_rate() -> f64 = |p| p.get_maximum_playback_rate();
+    get_valid_playback_rate_range() -> Range<f64> = |p| p.get_valid_playback_rate_range();
+
+    get_all_properties() -> PlayerProperties = |p| p.get_all_properties();
+    get_all_root_properties() -> RootProperties = |p| p.get_all_root_properties();
+    get_capabilities() -> PlayerCapabilities = |p| p.get_capabilities();
+    get_metadata() -> Metadata = |p| p.get_metadata();
+    get_current_track_id() -> Option<TrackID> = |p| p.get_current_track_id();
+    get_progress() -> Progress = |p| p.get_progress();
+    get_track_list() -> TrackList = |p| p.get_track_list();
+    checked_get_track_list() -> Option<TrackList> = |p| p.checked_get_track_list();
+    can_edit_tracks() -> bool = |p| p.can_edit_tracks();
+    get_tracks_metadata(track_ids: Vec<TrackID>) -> Vec<Metadata> =
+        |p| p.get_tracks_metadata(&track_ids);
+    get_track_metadata(track_id: TrackID) -> Metadata = |p| p.get_track_metadata(&track_id);
+
+    play_pause() -> () = |p| p.play_pause();
+    toggle_play() -> () = |p| p.toggle_play();
+    play() -> () = |p| p.play();
+    pause() -> () = |p| p.pause();
+    stop() -> () = |p| p.stop();
+    next() -> () = |p| p.next();
+    previous() -> () = |p| p.previous();
+    seek(offset_in_microseconds: i64) -> () = |p| p.seek(offset_in_microseconds);
+    seek_forwards(offset: Duration) -> () = |p| p.seek_forwards(&offset);
+    seek_backwards(offset: Duration) -> () = |p| p.seek_backwards(&offset);
+    raise() -> () = |p| p.raise();
+    checked_raise() -> bool = |p| p.checked_raise();
+    quit() -> () = |p| p.quit();
+    checked_quit() -> bool = |p| p.checked_quit();
+
+    go_to(track_id: TrackID) -> () = |p| p.go_to(&track_id);
+    add_track(uri: String, after: TrackID, set_as_current: bool) -> () =
+        |p| p.add_track(&uri, &after, set_as_current);
+    add_track_at_start(uri: String, set_as_current: bool) -> () =
+        |p| p.add_track_at_start(&uri, set_as_current);
+    remove_track(track_id: TrackID) -> () = |p| p.remove_track(&track_id);
+
+    get_playlist_count() -> u32 = |p| p.get_playlist_count();
+    get_playlist_orderings() -> Vec<PlaylistOrdering> = |p| p.get_playlist_orderings();
+    get_playlists(index: u32, max_count: u32, order: PlaylistOrdering, reverse_order: bool)
+        -> Vec<Playlist> = |p| p.get_playlists(index, max_count, order, reverse_order);
+    get_active_playlist() -> Option<Playlist> = |p| p.get_active_playlist();
+    activate_playlist(playlist_id: PlaylistID) -> () = |p| p.activate_playlist(&playlist_id);
+
+    open_uri(uri: String) -> () = |p| p.open_uri(&uri);
+    can_open_uri() -> bool = |p| p.can_open_uri();
+
+    checked_play_pause() -> bool = |p| p.checked_play_pause();
+    checked_play() -> bool = |p| p.checked_play();
+    checked_pause() -> bool = |p| p.checked_pause();
+    checked_stop() -> bool = |p| p.checked_stop();
+    checked_next() -> bool = |p| p.checked_next();
+    checked_previous() -> bool = |p| p.checked_previous();
+    checked_seek(offset_in_microseconds: i64) -> bool = |p| p.checked_seek(offset_in_microseconds);
+    checked_seek_forwards(offset: Duration) -> bool = |p| p.checked_seek_forwards(&offset);
+    checked_seek_backwards(offset: Duration) -> bool = |p| p.checked_seek_backwards(&offset);
+
+    can_raise() -> bool = |p| p.can_raise();
+    can_quit() -> bool = |p| p.can_quit();
+    can_set_fullscreen() -> bool = |p| p.can_set_fullscreen();
+    can_control() -> bool = |p| p.can_control();
+    can_go_next() -> bool = |p| p.can_go_next();
+    can_go_previous() -> bool = |p| p.can_go_previous();
+    can_pause() -> bool = |p| p.can_pause();
+    can_play() -> bool = |p| p.can_play();
+    can_seek() -> bool = |p| p.can_seek();
+    can_stop() -> bool = |p| p.can_stop();
+    can_set_playback_rate() -> bool = |p| p.can_set_playback_rate();
+    can_shuffle() -> bool = |p| p.can_shuffle();
+    can_loop() -> bool = |p| p.can_loop();
+    has_playback_rate() -> bool = |p| p.has_playback_rate();
+    has_position() -> bool = |p| p.has_position();
+    has_volume() -> bool = |p| p.has_volume();
+
+    get_fullscreen() -> Option<bool> = |p| p.get_fullscreen();
+    set_fullscreen(new_state: bool) -> bool = |p| p.set_fullscreen(new_state);
+    checked_set_fullscreen(new_state: bool) -> bool = |p| p.checked_set_fullscreen(new_state);
+    get_playback_status() -> PlaybackStatus = |p| p.get_playback_status();
+    get_shuffle() -> bool = |p| p.get_shuffle();
+    checked_get_shuffle() -> Option<bool> = |p| p.checked_get_shuffle();
+    set_shuffle(state: bool) -> () = |p| p.set_shuffle(state);
+    checked_set_shuffle(state: bool) -> bool = |p| p.checked_set_shuffle(state);
+    get_loop_status() -> LoopStatus = |p| p.get_loop_status();
+    checked_get_loop_status() -> Option<LoopStatus> = |p| p.checked_get_loop_status();
+    set_loop_status(status: LoopStatus) -> () = |p| p.set_loop_status(status);
+    checked_set_loop_status(status: LoopStatus) -> bool = |p| p.checked_set_loop_status(status);
+    get_volume() -> f64 = |p| p.get_volume();
+    checked_get_volume() -> Option<f64> = |p| p.checked_get_volume();
+    set_volume(volume: Volume) -> () = |p| p.set_volume(volume);
+    checked_set_volume(volume: Volume) -> bool = |p| p.checked_set_volume(volume);
+    set_volume_checked(volume: Volume) -> bool = |p| p.set_volume_checked(volume);
+}
+
+fn worker_stopped() -> DBusError {
+    worker::stopped("SharedPlayer")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::FakePlayer;
+    use std::thread;
+
+    #[test]
+    fn it_can_be_used_from_other_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedPlayer>();
+
+        let fake = FakePlayer::spawn_with_status("Paused");
+        let shared = SharedPlayer::from(fake.find());
+        assert_eq!(shared.identity(), "Fake player");
+
+        let background = shared.clone();
+        thread::spawn(move || background.play().unwrap())
+            .join()
+            .unwrap();
+        assert!(fake.received_calls().iter().any(|call| call == "Play"));
+        assert_eq!(
+            shared.get_playback_status().unwrap(),
+            PlaybackStatus::Paused
+        );
+    }
+
+    #[test]
+    fn it_fails_to_create_players_for_missing_bus_names() {
+        let result = SharedPlayer::new(
+            "org.mpris.MediaPlayer2.mpris_rs_missing_player",
+            "/org/mpris/MediaPlayer2",
+            100,
+        );
+
+        assert!(result.is_err());
+    }
+}
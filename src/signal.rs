@@ -0,0 +1,503 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use dbus::Message;
+
+use super::{DBusError, Metadata, MetadataValue, Player, Playlist, TrackID};
+use crate::extensions::DurationExtensions;
+use crate::pooled_connection::SignalPoll;
+
+/// A signal emitted by a [`Player`] over D-Bus.
+///
+/// Unlike [`Event`](crate::Event), these are the raw MPRIS signals rather than changes that have
+/// been detected by comparing the player's state before and after the signal. This also means
+/// that you will see every signal, even if it did not change anything.
+///
+/// See [`Player::subscribe_events`].
+#[derive(Debug, Clone)]
+pub enum PlayerEvent {
+    /// Properties of the `org.mpris.MediaPlayer2.Player` or `org.mpris.MediaPlayer2.TrackList`
+    /// interfaces changed.
+    ///
+    /// See: [`PropertiesChanged` from the D-Bus specification][properties_changed].
+    ///
+    /// [properties_changed]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces-properties
+    PropertiesChanged {
+        /// The new values of the properties that changed, with the property name as key.
+        ///
+        /// Properties that are marked as `invalidates` in the MPRIS specification will not have
+        /// their values included.
+        changed: HashMap<String, MetadataValue>,
+    },
+
+    /// The player seeked, or the position changed in a way that is inconsistent with the current
+    /// playing state.
+    ///
+    /// See: [MPRIS2 specification about `Seeked`][seeked].
+    ///
+    /// [seeked]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Signal:Seeked
+    Seeked {
+        /// The new position.
+        position: Duration,
+    },
+
+    /// The entire track list was replaced.
+    ///
+    /// See: [MPRIS2 specification about `TrackListReplaced`][replaced].
+    ///
+    /// [replaced]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackListReplaced
+    TrackListReplaced {
+        /// The new tracks, in order.
+        ids: Vec<TrackID>,
+        /// The current track.
+        current: TrackID,
+    },
+
+    /// A track was added to the track list.
+    ///
+    /// See: [MPRIS2 specification about `TrackAdded`][added].
+    ///
+    /// [added]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackAdded
+    TrackAdded {
+        /// The metadata of the new track.
+        metadata: Metadata,
+        /// The track that the new track was inserted after.
+        after: TrackID,
+    },
+
+    /// A track was removed from the track list.
+    ///
+    /// See: [MPRIS2 specification about `TrackRemoved`][removed].
+    ///
+    /// [removed]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackRemoved
+    TrackRemoved {
+        /// The track that was removed.
+        id: TrackID,
+    },
+
+    /// The metadata of a track on the track list changed.
+    ///
+    /// See: [MPRIS2 specification about `TrackMetadataChanged`][changed].
+    ///
+    /// [changed]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackMetadataChanged
+    TrackMetadataChanged {
+        /// The id of the track *before* the change. The new id might be part of `metadata`.
+        id: TrackID,
+        /// The new metadata of the track.
+        metadata: Metadata,
+    },
+
+    /// The name or icon of one of the player's playlists changed.
+    ///
+    /// See: [MPRIS2 specification about `PlaylistChanged`][changed].
+    ///
+    /// [changed]: https://specifications.freedesktop.org/mpris-spec/latest/Playlists_Interface.html#Signal:PlaylistChanged
+    PlaylistChanged {
+        /// The playlist, with its new name and icon.
+        playlist: Playlist,
+    },
+
+    /// Playback stopped.
+    ///
+    /// MPRIS has no dedicated signal for this; it is emitted right after a
+    /// [`PropertiesChanged`](Self::PropertiesChanged) that changed `PlaybackStatus` to `Stopped`,
+    /// which is what players do when they reach the end of the last track.
+    EndOfTrack,
+}
+
+/// Iterator that blocks until the [`Player`] emits a signal, and then returns it as a
+/// [`PlayerEvent`].
+///
+/// Iteration stops when the player quits. Signals that are not part of MPRIS are skipped.
+///
+/// Signals are only recorded while an [`EventIterator`] exists for the player. If you have more
+/// than one for the same player, each signal will only be returned by one of them.
+///
+/// See [`Player::subscribe_events`].
+#[derive(Debug)]
+pub struct EventIterator<'a> {
+    player: &'a Player<'a>,
+}
+
+/// Parses the position out of a [`Seeked`][seeked] signal.
+///
+/// Negative positions are returned as zero. The MPRIS specification has the position as a signed
+/// integer, but some players send an unsigned one; both are accepted.
+///
+/// ```rust
+/// use mpris::parse_seeked_signal;
+/// use std::time::Duration;
+///
+/// let message = dbus::Message::new_signal(
+///     "/org/mpris/MediaPlayer2",
+///     "org.mpris.MediaPlayer2.Player",
+///     "Seeked",
+/// )
+/// .unwrap()
+/// .append1(2_500_000i64);
+/// assert_eq!(parse_seeked_signal(&message).unwrap(), Duration::from_millis(2500));
+/// ```
+///
+/// # Errors
+///
+/// Fails with [`DBusError::InvalidMessage`] if the message is not a `Seeked` signal of the
+/// `org.mpris.MediaPlayer2.Player` interface, and with [`DBusError::TypeMismatchError`] if it does
+/// not carry a position.
+///
+/// [seeked]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Signal:Seeked
+pub fn parse_seeked_signal(message: &Message) -> Result<Duration, DBusError> {
+    let interface = message.interface();
+    let member = message.member();
+    match (interface.as_deref(), member.as_deref()) {
+        (Some("org.mpris.MediaPlayer2.Player"), Some("Seeked")) => {}
+        _ => {
+            return Err(DBusError::InvalidMessage(format!(
+                "Expected a Seeked signal of org.mpris.MediaPlayer2.Player, but got {}.{}",
+                interface.as_deref().unwrap_or_default(),
+                member.as_deref().unwrap_or_default(),
+            )))
+        }
+    }
+
+    let position_in_us = match message.read1::<i64>() {
+        Ok(position) => position.max(0) as u64,
+        Err(error) => message.read1::<u64>().map_err(|_| error)?,
+    };
+    Ok(Duration::from_micros_ext(position_in_us))
+}
+
+/// The most recent signals of a [`Player`], oldest first.
+///
+/// See [`Player::with_event_history`].
+#[derive(Debug)]
+pub(crate) struct EventHistory {
+    capacity: usize,
+    events: VecDeque<PlayerEvent>,
+}
+
+/// Wraps an [`EventIterator`] and merges [`PropertiesChanged`](PlayerEvent::PropertiesChanged)
+/// signals that arrive in quick succession into a single event.
+///
+/// Some players emit several `PropertiesChanged` signals within a millisecond when seeking or
+/// changing tracks. After a `PropertiesChanged` signal, the debouncer waits for the given window
+/// and merges every `PropertiesChanged` signal that directly follows it, keeping the most recent
+/// value of each property. Other signals, like [`Seeked`](PlayerEvent::Seeked), are never merged
+/// and keep their order.
+///
+/// ```rust,no_run
+/// use mpris::{EventDebouncer, PlayerFinder};
+/// use std::time::Duration;
+///
+/// let player = PlayerFinder::new().unwrap().find_active().unwrap();
+/// let events = EventDebouncer::new(player.subscribe_events(), Duration::from_millis(50));
+/// for event in events {
+///     println!("{:?}", event);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EventDebouncer<'a> {
+    events: EventIterator<'a>,
+    window: Duration,
+    pending: VecDeque<PlayerEvent>,
+}
+
+impl<'a> EventIterator<'a> {
+    pub(crate) fn new(player: &'a Player<'a>) -> Self {
+        player.connection().subscribe_signals(player.unique_name());
+        EventIterator { player }
+    }
+
+    /// Records signals for the given duration, and then returns every signal of the player that is
+    /// waiting to be read, without blocking any further.
+    fn collect_for(&self, duration: Duration) -> Vec<PlayerEvent> {
+        let connection = self.player.connection();
+        connection.process_events_blocking_for(duration);
+
+        let mut events = Vec::new();
+        while let SignalPoll::Signal(event) = connection.next_signal(self.player.unique_name()) {
+            events.push(event);
+        }
+        events
+    }
+}
+
+impl EventHistory {
+    pub(crate) fn new(capacity: usize) -> Self {
+        EventHistory {
+            capacity,
+            events: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Adds an event, dropping the oldest one when the history is full.
+    pub(crate) fn push(&mut self, event: PlayerEvent) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub(crate) fn to_vec(&self) -> Vec<PlayerEvent> {
+        self.events.iter().cloned().collect()
+    }
+}
+
+impl<'a> EventDebouncer<'a> {
+    /// Creates a debouncer that merges the `PropertiesChanged` signals of `events` that arrive
+    /// within `window` of each other.
+    pub fn new(events: EventIterator<'a>, window: Duration) -> Self {
+        EventDebouncer {
+            events,
+            window,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for EventIterator<'a> {
+    type Item = PlayerEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let connection = self.player.connection();
+        let unique_name = self.player.unique_name();
+
+        loop {
+            match connection.next_signal(unique_name) {
+                SignalPoll::Signal(event) => return Some(event),
+                SignalPoll::PlayerQuit => return None,
+                SignalPoll::Empty => {
+                    if !self.player.is_running() {
+                        return None;
+                    }
+                    connection.process_events_blocking_until_received();
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for EventDebouncer<'a> {
+    type Item = PlayerEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.pending.pop_front() {
+            Some(event) => event,
+            None => self.events.next()?,
+        };
+        let mut changed = match first {
+            PlayerEvent::PropertiesChanged { changed } => changed,
+            other => return Some(other),
+        };
+
+        if self.pending.is_empty() {
+            self.pending.extend(self.events.collect_for(self.window));
+        }
+        while let Some(PlayerEvent::PropertiesChanged { .. }) = self.pending.front() {
+            if let Some(PlayerEvent::PropertiesChanged { changed: newer }) =
+                self.pending.pop_front()
+            {
+                changed.extend(newer);
+            }
+        }
+        Some(PlayerEvent::PropertiesChanged { changed })
+    }
+}
+
+impl<'a> Drop for EventIterator<'a> {
+    fn drop(&mut self) {
+        self.player
+            .connection()
+            .unsubscribe_signals(self.player.unique_name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_player::FakePlayer;
+    use crate::player::MPRIS2_PATH;
+    use dbus::arg::{RefArg, Variant};
+
+    fn properties_changed<T: RefArg + 'static>(property: &'static str, value: T) -> Message {
+        let mut changed: HashMap<&str, Variant<Box<dyn RefArg>>> = HashMap::new();
+        changed.insert(property, Variant(Box::new(value)));
+        Message::new_signal(
+            MPRIS2_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+        )
+        .unwrap()
+        .append3(
+            "org.mpris.MediaPlayer2.Player",
+            changed,
+            Vec::<String>::new(),
+        )
+    }
+
+    fn seeked(seconds: u64) -> PlayerEvent {
+        PlayerEvent::Seeked {
+            position: Duration::from_secs(seconds),
+        }
+    }
+
+    fn positions(events: &[PlayerEvent]) -> Vec<u64> {
+        events
+            .iter()
+            .map(|event| match event {
+                PlayerEvent::Seeked { position } => position.as_secs(),
+                other => panic!("Unexpected event: {:?}", other),
+            })
+            .collect()
+    }
+
+    fn seeked_signal() -> Message {
+        Message::new_signal(MPRIS2_PATH, "org.mpris.MediaPlayer2.Player", "Seeked").unwrap()
+    }
+
+    #[test]
+    fn it_parses_seeked_signals() {
+        let signal = seeked_signal().append1(1_500_000i64);
+        assert_eq!(
+            parse_seeked_signal(&signal).unwrap(),
+            Duration::from_millis(1500)
+        );
+
+        let unsigned = seeked_signal().append1(3_000_000u64);
+        assert_eq!(
+            parse_seeked_signal(&unsigned).unwrap(),
+            Duration::from_secs(3)
+        );
+
+        let negative = seeked_signal().append1(-1i64);
+        assert_eq!(
+            parse_seeked_signal(&negative).unwrap(),
+            Duration::from_secs(0)
+        );
+    }
+
+    #[test]
+    fn it_rejects_other_messages_as_seeked() {
+        let other = Message::new_signal(MPRIS2_PATH, "org.mpris.MediaPlayer2.Player", "Other")
+            .unwrap()
+            .append1(0i64);
+        assert!(matches!(
+            parse_seeked_signal(&other),
+            Err(DBusError::InvalidMessage(_))
+        ));
+
+        let empty = seeked_signal();
+        assert!(matches!(
+            parse_seeked_signal(&empty),
+            Err(DBusError::TypeMismatchError(_))
+        ));
+    }
+
+    #[test]
+    fn history_keeps_the_latest_events_in_order() {
+        let mut history = EventHistory::new(3);
+        for seconds in 1..=5 {
+            history.push(seeked(seconds));
+        }
+        assert_eq!(positions(&history.to_vec()), vec![3, 4, 5]);
+
+        let mut disabled = EventHistory::new(0);
+        disabled.push(seeked(1));
+        assert!(disabled.to_vec().is_empty());
+    }
+
+    #[test]
+    fn players_record_their_signals() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find().with_event_history(2);
+        let mut events = player.subscribe_events();
+
+        fake.emit(properties_changed("Volume", 0.25));
+        fake.emit(properties_changed("Volume", 0.5));
+        fake.emit(properties_changed("Volume", 0.75));
+        for _ in 0..3 {
+            events.next();
+        }
+
+        let volumes: Vec<_> = player
+            .event_history()
+            .into_iter()
+            .map(|event| match event {
+                PlayerEvent::PropertiesChanged { changed } => changed["Volume"].clone(),
+                other => panic!("Unexpected event: {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            volumes,
+            vec![MetadataValue::F64(0.5), MetadataValue::F64(0.75)]
+        );
+    }
+
+    #[test]
+    fn it_merges_properties_changed_within_the_window() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let mut events = EventDebouncer::new(player.subscribe_events(), Duration::from_millis(50));
+
+        fake.emit(properties_changed("PlaybackStatus", String::from("Paused")));
+        fake.emit(properties_changed("Volume", 0.25));
+        fake.emit(
+            Message::new_signal(
+                MPRIS2_PATH,
+                "org.mpris.MediaPlayer2.TrackList",
+                "TrackRemoved",
+            )
+            .unwrap()
+            .append1(dbus::Path::from("/fake/track/1")),
+        );
+        fake.emit(properties_changed("Shuffle", true));
+
+        match events.next() {
+            Some(PlayerEvent::PropertiesChanged { changed }) => {
+                assert_eq!(changed.len(), 2);
+                assert_eq!(changed["PlaybackStatus"], MetadataValue::from("Paused"));
+                assert_eq!(changed["Volume"], MetadataValue::F64(0.25));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+        assert!(matches!(
+            events.next(),
+            Some(PlayerEvent::TrackRemoved { .. })
+        ));
+        match events.next() {
+            Some(PlayerEvent::PropertiesChanged { changed }) => {
+                assert_eq!(changed.len(), 1);
+                assert_eq!(changed["Shuffle"], MetadataValue::Bool(true));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_does_not_merge_properties_changed_across_seeked() {
+        let fake = FakePlayer::spawn();
+        let player = fake.find();
+        let mut events = EventDebouncer::new(player.subscribe_events(), Duration::from_millis(50));
+
+        fake.emit(properties_changed("Volume", 0.25));
+        fake.emit(seeked_signal().append1(1_000_000i64));
+        fake.emit(properties_changed("Shuffle", true));
+
+        assert!(matches!(
+            events.next(),
+            Some(PlayerEvent::PropertiesChanged { .. })
+        ));
+        match events.next() {
+            Some(PlayerEvent::Seeked { position }) => {
+                assert_eq!(position, Duration::from_secs(1));
+            }
+            other => panic!("Unexpected event: {:?}", other),
+        }
+        assert!(matches!(
+            events.next(),
+            Some(PlayerEvent::PropertiesChanged { .. })
+        ));
+    }
+}
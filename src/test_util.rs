@@ -0,0 +1,311 @@
+//! Helpers for testing code that uses this crate, without a D-Bus session or a running player.
+//!
+//! **Requires the `test_util` feature.**
+
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+use crate::extensions::DurationExtensions;
+use crate::{
+    DBusError, Metadata, MetadataBuilder, MetadataValue, PlaybackStatus, PlayerInterface, TrackID,
+    TrackList, Volume,
+};
+
+/// Returns the [`TrackID`] `/test/track/{n}`.
+///
+/// ```rust
+/// use mpris::test_util::track_id;
+/// assert_eq!(track_id(3).as_str(), "/test/track/3");
+/// ```
+pub fn track_id(n: u32) -> TrackID {
+    TrackID::new(format!("/test/track/{}", n)).expect("Test track IDs are valid paths")
+}
+
+/// Returns a [`TrackList`] with the tracks [`track_id(1)`](track_id) up to and including
+/// `track_id(count)`, with the [`make_metadata`] of each track already in its cache.
+///
+/// As everything is cached, the list can be iterated with
+/// [`iter_cached`](TrackList::iter_cached) without a player.
+pub fn make_track_list(count: u32) -> TrackList {
+    let mut list: TrackList = (1..=count).map(track_id).collect();
+    for n in 1..=count {
+        list.add_metadata(make_metadata(&track_id(n)));
+    }
+    list
+}
+
+/// Returns [`Metadata`] for the given track with every field of the
+/// [MPRIS metadata specification][spec] set to a recognizable test value.
+///
+/// The title, URL and track number are based on the ID, so that the metadata of different tracks
+/// can be told apart; the track number is the last part of the ID if that is a number, or `1`.
+///
+/// [spec]: https://www.freedesktop.org/wiki/Specifications/mpris-spec/metadata/
+pub fn make_metadata(id: &TrackID) -> Metadata {
+    let track_number = id
+        .as_str()
+        .rsplit('/')
+        .next()
+        .and_then(|last| last.parse::<i32>().ok())
+        .unwrap_or(1);
+
+    MetadataBuilder::new(id.as_str())
+        .title(format!("Test title {}", id))
+        .album("Test album")
+        .artists(vec!["Test artist"])
+        .length(Duration::from_secs(210))
+        .set_raw("mpris:artUrl", "file:///test/art.png")
+        .set_raw("xesam:albumArtist", strings("Test album artist"))
+        .set_raw("xesam:autoRating", 0.5)
+        .set_raw("xesam:comment", strings("Test comment"))
+        .set_raw("xesam:composer", strings("Test composer"))
+        .set_raw("xesam:contentCreated", "2020-01-01T00:00:00Z")
+        .set_raw("xesam:discNumber", 1)
+        .set_raw("xesam:genre", strings("Test genre"))
+        .set_raw("xesam:lyricist", strings("Test lyricist"))
+        .set_raw("xesam:trackNumber", track_number)
+        .set_raw("xesam:url", format!("file://{}.mp3", id).as_str())
+        .set_raw("xesam:useCount", 3)
+        .set_raw("xesam:userRating", 0.8)
+        .build()
+}
+
+fn strings(value: &str) -> MetadataValue {
+    MetadataValue::Array(vec![MetadataValue::from(value)])
+}
+
+/// A player that lives in memory, for testing code that is written against [`PlayerInterface`].
+///
+/// Its state is set up with the `with_*` methods and then changes like on a real player: `play`
+/// starts playing, `set_volume` changes the volume, and so on. Every call is recorded, so that
+/// tests can check what their code did.
+///
+/// ```rust
+/// use mpris::test_util::MockPlayer;
+/// use mpris::{Metadata, PlaybackStatus, PlayerInterface};
+///
+/// let mock = MockPlayer::new()
+///     .with_playback_status(PlaybackStatus::Paused)
+///     .with_metadata(Metadata::new("/org/example/Track/1"));
+///
+/// mock.play().unwrap();
+/// assert_eq!(mock.get_playback_status().unwrap(), PlaybackStatus::Playing);
+/// assert_eq!(mock.called_play_count(), 1);
+/// assert_eq!(mock.calls(), vec!["play", "get_playback_status"]);
+/// ```
+#[derive(Debug)]
+pub struct MockPlayer {
+    identity: String,
+    playback_status: Cell<PlaybackStatus>,
+    metadata: Metadata,
+    position: Cell<Duration>,
+    volume: Cell<f64>,
+    calls: RefCell<Vec<&'static str>>,
+}
+
+impl MockPlayer {
+    /// Creates a stopped player called `Mock player`, with empty metadata, at the start of the
+    /// track and at full volume.
+    pub fn new() -> Self {
+        MockPlayer {
+            identity: String::from("Mock player"),
+            playback_status: Cell::new(PlaybackStatus::Stopped),
+            metadata: Metadata::default(),
+            position: Cell::new(Duration::from_secs(0)),
+            volume: Cell::new(1.0),
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Sets the [`identity`](PlayerInterface::identity) of the player.
+    pub fn with_identity<S: Into<String>>(mut self, identity: S) -> Self {
+        self.identity = identity.into();
+        self
+    }
+
+    /// Sets the current playback status.
+    pub fn with_playback_status(self, status: PlaybackStatus) -> Self {
+        self.playback_status.set(status);
+        self
+    }
+
+    /// Sets the metadata of the current track.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Sets the current position in the track.
+    pub fn with_position(self, position: Duration) -> Self {
+        self.position.set(position);
+        self
+    }
+
+    /// Sets the current volume.
+    pub fn with_volume(self, volume: Volume) -> Self {
+        self.volume.set(volume.value());
+        self
+    }
+
+    /// Returns the names of every [`PlayerInterface`] method that was called so far, in order.
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.calls.borrow().clone()
+    }
+
+    /// Returns how often the [`PlayerInterface`] method with the given name was called.
+    pub fn call_count(&self, method: &str) -> usize {
+        self.calls
+            .borrow()
+            .iter()
+            .filter(|call| **call == method)
+            .count()
+    }
+
+    /// Returns how often [`play`](PlayerInterface::play) was called.
+    pub fn called_play_count(&self) -> usize {
+        self.call_count("play")
+    }
+
+    fn record(&self, method: &'static str) {
+        self.calls.borrow_mut().push(method);
+    }
+}
+
+impl Default for MockPlayer {
+    fn default() -> Self {
+        MockPlayer::new()
+    }
+}
+
+impl PlayerInterface for MockPlayer {
+    fn identity(&self) -> &str {
+        self.record("identity");
+        &self.identity
+    }
+
+    fn play(&self) -> Result<(), DBusError> {
+        self.record("play");
+        self.playback_status.set(PlaybackStatus::Playing);
+        Ok(())
+    }
+
+    fn pause(&self) -> Result<(), DBusError> {
+        self.record("pause");
+        self.playback_status.set(PlaybackStatus::Paused);
+        Ok(())
+    }
+
+    fn play_pause(&self) -> Result<(), DBusError> {
+        self.record("play_pause");
+        let status = match self.playback_status.get() {
+            PlaybackStatus::Playing => PlaybackStatus::Paused,
+            PlaybackStatus::Paused | PlaybackStatus::Stopped => PlaybackStatus::Playing,
+        };
+        self.playback_status.set(status);
+        Ok(())
+    }
+
+    fn stop(&self) -> Result<(), DBusError> {
+        self.record("stop");
+        self.playback_status.set(PlaybackStatus::Stopped);
+        self.position.set(Duration::from_secs(0));
+        Ok(())
+    }
+
+    fn next(&self) -> Result<(), DBusError> {
+        self.record("next");
+        Ok(())
+    }
+
+    fn previous(&self) -> Result<(), DBusError> {
+        self.record("previous");
+        Ok(())
+    }
+
+    fn seek(&self, offset_in_microseconds: i64) -> Result<(), DBusError> {
+        self.record("seek");
+        let position = self.position.get().as_micros_i64();
+        let new_position = position.saturating_add(offset_in_microseconds).max(0);
+        self.position
+            .set(Duration::from_micros(new_position as u64));
+        Ok(())
+    }
+
+    fn get_playback_status(&self) -> Result<PlaybackStatus, DBusError> {
+        self.record("get_playback_status");
+        Ok(self.playback_status.get())
+    }
+
+    fn get_metadata(&self) -> Result<Metadata, DBusError> {
+        self.record("get_metadata");
+        Ok(self.metadata.clone())
+    }
+
+    fn get_position(&self) -> Result<Duration, DBusError> {
+        self.record("get_position");
+        Ok(self.position.get())
+    }
+
+    fn get_volume(&self) -> Result<f64, DBusError> {
+        self.record("get_volume");
+        Ok(self.volume.get())
+    }
+
+    fn set_volume(&self, volume: Volume) -> Result<(), DBusError> {
+        self.record("set_volume");
+        self.volume.set(volume.value());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_changes_state_like_a_player() {
+        let mock = MockPlayer::new().with_position(Duration::from_secs(10));
+
+        mock.play_pause().unwrap();
+        assert_eq!(mock.get_playback_status().unwrap(), PlaybackStatus::Playing);
+        mock.play_pause().unwrap();
+        assert_eq!(mock.get_playback_status().unwrap(), PlaybackStatus::Paused);
+
+        mock.seek(-15_000_000).unwrap();
+        assert_eq!(mock.get_position().unwrap(), Duration::from_secs(0));
+        mock.seek(2_000_000).unwrap();
+        assert_eq!(mock.get_position().unwrap(), Duration::from_secs(2));
+
+        mock.set_volume(Volume::clamp(0.25)).unwrap();
+        assert_eq!(mock.get_volume().unwrap(), 0.25);
+    }
+
+    #[test]
+    fn it_makes_track_lists_with_cached_metadata() {
+        let list = make_track_list(3);
+        assert_eq!(list.ids(), &[track_id(1), track_id(2), track_id(3)]);
+
+        let cached: Vec<_> = list.iter_cached().collect();
+        assert_eq!(cached.len(), 3);
+        let (id, metadata) = &cached[1];
+        assert_eq!(metadata.track_id().as_ref(), Some(*id));
+        assert_eq!(metadata.title(), Some("Test title /test/track/2"));
+        assert_eq!(metadata.track_number(), Some(2));
+        assert_eq!(metadata.url(), Some("file:///test/track/2.mp3"));
+        assert_eq!(metadata.disc_number(), Some(1));
+        assert_eq!(metadata.user_rating(), Some(0.8));
+    }
+
+    #[test]
+    fn it_records_calls() {
+        let mock = MockPlayer::new();
+        mock.play().unwrap();
+        mock.pause().unwrap();
+        mock.play().unwrap();
+
+        assert_eq!(mock.called_play_count(), 2);
+        assert_eq!(mock.call_count("pause"), 1);
+        assert_eq!(mock.call_count("stop"), 0);
+        assert_eq!(mock.calls(), vec!["play", "pause", "play"]);
+    }
+}
@@ -0,0 +1,81 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// How long [`Player`](crate::Player) calls wait for the player to reply, per kind of call.
+///
+/// Most calls should be answered quickly, but loading the metadata of a long track list or seeking
+/// in a slow stream can take a lot longer.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use mpris::{PlayerFinder, TimeoutConfig};
+/// # use std::time::Duration;
+/// let player = PlayerFinder::new()
+///     .expect("Could not connect to D-Bus")
+///     .find_active()
+///     .expect("Could not find any player")
+///     .with_timeout_config(TimeoutConfig {
+///         get_tracks_metadata: Duration::from_secs(5),
+///         ..TimeoutConfig::default_all(Duration::from_millis(500))
+///     });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeoutConfig {
+    /// The timeout of every call that does not have its own below.
+    pub default: Duration,
+
+    /// The timeout of `GetTracksMetadata`, like in
+    /// [`Player::get_tracks_metadata`](crate::Player::get_tracks_metadata).
+    pub get_tracks_metadata: Duration,
+
+    /// The timeout of `Seek` and `SetPosition`, like in [`Player::seek`](crate::Player::seek) and
+    /// [`Player::set_position`](crate::Player::set_position).
+    pub seek: Duration,
+}
+
+// What libdbus uses for a negative timeout.
+const DBUS_DEFAULT_TIMEOUT: Duration = Duration::from_secs(25);
+
+impl TimeoutConfig {
+    /// Uses the same timeout for every kind of call.
+    pub fn default_all(timeout: Duration) -> Self {
+        TimeoutConfig {
+            default: timeout,
+            get_tracks_metadata: timeout,
+            seek: timeout,
+        }
+    }
+
+    /// Like [`default_all`](Self::default_all), with negative timeouts meaning the default of
+    /// libdbus.
+    pub(crate) fn from_millis(timeout_ms: i32) -> Self {
+        let timeout = u64::try_from(timeout_ms)
+            .map(Duration::from_millis)
+            .unwrap_or(DBUS_DEFAULT_TIMEOUT);
+        TimeoutConfig::default_all(timeout)
+    }
+}
+
+/// The timeout in the milliseconds that D-Bus calls take, saturating at `i32::MAX`.
+pub(crate) fn as_dbus_millis(timeout: Duration) -> i32 {
+    i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_milliseconds() {
+        let config = TimeoutConfig::from_millis(250);
+        assert_eq!(
+            config,
+            TimeoutConfig::default_all(Duration::from_millis(250))
+        );
+        assert_eq!(as_dbus_millis(config.seek), 250);
+
+        assert_eq!(TimeoutConfig::from_millis(-1).default, DBUS_DEFAULT_TIMEOUT);
+        assert_eq!(as_dbus_millis(Duration::from_secs(u64::MAX)), i32::MAX);
+    }
+}
@@ -1,11 +1,28 @@
+mod cache;
+mod csv;
+mod m3u;
+mod snapshot;
+#[cfg(feature = "xspf")]
+mod xspf;
+
+pub use self::cache::CacheStats;
+use self::cache::MetadataCache;
+pub use self::m3u::M3uParseError;
+pub use self::snapshot::TrackListSnapshot;
+#[cfg(feature = "xspf")]
+pub use self::xspf::XspfError;
 use super::{DBusError, Metadata, Player};
 use failure::Fail;
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{Ref, RefCell};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::iter::{FromIterator, IntoIterator};
 
-pub(crate) const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+#[cfg(feature = "async")]
+use crate::AsyncPlayer;
+
+pub(crate) const NO_TRACK_PATH: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
 
 /// Represents [the MPRIS `Track_Id` type][track_id].
 ///
@@ -14,7 +31,8 @@ pub(crate) const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
 /// let no_track = TrackID::new("/org/mpris/MediaPlayer2/TrackList/NoTrack").unwrap();
 /// ```
 ///
-/// TrackIDs must be valid D-Bus object paths according to the spec.
+/// TrackIDs must be valid D-Bus object paths according to the spec. They are ordered by their
+/// path, lexicographically.
 ///
 /// # Errors
 ///
@@ -27,7 +45,7 @@ pub(crate) const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
 /// ```
 ///
 /// [track_id]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Simple-Type:Track_Id
-#[derive(Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TrackID(pub(crate) String);
 
 /// Represents a [`MediaPlayer2.TrackList`][track_list].
@@ -38,13 +56,32 @@ pub struct TrackID(pub(crate) String);
 /// TrackLists cache metadata about tracks so multiple iterations should be fast. It also enables
 /// signals received from the Player to pre-populate metadata and to keep everything up to date.
 ///
+/// With the `track_list_index` feature, the list also keeps a set of its [`TrackID`]s so that
+/// [`contains`](Self::contains) does not have to scan the list.
+///
+/// The cache is unbounded, unless the list was created with
+/// [`with_capacity_limit`](Self::with_capacity_limit).
+///
+/// # Exporting
+///
+/// The exports, like `to_csv`, `to_m3u` and `to_xspf`, only write the metadata that is in the
+/// cache and never talk to the player. Use [`complete_cache`](Self::complete_cache) first to
+/// include the metadata of every track.
+///
 /// [track_list]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct TrackList {
     ids: Vec<TrackID>,
-    metadata_cache: RefCell<HashMap<TrackID, Metadata>>,
+    metadata_cache: RefCell<MetadataCache>,
+    #[cfg(feature = "track_list_index")]
+    index: HashSet<TrackID>,
+    #[cfg(feature = "parallel")]
+    fetch_chunk_size: usize,
 }
 
+#[cfg(feature = "parallel")]
+const DEFAULT_FETCH_CHUNK_SIZE: usize = 50;
+
 /// TrackList-related errors.
 ///
 /// This is mostly [`DBusError`] with the extra possibility of borrow errors of the internal metadata
@@ -62,11 +99,42 @@ pub enum TrackListError {
     BorrowError(String),
 }
 
+/// Iterator of the [`Metadata`] of every track on a [`TrackList`], in track order.
+///
+/// See [`TrackList::metadata_iter`].
 #[derive(Debug)]
 pub struct MetadataIter {
     order: Vec<TrackID>,
     metadata: HashMap<TrackID, Metadata>,
     current: usize,
+    // Exclusive end of the remaining tracks, moved by `next_back`.
+    tail: usize,
+}
+
+/// Iterator of the [`TrackID`]s on a [`TrackList`], in track order.
+///
+/// See [`TrackList::iter`] and [`TrackList::iter_from`].
+#[derive(Debug, Clone)]
+pub struct TrackIDIter<'a> {
+    inner: std::slice::Iter<'a, TrackID>,
+}
+
+/// Iterator of the [`TrackID`] and cached [`Metadata`] of the tracks on a [`TrackList`] that are
+/// in its cache, in track order.
+///
+/// See [`TrackList::iter_cached`].
+#[derive(Debug)]
+pub struct CachedMetadataIter<'a> {
+    ids: std::slice::Iter<'a, TrackID>,
+    cache: Ref<'a, MetadataCache>,
+}
+
+/// Iterator of the [`TrackID`] and [`Metadata`] of every track on a [`TrackList`], in track order.
+///
+/// See [`TrackList::metadata_iter_with_ids`].
+#[derive(Debug)]
+pub struct MetadataWithIdIter {
+    inner: MetadataIter,
 }
 
 impl<'a> From<dbus::Path<'a>> for TrackID {
@@ -93,6 +161,29 @@ impl<'a> From<&'a TrackID> for dbus::Path<'a> {
     }
 }
 
+/// A [`TrackID`] is serialized as its path string.
+#[cfg(feature = "serde")]
+impl serde::Serialize for TrackID {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+/// Deserializing fails if the string is not a valid D-Bus path, just like [`TrackID::new`].
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TrackID {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let id = String::deserialize(deserializer)?;
+        TrackID::new(id).map_err(serde::de::Error::custom)
+    }
+}
+
 impl fmt::Display for TrackID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -122,6 +213,60 @@ impl TrackID {
         }
     }
 
+    /// Create a [`TrackID`] from the URI of a media file, like `file:///music/song.mp3`.
+    ///
+    /// The scheme becomes the first element of the path and every part of the URI between slashes
+    /// becomes another one, with empty parts skipped. As D-Bus paths only allow ASCII letters,
+    /// digits and underscores, every other byte (including underscores themselves) is written as
+    /// `_` and two lowercase hex digits:
+    ///
+    /// ```rust
+    /// use mpris::TrackID;
+    /// let id = TrackID::from_uri("file:///music/my_song.mp3").unwrap();
+    /// assert_eq!(id.as_str(), "/file/music/my_5fsong_2emp3");
+    /// ```
+    ///
+    /// Players that derive their IDs from URIs do not all do it the same way, so this only finds
+    /// tracks of players that use this encoding; it is mostly useful for test setup.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string does not start with a URI scheme, like `file:`.
+    pub fn from_uri(uri: &str) -> Result<Self, String> {
+        let (scheme, rest) = match uri.find(':') {
+            Some(colon) => (&uri[..colon], &uri[colon + 1..]),
+            None => return Err(format!("{:?} is not a URI: it has no scheme", uri)),
+        };
+
+        let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+        if !valid_scheme {
+            return Err(format!(
+                "{:?} is not a URI: invalid scheme {:?}",
+                uri, scheme
+            ));
+        }
+
+        let mut path = String::with_capacity(uri.len() * 2);
+        for element in std::iter::once(scheme).chain(rest.split('/')) {
+            if element.is_empty() {
+                continue;
+            }
+            path.push('/');
+            for byte in element.bytes() {
+                if byte.is_ascii_alphanumeric() {
+                    path.push(byte as char);
+                } else {
+                    path.push_str(&format!("_{:02x}", byte));
+                }
+            }
+        }
+
+        TrackID::new(path)
+    }
+
     /// Return a new [`TrackID`] that matches the MPRIS standard for the "No track" sentinel value.
     ///
     /// Some APIs takes this in order to signal a missing value for a track, for example by saying
@@ -135,7 +280,13 @@ impl TrackID {
     ///
     /// [track_id]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Simple-Type:Track_Id
     pub fn no_track() -> Self {
-        TrackID(NO_TRACK.into())
+        TrackID(NO_TRACK_PATH.into())
+    }
+
+    /// Checks if this is the "No track" sentinel value, as returned by
+    /// [`no_track`](Self::no_track).
+    pub fn is_no_track(&self) -> bool {
+        self.0 == NO_TRACK_PATH
     }
 
     /// Returns a `&str` variant of the ID.
@@ -171,10 +322,40 @@ impl FromIterator<TrackID> for TrackList {
 impl TrackList {
     /// Construct a new [`TrackList`] without any existing cache.
     pub fn new(ids: Vec<TrackID>) -> TrackList {
-        TrackList {
-            metadata_cache: RefCell::new(HashMap::with_capacity(ids.len())),
+        let cache = MetadataCache::with_capacity(ids.len());
+        TrackList::with_cache(ids, cache)
+    }
+
+    /// Construct a new [`TrackList`] that caches the [`Metadata`] of at most `max_cache_entries`
+    /// tracks.
+    ///
+    /// When the cache is full, the metadata of the track that was least recently added, updated or
+    /// read is evicted to make room. Use this for very long lists where caching every track would use
+    /// too much memory; iterating such a list loads the evicted metadata from the player again.
+    pub fn with_capacity_limit(ids: Vec<TrackID>, max_cache_entries: usize) -> TrackList {
+        TrackList::with_cache(ids, MetadataCache::with_limit(max_cache_entries))
+    }
+
+    fn with_cache(ids: Vec<TrackID>, cache: MetadataCache) -> TrackList {
+        let mut list = TrackList {
+            metadata_cache: RefCell::new(cache),
             ids,
-        }
+            #[cfg(feature = "track_list_index")]
+            index: HashSet::new(),
+            #[cfg(feature = "parallel")]
+            fetch_chunk_size: DEFAULT_FETCH_CHUNK_SIZE,
+        };
+        list.rebuild_index();
+        list
+    }
+
+    /// Sets how many tracks are loaded per call when metadata is missing from the cache.
+    /// Defaults to 50; values below 1 are treated as 1.
+    ///
+    /// **Requires the `parallel` feature.**
+    #[cfg(feature = "parallel")]
+    pub fn set_fetch_chunk_size(&mut self, chunk_size: usize) {
+        self.fetch_chunk_size = chunk_size.max(1);
     }
 
     /// Get a list of [`TrackID`]s that are part of this [`TrackList`]. The order matters.
@@ -197,29 +378,136 @@ impl TrackList {
         self.ids.get(index)
     }
 
-    /// Insert a new track (via its metadata) after another one. If the provided ID cannot be found
-    /// on the list, it will be inserted at the end.
+    /// Return the [`TrackID`] of the first track, or [`None`] if the list is empty.
+    pub fn first(&self) -> Option<&TrackID> {
+        self.ids.first()
+    }
+
+    /// Return the [`TrackID`] of the last track, or [`None`] if the list is empty.
+    pub fn last(&self) -> Option<&TrackID> {
+        self.ids.last()
+    }
+
+    /// Returns an owned copy of the list and its cached metadata, which can be sent to other
+    /// threads. See [`TrackListSnapshot`].
+    pub fn snapshot(&self) -> TrackListSnapshot {
+        let metadata = self.metadata_cache.borrow().entries().clone();
+        TrackListSnapshot::new(self.ids.clone(), metadata)
+    }
+
+    /// Iterates the [`TrackID`]s on the list, in order. This is the same as iterating `&list`.
+    pub fn iter(&self) -> TrackIDIter<'_> {
+        TrackIDIter {
+            inner: self.ids.iter(),
+        }
+    }
+
+    /// Iterates the [`TrackID`]s from the first occurrence of `start` (inclusive) to the end of the
+    /// list, e.g. to play the rest of the list from a track on.
+    ///
+    /// If `start` is not on the list, the whole list is iterated.
+    pub fn iter_from<'a>(&'a self, start: &TrackID) -> TrackIDIter<'a> {
+        let index = self.index_of(start).unwrap_or(0);
+        TrackIDIter {
+            inner: self.ids[index..].iter(),
+        }
+    }
+
+    /// Checks if the [`TrackID`] is part of this [`TrackList`].
+    ///
+    /// This scans the list, unless the `track_list_index` feature is enabled.
+    pub fn contains(&self, id: &TrackID) -> bool {
+        #[cfg(feature = "track_list_index")]
+        {
+            self.index.contains(id)
+        }
+        #[cfg(not(feature = "track_list_index"))]
+        {
+            self.ids.contains(id)
+        }
+    }
+
+    /// Returns the index of the first occurrence of the [`TrackID`] on the list, or [`None`] if it
+    /// is not part of this [`TrackList`].
+    pub fn index_of(&self, id: &TrackID) -> Option<usize> {
+        self.ids.iter().position(|item_id| item_id == id)
+    }
+
+    /// Insert a new track (via its metadata) after another one. If `after` is
+    /// [`TrackID::no_track`], the track is inserted at the start of the list, like the
+    /// [`TrackAdded`][added] signal does. If the provided ID cannot be found on the list, it will be
+    /// inserted at the end.
     ///
     /// **NOTE:** This is *not* something that will affect a player's actual tracklist; this is
     /// strictly for client-side representation. Use this if you want to maintain your own instance
     /// of [`TrackList`] or to feed your code with test fixtures.
+    ///
+    /// [added]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackAdded
     pub fn insert(&mut self, after: &TrackID, metadata: Metadata) {
+        // Vec::insert inserts BEFORE the given index, but we need to insert *after* the index.
+        let index = if after.is_no_track() {
+            0
+        } else {
+            self.index_of(after)
+                .map_or_else(|| self.ids.len(), |index| index + 1)
+        };
+        self.insert_at(index, metadata);
+    }
+
+    /// Insert a new track (via its metadata) before another one. If `before` is
+    /// [`TrackID::no_track`], the track is inserted at the start of the list. If the provided ID
+    /// cannot be found on the list, it will be inserted at the end, like with
+    /// [`insert`](Self::insert).
+    ///
+    /// **NOTE:** Like [`insert`](Self::insert), this does not affect the player's actual tracklist.
+    pub fn insert_before(&mut self, before: &TrackID, metadata: Metadata) {
+        let index = if before.is_no_track() {
+            0
+        } else {
+            self.index_of(before).unwrap_or(self.ids.len())
+        };
+        self.insert_at(index, metadata);
+    }
+
+    fn insert_at(&mut self, index: usize, metadata: Metadata) {
         let new_id = match metadata.track_id() {
             Some(val) => val,
             // Cannot insert ID if there is no ID in the metadata.
             None => return,
         };
 
-        let index = self.index_of_id(after).unwrap_or_else(|| self.ids.len());
+        self.ids.insert(index, new_id.clone());
+        #[cfg(feature = "track_list_index")]
+        self.index.insert(new_id.clone());
 
-        // Vec::insert inserts BEFORE the given index, but we need to insert *after* the index.
-        if index >= self.ids.len() {
-            self.ids.push(new_id.clone());
-        } else {
-            self.ids.insert(index + 1, new_id.clone());
+        self.change_metadata(|cache| cache.insert(new_id, metadata));
+    }
+
+    /// Moves a track to right after another one, keeping its cached metadata. If `after` is
+    /// [`TrackID::no_track`], the track is moved to the start of the list.
+    ///
+    /// Returns `false` without changing anything if either ID cannot be found on the list.
+    ///
+    /// **NOTE:** Like [`insert`](Self::insert), this does not affect the player's actual tracklist.
+    pub fn move_track(&mut self, id: &TrackID, after: &TrackID) -> bool {
+        let from = match self.index_of(id) {
+            Some(index) => index,
+            None => return false,
+        };
+        if after.is_no_track() {
+            let moved = self.ids.remove(from);
+            self.ids.insert(0, moved);
+            return true;
+        }
+        if !self.ids.contains(after) {
+            return false;
         }
 
-        self.change_metadata(|cache| cache.insert(new_id, metadata));
+        let moved = self.ids.remove(from);
+        // The ID might have been before `after`, which shifted `after` one step to the front.
+        let to = self.index_of(after).map_or(from, |index| index + 1);
+        self.ids.insert(to, moved);
+        true
     }
 
     /// Removes a track from the list and metadata cache.
@@ -227,6 +515,8 @@ impl TrackList {
     /// **Note:** If the same id is present multiple times, all of them will be removed.
     pub fn remove(&mut self, id: &TrackID) {
         self.ids.retain(|existing_id| existing_id != id);
+        #[cfg(feature = "track_list_index")]
+        self.index.remove(id);
 
         self.change_metadata(|cache| cache.remove(id));
     }
@@ -234,14 +524,88 @@ impl TrackList {
     /// Clears the entire list and cache.
     pub fn clear(&mut self) {
         self.ids.clear();
+        self.rebuild_index();
         self.change_metadata(|cache| cache.clear());
     }
 
+    /// Shortens the list to at most `len` tracks, removing the cache of the tracks that are no
+    /// longer on it. Does nothing if the list is not longer than that.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.ids.len() {
+            return;
+        }
+        self.ids.truncate(len);
+        self.rebuild_index();
+        self.clear_extra_cache();
+    }
+
+    /// Removes every track for which the predicate returns `false`, together with its cache.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&TrackID) -> bool,
+    {
+        self.retain_with_metadata(|id, _| predicate(id));
+    }
+
+    /// Like [`retain`](Self::retain), but also passes the cached [`Metadata`] of each track to the
+    /// predicate, or [`None`] if it is not in the cache.
+    pub fn retain_with_metadata<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&TrackID, Option<&Metadata>) -> bool,
+    {
+        {
+            let cache = self.metadata_cache.borrow();
+            self.ids.retain(|id| predicate(id, cache.entries().get(id)));
+        }
+        self.rebuild_index();
+        self.clear_extra_cache();
+    }
+
+    /// Reverses the order of the tracks. The cache is kept as-is.
+    pub fn reverse(&mut self) {
+        self.ids.reverse();
+    }
+
+    /// Sorts the tracks by comparing their cached [`Metadata`], without talking to the player.
+    ///
+    /// Tracks without cached metadata are moved to the end. The sort is stable, so tracks that
+    /// compare equal keep their order. The cache is kept as-is.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Metadata, &Metadata) -> Ordering,
+    {
+        let cache = self.metadata_cache.borrow();
+        let entries = cache.entries();
+        self.ids
+            .sort_by(|a, b| match (entries.get(a), entries.get(b)) {
+                (Some(a), Some(b)) => compare(a, b),
+                (a, b) => missing_last(a.map(|_| ()), b.map(|_| ())),
+            });
+    }
+
+    /// Sorts the tracks by their [`title`](Metadata::title), ignoring case. Tracks without a title
+    /// are moved to the end. See [`sort_by`](Self::sort_by).
+    pub fn sort_by_title(&mut self) {
+        self.sort_by(|a, b| {
+            missing_last(
+                a.title().map(str::to_lowercase),
+                b.title().map(str::to_lowercase),
+            )
+        });
+    }
+
+    /// Sorts the tracks by their [`track_number`](Metadata::track_number). Tracks without a track
+    /// number are moved to the end. See [`sort_by`](Self::sort_by).
+    pub fn sort_by_track_number(&mut self) {
+        self.sort_by(|a, b| missing_last(a.track_number(), b.track_number()));
+    }
+
     /// Replace the contents with the contents of the provided list. Cache will be reused when
     /// possible.
     pub fn replace(&mut self, other: TrackList) {
         self.ids = other.ids;
-        let other_cache = other.metadata_cache.into_inner();
+        self.rebuild_index();
+        let other_cache = other.metadata_cache.into_inner().into_entries();
 
         self.change_metadata(|self_cache| {
             // Will overwrite existing keys on conflicts; e.g. the newer cache wins.
@@ -249,6 +613,61 @@ impl TrackList {
         });
     }
 
+    /// Returns a new list with every track of this list, followed by the tracks of `other` that
+    /// are not on this list. Every track is only included once.
+    ///
+    /// The cached metadata of both lists is copied over to the new list.
+    pub fn union(&self, other: &TrackList) -> TrackList {
+        let ids = self.ids.iter().chain(other.ids.iter());
+        self.combined_with(Some(other), ids.cloned().collect())
+    }
+
+    /// Returns a new list with the tracks of this list that are also on `other`, in the order of
+    /// this list. Every track is only included once.
+    ///
+    /// The cached metadata of both lists is copied over to the new list.
+    pub fn intersection(&self, other: &TrackList) -> TrackList {
+        let ids = self.ids.iter().filter(|id| other.contains(id));
+        self.combined_with(Some(other), ids.cloned().collect())
+    }
+
+    /// Returns a new list with the tracks of this list that are not on `other`, in the order of
+    /// this list. Every track is only included once.
+    ///
+    /// The cached metadata of this list is copied over to the new list.
+    pub fn difference(&self, other: &TrackList) -> TrackList {
+        let ids = self.ids.iter().filter(|id| !other.contains(id));
+        self.combined_with(None, ids.cloned().collect())
+    }
+
+    /// Builds a list of the given IDs without their duplicates, with metadata from the cache of
+    /// this list or else from the cache of `other`.
+    fn combined_with(&self, other: Option<&TrackList>, ids: Vec<TrackID>) -> TrackList {
+        let mut seen = HashSet::with_capacity(ids.len());
+        let ids: Vec<TrackID> = ids
+            .into_iter()
+            .filter(|id| seen.insert(id.clone()))
+            .collect();
+
+        let list = TrackList::new(ids);
+        {
+            let own_cache = self.metadata_cache.borrow();
+            let other_cache = other.map(|other| other.metadata_cache.borrow());
+            let mut cache = list.metadata_cache.borrow_mut();
+            for id in &list.ids {
+                let metadata = own_cache.entries().get(id).or_else(|| {
+                    other_cache
+                        .as_ref()
+                        .and_then(|other_cache| other_cache.entries().get(id))
+                });
+                if let Some(metadata) = metadata {
+                    cache.insert(id.clone(), metadata.clone());
+                }
+            }
+        }
+        list
+    }
+
     /// Adds/updates the metadata cache for a track (as identified by [`Metadata::track_id`]).
     ///
     /// The metadata will be added to the cache even if the [`TrackID`] isn't part of the list, but
@@ -277,8 +696,16 @@ impl TrackList {
         new_metadata: Metadata,
     ) -> Option<TrackID> {
         if let Some(new_id) = new_metadata.track_id() {
-            if let Some(index) = self.index_of_id(old_id) {
+            if let Some(index) = self.index_of(old_id) {
                 self.ids[index] = new_id.to_owned();
+                // The old ID might still be on the list if it was there more than once.
+                #[cfg(feature = "track_list_index")]
+                {
+                    if !self.ids.contains(old_id) {
+                        self.index.remove(old_id);
+                    }
+                    self.index.insert(new_id.to_owned());
+                }
                 self.change_metadata(|cache| cache.insert(new_id.to_owned(), new_metadata));
 
                 return Some(new_id);
@@ -288,23 +715,131 @@ impl TrackList {
         None
     }
 
+    /// Applies a [`TrackListReplaced`][replaced] signal: the list gets the new IDs, and the cache
+    /// of tracks that are no longer on it is removed. Nothing is loaded from the player.
+    ///
+    /// `current` is the current track that the signal carries. It is ignored, as a [`TrackList`]
+    /// does not keep track of the current track.
+    ///
+    /// [replaced]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackListReplaced
+    pub fn apply_tracklist_replaced(&mut self, new_ids: Vec<TrackID>, _current: &TrackID) {
+        self.ids = new_ids;
+        self.rebuild_index();
+        self.clear_extra_cache();
+    }
+
+    /// Applies a [`TrackAdded`][added] signal, like [`insert`](Self::insert).
+    ///
+    /// [added]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackAdded
+    pub fn apply_track_added(&mut self, metadata: Metadata, after: &TrackID) {
+        self.insert(after, metadata);
+    }
+
+    /// Applies a [`TrackRemoved`][removed] signal, like [`remove`](Self::remove).
+    ///
+    /// [removed]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackRemoved
+    pub fn apply_track_removed(&mut self, id: &TrackID) {
+        self.remove(id);
+    }
+
+    /// Applies a [`TrackMetadataChanged`][changed] signal, like
+    /// [`replace_track_metadata`](Self::replace_track_metadata), and returns the new ID of the
+    /// track.
+    ///
+    /// [changed]: https://specifications.freedesktop.org/mpris-spec/latest/Track_List_Interface.html#Signal:TrackMetadataChanged
+    pub fn apply_track_metadata_changed(
+        &mut self,
+        id: &TrackID,
+        metadata: Metadata,
+    ) -> Option<TrackID> {
+        self.replace_track_metadata(id, metadata)
+    }
+
     /// Iterates the tracks in the tracklist, returning a tuple of [`TrackID`] and [`Metadata`] for that
     /// track.
     ///
     /// [`Metadata`] will be loaded from the provided player when not present in the metadata cache.
     /// If metadata loading fails, then a [`DBusError`] will be returned instead of the iterator.
     pub fn metadata_iter(&self, player: &Player<'_>) -> Result<MetadataIter, TrackListError> {
-        self.complete_cache(player)?;
-        let metadata: HashMap<_, _> = self.metadata_cache.clone().into_inner();
+        let loaded = self.load_missing_metadata(player)?;
+        let mut metadata = {
+            let cache = self.metadata_cache.borrow();
+            cache.touch_all(&self.ids);
+            cache.entries().clone()
+        };
+        // With a capacity limit, the cache might not be able to hold all of the loaded tracks.
+        metadata.extend(loaded);
         let ids = self.ids.clone();
 
         Ok(MetadataIter {
             current: 0,
+            tail: ids.len(),
             order: ids,
             metadata,
         })
     }
 
+    /// Iterates the tracks whose [`Metadata`] is already cached, skipping the others, without
+    /// talking to the player.
+    ///
+    /// The iterator and the [`Metadata`] it yields borrow the cache, so methods that fill the cache
+    /// (like [`complete_cache`](Self::complete_cache)) fail with a
+    /// [`BorrowError`](TrackListError::BorrowError) while any of them are alive.
+    pub fn iter_cached(&self) -> CachedMetadataIter<'_> {
+        CachedMetadataIter {
+            ids: self.ids.iter(),
+            cache: self.metadata_cache.borrow(),
+        }
+    }
+
+    /// Returns a copy of the cached [`Metadata`] of a track, or [`None`] if it is not in the cache.
+    ///
+    /// This never talks to the player, so it does not block. Unlike
+    /// [`iter_cached`](Self::iter_cached), it does not keep the cache borrowed.
+    pub fn get_metadata_cached(&self, id: &TrackID) -> Option<Metadata> {
+        self.metadata_cache.borrow().get(id).cloned()
+    }
+
+    /// Returns `true` if the [`Metadata`] of the track is in the cache, without talking to the
+    /// player.
+    pub fn has_metadata_for(&self, id: &TrackID) -> bool {
+        self.metadata_cache.borrow().contains_key(id)
+    }
+
+    /// Returns the IDs of the tracks on the list that have no cached [`Metadata`], in list order.
+    ///
+    /// These are the tracks that [`complete_cache`](Self::complete_cache) would load. This never
+    /// talks to the player, so it can be used to load only some of them, like the tracks that are
+    /// visible.
+    pub fn uncached_ids(&self) -> Vec<TrackID> {
+        self.ids_without_cache().into_iter().cloned().collect()
+    }
+
+    /// Like [`metadata_iter`](Self::metadata_iter), but starts at the track with the given
+    /// [`TrackID`] instead of at the start of the list. See [`iter_from`](Self::iter_from).
+    pub fn metadata_iter_from(
+        &self,
+        start: &TrackID,
+        player: &Player<'_>,
+    ) -> Result<MetadataIter, TrackListError> {
+        let mut iter = self.metadata_iter(player)?;
+        iter.current = self.index_of(start).unwrap_or(0);
+        Ok(iter)
+    }
+
+    /// Like [`metadata_iter`](Self::metadata_iter), but yields the [`TrackID`] of each track
+    /// together with its [`Metadata`].
+    ///
+    /// The [`TrackID`] is the one on the list, so it is there even if the [`Metadata`] does not
+    /// contain one.
+    pub fn metadata_iter_with_ids(
+        &self,
+        player: &Player<'_>,
+    ) -> Result<MetadataWithIdIter, TrackListError> {
+        self.metadata_iter(player)
+            .map(|inner| MetadataWithIdIter { inner })
+    }
+
     /// Reloads the tracklist from the given player. This can be compared with loading a new track
     /// list, but in this case the metadata cache can be maintained for tracks that remain on the
     /// list.
@@ -312,6 +847,18 @@ impl TrackList {
     /// Cache for tracks that are no longer part of the player's tracklist will be removed.
     pub fn reload(&mut self, player: &Player<'_>) -> Result<(), TrackListError> {
         self.ids = player.get_track_list()?.ids;
+        self.rebuild_index();
+        self.clear_extra_cache();
+        Ok(())
+    }
+
+    /// Async version of [`reload`](Self::reload).
+    ///
+    /// **Requires the `async` feature.**
+    #[cfg(feature = "async")]
+    pub async fn reload_async(&mut self, player: &AsyncPlayer) -> Result<(), TrackListError> {
+        self.ids = player.get_track_list().await?.ids;
+        self.rebuild_index();
         self.clear_extra_cache();
         Ok(())
     }
@@ -325,11 +872,28 @@ impl TrackList {
             .ids
             .iter()
             .cloned()
-            .zip(player.get_tracks_metadata(&self.ids)?);
+            .zip(self.fetch_metadata(player, &self.ids)?);
+
+        // We only have a &self reference, so fail if we cannot borrow.
+        let mut cache = self.metadata_cache.try_borrow_mut()?;
+        cache.clear();
+        cache.extend(id_metadata);
+
+        Ok(())
+    }
+
+    /// Async version of [`reload_cache`](Self::reload_cache).
+    ///
+    /// **Requires the `async` feature.**
+    #[cfg(feature = "async")]
+    pub async fn reload_cache_async(&self, player: &AsyncPlayer) -> Result<(), TrackListError> {
+        let metadata = player.get_tracks_metadata(self.ids.clone()).await?;
+        let id_metadata = self.ids.iter().cloned().zip(metadata);
 
         // We only have a &self reference, so fail if we cannot borrow.
         let mut cache = self.metadata_cache.try_borrow_mut()?;
-        *cache = id_metadata.collect();
+        cache.clear();
+        cache.extend(id_metadata);
 
         Ok(())
     }
@@ -337,31 +901,73 @@ impl TrackList {
     /// Fill in any holes in the cache so that each track on the list has a cached [`Metadata`] entry.
     ///
     /// If all tracks already have a cache entry, then this will do nothing.
+    ///
+    /// With a [capacity limit](Self::with_capacity_limit) that is lower than the length of the
+    /// list, only the last loaded tracks will remain in the cache.
     pub fn complete_cache(&self, player: &Player<'_>) -> Result<(), TrackListError> {
-        let ids: Vec<_> = self
-            .ids_without_cache()
+        self.load_missing_metadata(player).map(|_| ())
+    }
+
+    /// Loads metadata for the tracks without a cache entry into the cache, and also returns it in
+    /// case the cache evicted some of it again.
+    fn load_missing_metadata(
+        &self,
+        player: &Player<'_>,
+    ) -> Result<HashMap<TrackID, Metadata>, TrackListError> {
+        let ids = self.uncached_ids();
+        self.metadata_cache
+            .borrow()
+            .record_lookups(self.ids.len() - ids.len(), ids.len());
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let loaded: HashMap<_, _> = self
+            .fetch_metadata(player, &ids)?
             .into_iter()
-            .map(Clone::clone)
+            .filter_map(|info| info.track_id().map(|id| (id, info)))
             .collect();
-        if !ids.is_empty() {
-            let metadata = player.get_tracks_metadata(&ids)?;
 
-            // We only have a &self reference, so fail if we cannot borrow.
-            let mut cache = self.metadata_cache.try_borrow_mut()?;
+        // We only have a &self reference, so fail if we cannot borrow.
+        let mut cache = self.metadata_cache.try_borrow_mut()?;
+        cache.extend(loaded.iter().map(|(id, info)| (id.clone(), info.clone())));
+
+        Ok(loaded)
+    }
 
-            for info in metadata.into_iter() {
-                if let Some(id) = info.track_id() {
-                    cache.insert(id, info);
-                }
-            }
+    /// Returns how often the metadata of tracks on the list was found in the cache, and how often
+    /// it had to be loaded from the player, since the list was created or
+    /// [`reset_cache_stats`](Self::reset_cache_stats) was called.
+    ///
+    /// Nothing is counted with the `no_stats` feature.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.metadata_cache.borrow().stats()
+    }
+
+    /// Sets all [`cache_stats`](Self::cache_stats) back to zero.
+    pub fn reset_cache_stats(&self) {
+        self.metadata_cache.borrow().reset_stats();
+    }
+
+    fn fetch_metadata(
+        &self,
+        player: &Player<'_>,
+        ids: &[TrackID],
+    ) -> Result<Vec<Metadata>, DBusError> {
+        #[cfg(feature = "parallel")]
+        {
+            player.get_tracks_metadata_in_chunks(ids, self.fetch_chunk_size)
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            player.get_tracks_metadata(ids)
         }
-        Ok(())
     }
 
     /// Change metadata cache. As this requires a `&mut self`, the borrow is guaranteed to work.
     fn change_metadata<T, F>(&mut self, f: F) -> T
     where
-        F: FnOnce(&mut HashMap<TrackID, Metadata>) -> T,
+        F: FnOnce(&mut MetadataCache) -> T,
     {
         let mut cache = self.metadata_cache.borrow_mut(); // Safe. &mut self reference.
         f(&mut *cache)
@@ -378,27 +984,59 @@ impl TrackList {
     fn clear_extra_cache(&mut self) {
         let ids: Vec<TrackID> = self.ids().iter().map(TrackID::from).collect();
 
-        self.change_metadata(|cache| {
-            // For each id in the list, move the cache out into a new HashMap, then replace the old
-            // one with the new. Only ids on the list will therefore be present on the new list.
-            let new_cache: HashMap<TrackID, Metadata> = ids
-                .iter()
-                .flat_map(|id| match cache.remove(id) {
-                    Some(value) => Some((id.to_owned(), value)),
-                    None => None,
-                })
-                .collect();
+        self.change_metadata(|cache| cache.retain_ids(&ids));
+    }
 
-            *cache = new_cache;
-        });
+    /// Rebuilds the set of [`TrackID`]s after the list has been changed in bulk. Does nothing
+    /// without the `track_list_index` feature.
+    fn rebuild_index(&mut self) {
+        #[cfg(feature = "track_list_index")]
+        {
+            self.index = self.ids.iter().cloned().collect();
+        }
     }
+}
 
-    fn index_of_id(&self, id: &TrackID) -> Option<usize> {
-        self.ids
-            .iter()
-            .enumerate()
-            .find(|(_, item_id)| *item_id == id)
-            .map(|(index, _)| index)
+/// Appends the tracks to the end of the list, without touching the cache.
+impl Extend<TrackID> for TrackList {
+    fn extend<I: IntoIterator<Item = TrackID>>(&mut self, iter: I) {
+        self.ids.extend(iter);
+        self.rebuild_index();
+    }
+}
+
+impl<'a> IntoIterator for &'a TrackList {
+    type Item = &'a TrackID;
+    type IntoIter = TrackIDIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Consumes the list, yielding its [`TrackID`]s in order. The cache is dropped.
+impl IntoIterator for TrackList {
+    type Item = TrackID;
+    type IntoIter = ::std::vec::IntoIter<TrackID>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ids.into_iter()
+    }
+}
+
+/// Orders present values like [`Option`] does, but with [`None`] after them instead of before.
+fn missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl Default for TrackList {
+    fn default() -> Self {
+        TrackList::new(Vec::new())
     }
 }
 
@@ -408,35 +1046,144 @@ impl PartialEq<TrackList> for TrackList {
     }
 }
 
+impl Eq for TrackList {}
+
+impl MetadataIter {
+    fn next_index(&mut self) -> Option<usize> {
+        if self.current >= self.tail {
+            return None;
+        }
+        self.current += 1;
+        Some(self.current - 1)
+    }
+
+    fn next_back_index(&mut self) -> Option<usize> {
+        if self.current >= self.tail {
+            return None;
+        }
+        self.tail -= 1;
+        Some(self.tail)
+    }
+
+    fn take_metadata(&mut self, index: usize) -> Metadata {
+        let id = &self.order[index];
+        // In case of race conditions with cache population, emit a simple Metadata without any
+        // interesting data in it.
+        self.metadata
+            .remove(id)
+            .unwrap_or_else(|| Metadata::new(id.clone()))
+    }
+
+    fn take_with_id(&mut self, index: usize) -> (TrackID, Metadata) {
+        let metadata = self.take_metadata(index);
+        (self.order[index].clone(), metadata)
+    }
+}
+
 impl Iterator for MetadataIter {
     type Item = Metadata;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.order.get(self.current) {
-            Some(next_id) => {
-                self.current += 1;
-                // In case of race conditions with cache population, emit a simple Metadata without
-                // any interesting data in it.
-                Some(
-                    self.metadata
-                        .remove(next_id)
-                        .unwrap_or_else(|| Metadata::new(next_id.clone())),
-                )
-            }
-            None => None,
-        }
+        self.next_index().map(|index| self.take_metadata(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
     }
 }
 
-impl From<DBusError> for TrackListError {
-    fn from(error: DBusError) -> TrackListError {
-        TrackListError::DBusError(error)
+impl DoubleEndedIterator for MetadataIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.next_back_index()
+            .map(|index| self.take_metadata(index))
     }
 }
 
-impl From<::std::cell::BorrowMutError> for TrackListError {
-    fn from(error: ::std::cell::BorrowMutError) -> TrackListError {
-        TrackListError::BorrowError(format!("Could not borrow mutably: {}", error))
+impl ExactSizeIterator for MetadataIter {
+    fn len(&self) -> usize {
+        self.tail.saturating_sub(self.current)
+    }
+}
+
+impl<'a> Iterator for TrackIDIter<'a> {
+    type Item = &'a TrackID;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for TrackIDIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a> ExactSizeIterator for TrackIDIter<'a> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl<'a> Iterator for CachedMetadataIter<'a> {
+    type Item = (&'a TrackID, Ref<'a, Metadata>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cache = &self.cache;
+        self.ids.find_map(|id| {
+            Ref::filter_map(Ref::clone(cache), |cache| cache.get(id))
+                .ok()
+                .map(|metadata| (id, metadata))
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.ids.size_hint().1)
+    }
+}
+
+impl Iterator for MetadataWithIdIter {
+    type Item = (TrackID, Metadata);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let inner = &mut self.inner;
+        inner.next_index().map(|index| inner.take_with_id(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for MetadataWithIdIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let inner = &mut self.inner;
+        inner
+            .next_back_index()
+            .map(|index| inner.take_with_id(index))
+    }
+}
+
+impl ExactSizeIterator for MetadataWithIdIter {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl From<DBusError> for TrackListError {
+    fn from(error: DBusError) -> TrackListError {
+        TrackListError::DBusError(error)
+    }
+}
+
+impl From<::std::cell::BorrowMutError> for TrackListError {
+    fn from(error: ::std::cell::BorrowMutError) -> TrackListError {
+        TrackListError::BorrowError(format!("Could not borrow mutably: {}", error))
     }
 }
 
@@ -448,18 +1195,160 @@ mod tests {
         TrackID::new(s).expect("Failed to parse a TrackID fixture")
     }
 
+    fn metadata_iter(ids: &[&str]) -> MetadataIter {
+        let order: Vec<TrackID> = ids.iter().map(|id| track_id(id)).collect();
+        let metadata = order
+            .iter()
+            .map(|id| (id.clone(), Metadata::new(id.as_str())))
+            .collect();
+
+        MetadataIter {
+            current: 0,
+            tail: order.len(),
+            order,
+            metadata,
+        }
+    }
+
+    #[test]
+    fn it_recognizes_the_no_track_sentinel() {
+        let no_track = TrackID::no_track();
+        assert!(no_track.is_no_track());
+        assert!(TrackID::new(no_track.as_str()).is_ok());
+        assert_eq!(
+            no_track,
+            track_id("/org/mpris/MediaPlayer2/TrackList/NoTrack")
+        );
+
+        assert!(!track_id("/path/1").is_no_track());
+    }
+
+    #[test]
+    fn it_creates_track_ids_from_uris() {
+        assert_eq!(
+            TrackID::from_uri("file:///home/me/My Music/song.mp3"),
+            Ok(track_id("/file/home/me/My_20Music/song_2emp3"))
+        );
+        assert_eq!(
+            TrackID::from_uri("https://example.com/a_b?c=1"),
+            Ok(track_id("/https/example_2ecom/a_5fb_3fc_3d1"))
+        );
+        assert_eq!(
+            TrackID::from_uri("file:///café"),
+            Ok(track_id("/file/caf_c3_a9"))
+        );
+        assert_eq!(TrackID::from_uri("spotify:"), Ok(track_id("/spotify")));
+
+        assert!(TrackID::from_uri("").is_err());
+        assert!(TrackID::from_uri("/music/song.mp3").is_err());
+        assert!(TrackID::from_uri(":no-scheme").is_err());
+        assert!(TrackID::from_uri("1http://example.com").is_err());
+    }
+
+    #[test]
+    fn it_compares_track_ids() {
+        assert_eq!(track_id("/path/1"), track_id("/path/1"));
+        assert_ne!(track_id("/path/1"), track_id("/path/2"));
+
+        assert!(track_id("/path/1") < track_id("/path/2"));
+        assert!(track_id("/path/10") < track_id("/path/2"));
+
+        let sorted: std::collections::BTreeSet<TrackID> =
+            vec![track_id("/path/b"), track_id("/path/a")]
+                .into_iter()
+                .collect();
+        assert_eq!(
+            sorted.into_iter().collect::<Vec<_>>(),
+            vec![track_id("/path/a"), track_id("/path/b")]
+        );
+    }
+
+    mod metadata_iter {
+        use super::*;
+
+        fn ids(iter: impl Iterator<Item = Metadata>) -> Vec<TrackID> {
+            iter.flat_map(|metadata| metadata.track_id()).collect()
+        }
+
+        #[test]
+        fn it_iterates_in_reverse() {
+            let iter = metadata_iter(&["/path/1", "/path/2", "/path/3"]);
+
+            assert_eq!(
+                ids(iter.rev()),
+                vec![
+                    track_id("/path/3"),
+                    track_id("/path/2"),
+                    track_id("/path/1")
+                ]
+            );
+        }
+
+        #[test]
+        fn it_meets_in_the_middle() {
+            let mut iter = metadata_iter(&["/path/1", "/path/2", "/path/3"]);
+            assert_eq!(iter.len(), 3);
+
+            assert_eq!(
+                iter.next().and_then(|m| m.track_id()),
+                Some(track_id("/path/1"))
+            );
+            assert_eq!(
+                iter.next_back().and_then(|m| m.track_id()),
+                Some(track_id("/path/3"))
+            );
+            assert_eq!(iter.len(), 1);
+
+            assert_eq!(
+                iter.next_back().and_then(|m| m.track_id()),
+                Some(track_id("/path/2"))
+            );
+            assert_eq!(iter.len(), 0);
+            assert!(iter.next().is_none());
+            assert!(iter.next_back().is_none());
+        }
+
+        #[test]
+        fn it_yields_ids_of_missing_metadata() {
+            let mut inner = metadata_iter(&["/path/1", "/path/2"]);
+            inner.metadata.clear();
+            let iter = MetadataWithIdIter { inner };
+            assert_eq!(iter.len(), 2);
+
+            let pairs: Vec<(TrackID, Metadata)> = iter.collect();
+            assert_eq!(pairs[0].0, track_id("/path/1"));
+            assert_eq!(pairs[1].0, track_id("/path/2"));
+            assert_eq!(pairs[1].1.track_id(), Some(track_id("/path/2")));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_round_trips_track_ids_through_serde() {
+        let id = track_id("/foo/1");
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, "\"/foo/1\"");
+        assert_eq!(serde_json::from_str::<TrackID>(&json).unwrap(), id);
+
+        assert!(serde_json::from_str::<TrackID>("\"not a path\"").is_err());
+
+        let no_track = serde_json::to_string(&TrackID::no_track()).unwrap();
+        assert_eq!(no_track, "\"/org/mpris/MediaPlayer2/TrackList/NoTrack\"");
+        assert!(serde_json::from_str::<TrackID>(&no_track)
+            .unwrap()
+            .is_no_track());
+    }
+
     mod track_list {
         use super::*;
+        use crate::fake_player::FakePlayer;
 
         #[test]
         fn it_inserts_after_given_id() {
             let first = track_id("/path/1");
             let third = track_id("/path/3");
 
-            let mut list = TrackList {
-                ids: vec![first, third],
-                metadata_cache: RefCell::new(HashMap::new()),
-            };
+            let mut list = TrackList::new(vec![first, third]);
 
             let metadata = Metadata::new("/path/new");
             list.insert(&track_id("/path/1"), metadata);
@@ -484,10 +1373,7 @@ mod tests {
             let first = track_id("/path/1");
             let third = track_id("/path/3");
 
-            let mut list = TrackList {
-                ids: vec![first, third],
-                metadata_cache: RefCell::new(HashMap::new()),
-            };
+            let mut list = TrackList::new(vec![first, third]);
 
             let metadata = Metadata::new("/path/new");
             list.insert(&track_id("/path/missing"), metadata);
@@ -507,6 +1393,49 @@ mod tests {
             );
         }
 
+        #[test]
+        fn it_inserts_before_given_id() {
+            let mut list = TrackList::new(vec![track_id("/path/1"), track_id("/path/3")]);
+
+            list.insert_before(&track_id("/path/1"), Metadata::new("/path/0"));
+            list.insert_before(&track_id("/path/3"), Metadata::new("/path/2"));
+            list.insert_before(&track_id("/path/missing"), Metadata::new("/path/4"));
+            list.insert_before(&TrackID::no_track(), Metadata::new("/path/start"));
+
+            assert_eq!(
+                &list.ids,
+                &[
+                    track_id("/path/start"),
+                    track_id("/path/0"),
+                    track_id("/path/1"),
+                    track_id("/path/2"),
+                    track_id("/path/3"),
+                    track_id("/path/4"),
+                ]
+            );
+            assert_eq!(
+                list.ids_without_cache(),
+                vec![&track_id("/path/1"), &track_id("/path/3")],
+            );
+        }
+
+        #[test]
+        fn it_inserts_at_start_after_no_track() {
+            let mut list = TrackList::new(vec![track_id("/path/1")]);
+
+            list.insert(&TrackID::no_track(), Metadata::new("/path/0"));
+            list.apply_track_added(Metadata::new("/path/start"), &TrackID::no_track());
+
+            assert_eq!(
+                &list.ids,
+                &[
+                    track_id("/path/start"),
+                    track_id("/path/0"),
+                    track_id("/path/1")
+                ]
+            );
+        }
+
         #[test]
         fn it_inserts_at_end_on_empty() {
             let mut list = TrackList::default();
@@ -518,5 +1447,487 @@ mod tests {
             assert_eq!(&list.ids, &[track_id("/path/new")]);
             assert!(list.ids_without_cache().is_empty());
         }
+
+        #[test]
+        fn it_finds_ids_on_the_list() {
+            let mut list = TrackList::from(vec![track_id("/path/1"), track_id("/path/2")]);
+
+            assert!(list.contains(&track_id("/path/2")));
+            assert!(!list.contains(&track_id("/path/3")));
+            assert_eq!(list.index_of(&track_id("/path/2")), Some(1));
+            assert_eq!(list.index_of(&track_id("/path/3")), None);
+
+            list.remove(&track_id("/path/2"));
+            assert!(!list.contains(&track_id("/path/2")));
+
+            list.insert(&track_id("/path/1"), Metadata::new("/path/3"));
+            assert!(list.contains(&track_id("/path/3")));
+            assert_eq!(list.index_of(&track_id("/path/3")), Some(1));
+        }
+
+        #[test]
+        fn it_returns_the_first_and_last_ids() {
+            let list = TrackList::from(vec![track_id("/path/1"), track_id("/path/2")]);
+            assert_eq!(list.first(), Some(&track_id("/path/1")));
+            assert_eq!(list.last(), Some(&track_id("/path/2")));
+
+            let empty = TrackList::default();
+            assert_eq!(empty.first(), None);
+            assert_eq!(empty.last(), None);
+        }
+
+        #[test]
+        fn it_truncates_extends_and_reverses() {
+            let mut list = TrackList::from(vec![track_id("/path/1"), track_id("/path/2")]);
+            list.add_metadata(Metadata::new("/path/1"));
+            list.add_metadata(Metadata::new("/path/2"));
+
+            list.truncate(1);
+            assert_eq!(list.ids(), &[track_id("/path/1")]);
+            assert!(!list.contains(&track_id("/path/2")));
+            assert_eq!(list.metadata_cache.borrow().entries().len(), 1);
+
+            list.extend(vec![track_id("/path/3"), track_id("/path/4")]);
+            assert!(list.contains(&track_id("/path/4")));
+            list.reverse();
+            assert_eq!(list.first(), Some(&track_id("/path/4")));
+            assert_eq!(list.last(), Some(&track_id("/path/1")));
+            assert_eq!(
+                list.ids_without_cache(),
+                vec![&track_id("/path/4"), &track_id("/path/3")]
+            );
+
+            list.clear();
+            assert_eq!(list.len(), 0);
+            assert!(list.is_empty());
+            assert!(list.metadata_cache.borrow().entries().is_empty());
+        }
+
+        mod set_operations {
+            use super::*;
+
+            fn list(ids: &[&str]) -> TrackList {
+                let list: TrackList = ids.iter().map(|id| track_id(id)).collect();
+                for id in ids {
+                    list.metadata_cache
+                        .borrow_mut()
+                        .insert(track_id(id), Metadata::new(*id));
+                }
+                list
+            }
+
+            fn ids(list: &TrackList) -> Vec<&str> {
+                list.ids().iter().map(TrackID::as_str).collect()
+            }
+
+            #[test]
+            fn it_combines_empty_lists() {
+                let empty = TrackList::default();
+                let other = list(&["/path/1", "/path/2"]);
+
+                assert_eq!(ids(&empty.union(&other)), vec!["/path/1", "/path/2"]);
+                assert_eq!(ids(&other.union(&empty)), vec!["/path/1", "/path/2"]);
+                assert!(empty.intersection(&other).is_empty());
+                assert!(other.intersection(&empty).is_empty());
+                assert!(empty.difference(&other).is_empty());
+                assert_eq!(ids(&other.difference(&empty)), vec!["/path/1", "/path/2"]);
+            }
+
+            #[test]
+            fn it_combines_disjoint_lists() {
+                let first = list(&["/path/1", "/path/2"]);
+                let second = list(&["/path/3", "/path/4"]);
+
+                let union = first.union(&second);
+                assert_eq!(
+                    ids(&union),
+                    vec!["/path/1", "/path/2", "/path/3", "/path/4"]
+                );
+                assert!(union.ids_without_cache().is_empty());
+                assert!(first.intersection(&second).is_empty());
+                assert_eq!(ids(&first.difference(&second)), vec!["/path/1", "/path/2"]);
+            }
+
+            #[test]
+            fn it_combines_overlapping_lists() {
+                let first = list(&["/path/1", "/path/2", "/path/1"]);
+                let second = TrackList::from(vec![track_id("/path/2"), track_id("/path/1")]);
+
+                assert_eq!(ids(&first.union(&second)), vec!["/path/1", "/path/2"]);
+                let intersection = first.intersection(&second);
+                assert_eq!(ids(&intersection), vec!["/path/1", "/path/2"]);
+                assert!(intersection.ids_without_cache().is_empty());
+                assert!(first.difference(&second).is_empty());
+            }
+
+            #[test]
+            fn it_keeps_only_own_metadata_in_differences() {
+                let first = TrackList::from(vec![track_id("/path/1")]);
+                let mut second = list(&["/path/2"]);
+                // Left in the cache of `second`, although the track is not on it.
+                second.add_metadata(Metadata::new("/path/1"));
+
+                let difference = first.difference(&second);
+                assert_eq!(ids(&difference), vec!["/path/1"]);
+                assert_eq!(difference.ids_without_cache(), vec![&track_id("/path/1")]);
+            }
+        }
+
+        mod move_track {
+            use super::*;
+
+            fn list() -> TrackList {
+                ["/path/1", "/path/2", "/path/3", "/path/4"]
+                    .iter()
+                    .map(|id| track_id(id))
+                    .collect()
+            }
+
+            fn ids(list: &TrackList) -> Vec<&str> {
+                list.ids().iter().map(TrackID::as_str).collect()
+            }
+
+            #[test]
+            fn it_moves_to_the_middle() {
+                let mut list = list();
+                list.add_metadata(Metadata::new("/path/1"));
+
+                assert!(list.move_track(&track_id("/path/1"), &track_id("/path/2")));
+                assert_eq!(ids(&list), vec!["/path/2", "/path/1", "/path/3", "/path/4"]);
+                assert!(!list.ids_without_cache().contains(&&track_id("/path/1")));
+
+                assert!(list.move_track(&track_id("/path/4"), &track_id("/path/2")));
+                assert_eq!(ids(&list), vec!["/path/2", "/path/4", "/path/1", "/path/3"]);
+            }
+
+            #[test]
+            fn it_moves_to_the_end() {
+                let mut list = list();
+                assert!(list.move_track(&track_id("/path/2"), &track_id("/path/4")));
+                assert_eq!(ids(&list), vec!["/path/1", "/path/3", "/path/4", "/path/2"]);
+            }
+
+            #[test]
+            fn it_moves_to_the_start_after_no_track() {
+                let mut list = list();
+                assert!(list.move_track(&track_id("/path/3"), &TrackID::no_track()));
+                assert_eq!(ids(&list), vec!["/path/3", "/path/1", "/path/2", "/path/4"]);
+            }
+
+            #[test]
+            fn it_does_not_move_missing_ids() {
+                let mut list = list();
+                assert!(!list.move_track(&track_id("/path/5"), &track_id("/path/1")));
+                assert!(!list.move_track(&track_id("/path/1"), &track_id("/path/5")));
+                assert_eq!(ids(&list), vec!["/path/1", "/path/2", "/path/3", "/path/4"]);
+            }
+        }
+
+        #[test]
+        fn it_iterates_from_a_track() {
+            let list: TrackList = ["/path/1", "/path/2", "/path/3"]
+                .iter()
+                .map(|id| track_id(id))
+                .collect();
+
+            let from_second: Vec<_> = list.iter_from(&track_id("/path/2")).collect();
+            assert_eq!(
+                from_second,
+                vec![&track_id("/path/2"), &track_id("/path/3")]
+            );
+            assert_eq!(list.iter_from(&track_id("/path/missing")).len(), 3);
+
+            let fake = FakePlayer::spawn();
+            let player = fake.find();
+            let ids: Vec<_> = list
+                .metadata_iter_from(&track_id("/path/3"), &player)
+                .unwrap()
+                .map(|metadata| metadata.track_id())
+                .collect();
+            assert_eq!(ids, vec![Some(track_id("/path/3"))]);
+        }
+
+        #[test]
+        fn it_retains_tracks_with_their_cache() {
+            let mut list: TrackList = ["/path/1", "/path/2", "/path/3", "/path/4"]
+                .iter()
+                .map(|id| track_id(id))
+                .collect();
+            for id in &["/path/1", "/path/2", "/path/3"] {
+                list.add_metadata(Metadata::new(*id));
+            }
+
+            list.retain(|id| id.as_str() != "/path/2");
+            assert_eq!(list.len(), 3);
+            assert!(!list.contains(&track_id("/path/2")));
+            assert!(!list
+                .metadata_cache
+                .borrow()
+                .contains_key(&track_id("/path/2")));
+            assert_eq!(list.ids_without_cache(), vec![&track_id("/path/4")]);
+
+            list.retain_with_metadata(|id, metadata| {
+                metadata.is_some() && id.as_str() != "/path/3"
+            });
+            assert_eq!(list.ids(), &[track_id("/path/1")]);
+            assert!(list.ids_without_cache().is_empty());
+            assert_eq!(list.metadata_cache.borrow().entries().len(), 1);
+        }
+
+        #[test]
+        fn it_iterates_ids() {
+            let ids = vec![track_id("/path/1"), track_id("/path/2")];
+            let list = TrackList::from(ids.clone());
+
+            let mut borrowed = Vec::new();
+            for id in &list {
+                borrowed.push(id.clone());
+            }
+            assert_eq!(borrowed, ids);
+            assert_eq!(list.iter().next_back(), Some(&track_id("/path/2")));
+            assert_eq!(list.into_iter().collect::<Vec<_>>(), ids);
+        }
+
+        #[test]
+        fn it_iterates_cached_metadata_only() {
+            let mut list: TrackList = ["/path/1", "/path/2", "/path/3"]
+                .iter()
+                .map(|id| track_id(id))
+                .collect();
+            list.add_metadata(Metadata::new("/path/3"));
+            list.add_metadata(Metadata::new("/path/1"));
+
+            let cached: Vec<_> = list
+                .iter_cached()
+                .map(|(id, metadata)| (id.clone(), metadata.track_id()))
+                .collect();
+            assert_eq!(
+                cached,
+                vec![
+                    (track_id("/path/1"), Some(track_id("/path/1"))),
+                    (track_id("/path/3"), Some(track_id("/path/3"))),
+                ]
+            );
+        }
+
+        #[test]
+        fn it_compares_only_ids() {
+            let ids = vec![track_id("/path/1"), track_id("/path/2")];
+            let mut cached = TrackList::from(ids.clone());
+            cached.add_metadata(Metadata::new("/path/1"));
+
+            assert_eq!(cached, TrackList::from(ids));
+            assert_ne!(cached, TrackList::from(vec![track_id("/path/1")]));
+        }
+
+        #[test]
+        fn it_finds_ids_after_replace() {
+            let mut list = TrackList::from(vec![track_id("/path/1"), track_id("/path/2")]);
+
+            list.replace(TrackList::from(vec![
+                track_id("/path/2"),
+                track_id("/path/3"),
+            ]));
+
+            assert!(!list.contains(&track_id("/path/1")));
+            assert!(list.contains(&track_id("/path/2")));
+            assert!(list.contains(&track_id("/path/3")));
+            assert_eq!(list.index_of(&track_id("/path/2")), Some(0));
+        }
+
+        #[test]
+        fn it_gets_cached_metadata_of_single_tracks() {
+            let mut list = TrackList::from(vec![track_id("/path/1"), track_id("/path/2")]);
+            list.add_metadata(Metadata::new("/path/1"));
+
+            assert_eq!(
+                list.get_metadata_cached(&track_id("/path/1"))
+                    .and_then(|metadata| metadata.track_id()),
+                Some(track_id("/path/1"))
+            );
+            assert!(list.get_metadata_cached(&track_id("/path/2")).is_none());
+            assert!(list.get_metadata_cached(&track_id("/path/3")).is_none());
+        }
+
+        #[test]
+        fn it_tells_which_tracks_are_cached() {
+            let mut list: TrackList = ["/path/1", "/path/2", "/path/3"]
+                .iter()
+                .map(|id| track_id(id))
+                .collect();
+            list.add_metadata(Metadata::new("/path/2"));
+
+            assert!(!list.has_metadata_for(&track_id("/path/1")));
+            assert!(list.has_metadata_for(&track_id("/path/2")));
+            assert!(!list.has_metadata_for(&track_id("/path/4")));
+            assert_eq!(
+                list.uncached_ids(),
+                vec![track_id("/path/1"), track_id("/path/3")]
+            );
+        }
+
+        #[test]
+        fn it_sorts_by_cached_metadata() {
+            let mut list: TrackList = ["/path/1", "/path/2", "/path/3", "/path/4"]
+                .iter()
+                .map(|id| track_id(id))
+                .collect();
+            for (id, title, number) in &[
+                ("/path/1", "charlie", 2),
+                ("/path/2", "Alpha", 3),
+                ("/path/4", "bravo", 1),
+            ] {
+                list.add_metadata(
+                    crate::MetadataBuilder::new(*id)
+                        .title(*title)
+                        .set_raw("xesam:trackNumber", *number)
+                        .build(),
+                );
+            }
+
+            list.sort_by_title();
+            assert_eq!(
+                list.ids(),
+                &[
+                    track_id("/path/2"),
+                    track_id("/path/4"),
+                    track_id("/path/1"),
+                    track_id("/path/3")
+                ]
+            );
+
+            list.sort_by_track_number();
+            assert_eq!(
+                list.ids(),
+                &[
+                    track_id("/path/4"),
+                    track_id("/path/1"),
+                    track_id("/path/2"),
+                    track_id("/path/3")
+                ]
+            );
+        }
+
+        #[test]
+        fn it_applies_track_list_signals() {
+            let mut list: TrackList = ["/path/1", "/path/2"]
+                .iter()
+                .map(|id| track_id(id))
+                .collect();
+            list.add_metadata(Metadata::new("/path/1"));
+            list.add_metadata(Metadata::new("/path/2"));
+
+            list.apply_tracklist_replaced(
+                vec![track_id("/path/2"), track_id("/path/3")],
+                &track_id("/path/2"),
+            );
+            assert_eq!(list.ids(), &[track_id("/path/2"), track_id("/path/3")]);
+            assert!(!list
+                .metadata_cache
+                .borrow()
+                .contains_key(&track_id("/path/1")));
+
+            list.apply_track_added(Metadata::new("/path/4"), &track_id("/path/2"));
+            assert_eq!(list.index_of(&track_id("/path/4")), Some(1));
+
+            let new_id =
+                list.apply_track_metadata_changed(&track_id("/path/4"), Metadata::new("/path/5"));
+            assert_eq!(new_id, Some(track_id("/path/5")));
+
+            list.apply_track_removed(&track_id("/path/2"));
+            assert_eq!(list.ids(), &[track_id("/path/5"), track_id("/path/3")]);
+        }
+
+        #[test]
+        fn it_loads_missing_metadata_from_the_player() {
+            let fake = FakePlayer::spawn();
+            let player = fake.find();
+            let ids = (1..=5).map(|n| track_id(&format!("/path/{}", n))).collect();
+
+            #[allow(unused_mut)]
+            let mut list = TrackList::new(ids);
+            #[cfg(feature = "parallel")]
+            list.set_fetch_chunk_size(2);
+
+            list.complete_cache(&player).unwrap();
+            assert!(list.ids_without_cache().is_empty());
+            assert_eq!(list.metadata_cache.borrow().entries().len(), 5);
+        }
+
+        #[cfg(feature = "async")]
+        #[tokio::test]
+        async fn it_reloads_the_cache_async_like_sync() {
+            use crate::player::MPRIS2_PATH;
+
+            let fake = FakePlayer::spawn();
+            let ids: Vec<_> = (1..=3).map(|n| track_id(&format!("/path/{}", n))).collect();
+
+            let mut sync_list = TrackList::new(ids.clone());
+            sync_list.add_metadata(Metadata::new("/path/old"));
+            sync_list.reload_cache(&fake.find()).unwrap();
+
+            let mut async_list = TrackList::new(ids);
+            async_list.add_metadata(Metadata::new("/path/old"));
+            let player = AsyncPlayer::new(fake.bus_name(), MPRIS2_PATH, 500)
+                .await
+                .unwrap();
+            async_list.reload_cache_async(&player).await.unwrap();
+
+            let cached_ids = |list: &TrackList| {
+                let mut ids: Vec<_> = list
+                    .metadata_cache
+                    .borrow()
+                    .entries()
+                    .iter()
+                    .map(|(id, metadata)| (id.clone(), metadata.track_id()))
+                    .collect();
+                ids.sort();
+                ids
+            };
+            assert_eq!(cached_ids(&async_list), cached_ids(&sync_list));
+            assert_eq!(cached_ids(&async_list).len(), 3);
+        }
+
+        #[test]
+        #[cfg(not(feature = "no_stats"))]
+        fn it_counts_cache_hits_and_misses() {
+            let fake = FakePlayer::spawn();
+            let player = fake.find();
+            let ids: Vec<_> = (1..=3).map(|n| track_id(&format!("/path/{}", n))).collect();
+
+            let mut list = TrackList::new(ids.clone());
+            for id in &ids {
+                list.add_metadata(Metadata::new(id.clone()));
+            }
+            assert_eq!(list.metadata_iter(&player).unwrap().len(), 3);
+            assert_eq!(
+                list.cache_stats(),
+                CacheStats {
+                    hits: 3,
+                    misses: 0,
+                    evictions: 0
+                }
+            );
+
+            list.reset_cache_stats();
+            list.insert(&track_id("/path/3"), Metadata::new("/path/4"));
+            list.remove(&track_id("/path/1"));
+            list.extend(std::iter::once(track_id("/path/1")));
+            list.complete_cache(&player).unwrap();
+            assert_eq!(list.cache_stats().hits, 3);
+            assert_eq!(list.cache_stats().misses, 1);
+        }
+
+        #[test]
+        fn it_limits_the_cache_size() {
+            let mut list = TrackList::with_capacity_limit(Vec::new(), 2);
+            for id in &["/path/1", "/path/2", "/path/3"] {
+                list.insert(&track_id("/path/missing"), Metadata::new(*id));
+            }
+
+            assert_eq!(list.len(), 3);
+            assert_eq!(list.metadata_cache.borrow().entries().len(), 2);
+            assert_eq!(list.ids_without_cache(), vec![&track_id("/path/1")]);
+        }
     }
 }
@@ -0,0 +1,322 @@
+use super::{Metadata, TrackID};
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+#[cfg(not(feature = "no_stats"))]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How well the metadata cache of a [`TrackList`](super::TrackList) has been doing.
+///
+/// With the `no_stats` feature nothing is counted, and all of these stay `0`.
+///
+/// See [`TrackList::cache_stats`](super::TrackList::cache_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// Tracks whose metadata was already cached when it was needed.
+    pub hits: u64,
+    /// Tracks whose metadata had to be loaded from the player.
+    pub misses: u64,
+    /// Cache entries that were dropped to stay within the
+    /// [capacity limit](super::TrackList::with_capacity_limit).
+    pub evictions: u64,
+}
+
+// Atomics, so that lookups can be counted while the cache is only borrowed immutably.
+#[derive(Debug, Default)]
+struct CacheCounters {
+    #[cfg(not(feature = "no_stats"))]
+    hits: AtomicU64,
+    #[cfg(not(feature = "no_stats"))]
+    misses: AtomicU64,
+    #[cfg(not(feature = "no_stats"))]
+    evictions: AtomicU64,
+}
+
+/// The metadata cache of a [`TrackList`](super::TrackList).
+///
+/// Without a limit this is a plain map. With a limit it also remembers when entries were last
+/// inserted, updated or read through [`get`](Self::get), and evicts the least recently used entry
+/// when an insertion would go over the limit.
+#[derive(Debug, Default)]
+pub(crate) struct MetadataCache {
+    entries: HashMap<TrackID, Metadata>,
+    limit: Option<usize>,
+    // Only kept up to date when there is a limit. In a `RefCell`, so that reads can be recorded
+    // while the cache is only borrowed immutably.
+    recency: RefCell<Recency>,
+    counters: CacheCounters,
+}
+
+/// The order in which cache entries were used, with every operation in amortized O(1).
+///
+/// Every use gets a higher stamp than the one before and is queued. An entry is only as recent as
+/// its latest stamp, so older queued uses of it are skipped when looking for the least recently
+/// used entry, and dropped once they make up most of the queue.
+#[derive(Debug, Default)]
+struct Recency {
+    stamps: HashMap<TrackID, u64>,
+    // Oldest first.
+    uses: VecDeque<(u64, TrackID)>,
+    next_stamp: u64,
+}
+
+impl MetadataCache {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        MetadataCache {
+            entries: HashMap::with_capacity(capacity),
+            limit: None,
+            recency: RefCell::default(),
+            counters: CacheCounters::default(),
+        }
+    }
+
+    pub(crate) fn with_limit(limit: usize) -> Self {
+        MetadataCache {
+            entries: HashMap::with_capacity(limit),
+            limit: Some(limit),
+            recency: RefCell::default(),
+            counters: CacheCounters::default(),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, id: &TrackID) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    pub(crate) fn entries(&self) -> &HashMap<TrackID, Metadata> {
+        &self.entries
+    }
+
+    /// Returns the metadata of a track, and marks it as used.
+    pub(crate) fn get(&self, id: &TrackID) -> Option<&Metadata> {
+        let metadata = self.entries.get(id)?;
+        self.touch(id);
+        Some(metadata)
+    }
+
+    /// Marks the entries for the given IDs as used, in order.
+    pub(crate) fn touch_all<'a, I: IntoIterator<Item = &'a TrackID>>(&self, ids: I) {
+        for id in ids {
+            if self.entries.contains_key(id) {
+                self.touch(id);
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, id: TrackID, metadata: Metadata) -> Option<Metadata> {
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => return self.entries.insert(id, metadata),
+        };
+        if limit == 0 {
+            return None;
+        }
+
+        if !self.entries.contains_key(&id) && self.entries.len() >= limit {
+            if let Some(oldest) = self.recency.get_mut().pop_least_recent() {
+                self.entries.remove(&oldest);
+                self.counters.record_eviction();
+            }
+        }
+        self.touch(&id);
+        self.entries.insert(id, metadata)
+    }
+
+    pub(crate) fn remove(&mut self, id: &TrackID) -> Option<Metadata> {
+        self.recency.get_mut().forget(id);
+        self.entries.remove(id)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        *self.recency.get_mut() = Recency::default();
+    }
+
+    /// Removes every entry that is not for one of the given IDs.
+    pub(crate) fn retain_ids(&mut self, ids: &[TrackID]) {
+        let mut kept = HashMap::with_capacity(ids.len().min(self.entries.len()));
+        for id in ids {
+            if let Some(metadata) = self.entries.remove(id) {
+                kept.insert(id.to_owned(), metadata);
+            }
+        }
+        self.entries = kept;
+
+        let recency = self.recency.get_mut();
+        let entries = &self.entries;
+        recency.stamps.retain(|id, _| entries.contains_key(id));
+        recency.compact();
+    }
+
+    pub(crate) fn record_lookups(&self, hits: usize, misses: usize) {
+        self.counters.record_lookups(hits, misses);
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        self.counters.stats()
+    }
+
+    pub(crate) fn reset_stats(&self) {
+        self.counters.reset();
+    }
+
+    pub(crate) fn into_entries(self) -> HashMap<TrackID, Metadata> {
+        self.entries
+    }
+
+    fn touch(&self, id: &TrackID) {
+        if self.limit.is_some() {
+            self.recency.borrow_mut().touch(id);
+        }
+    }
+}
+
+impl Recency {
+    fn touch(&mut self, id: &TrackID) {
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        self.stamps.insert(id.clone(), stamp);
+        self.uses.push_back((stamp, id.clone()));
+
+        // Keeps the queue within a constant factor of the number of entries.
+        if self.uses.len() > 2 * self.stamps.len() + 16 {
+            self.compact();
+        }
+    }
+
+    fn forget(&mut self, id: &TrackID) {
+        self.stamps.remove(id);
+    }
+
+    fn pop_least_recent(&mut self) -> Option<TrackID> {
+        while let Some((stamp, id)) = self.uses.pop_front() {
+            if self.stamps.get(&id) == Some(&stamp) {
+                self.stamps.remove(&id);
+                return Some(id);
+            }
+        }
+        None
+    }
+
+    /// Drops the queued uses that are not the latest use of an entry.
+    fn compact(&mut self) {
+        let stamps = &self.stamps;
+        self.uses
+            .retain(|(stamp, id)| stamps.get(id) == Some(stamp));
+    }
+}
+
+#[cfg(not(feature = "no_stats"))]
+impl CacheCounters {
+    fn record_lookups(&self, hits: usize, misses: usize) {
+        self.hits.fetch_add(hits as u64, Ordering::Relaxed);
+        self.misses.fetch_add(misses as u64, Ordering::Relaxed);
+    }
+
+    fn record_eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn reset(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "no_stats")]
+impl CacheCounters {
+    fn record_lookups(&self, _hits: usize, _misses: usize) {}
+
+    fn record_eviction(&self) {}
+
+    fn stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+
+    fn reset(&self) {}
+}
+
+impl Extend<(TrackID, Metadata)> for MetadataCache {
+    fn extend<I: IntoIterator<Item = (TrackID, Metadata)>>(&mut self, iter: I) {
+        for (id, metadata) in iter {
+            self.insert(id, metadata);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track_id(s: &str) -> TrackID {
+        TrackID::new(s).expect("Failed to parse a TrackID fixture")
+    }
+
+    fn insert(cache: &mut MetadataCache, id: &str) {
+        cache.insert(track_id(id), Metadata::new(id));
+    }
+
+    #[test]
+    fn it_evicts_the_least_recently_used_entry() {
+        let mut cache = MetadataCache::with_limit(2);
+        insert(&mut cache, "/path/1");
+        insert(&mut cache, "/path/2");
+        insert(&mut cache, "/path/1");
+        insert(&mut cache, "/path/3");
+
+        assert_eq!(cache.entries().len(), 2);
+        assert!(cache.contains_key(&track_id("/path/1")));
+        assert!(!cache.contains_key(&track_id("/path/2")));
+        assert!(cache.contains_key(&track_id("/path/3")));
+        #[cfg(not(feature = "no_stats"))]
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn it_counts_reads_as_uses() {
+        let mut cache = MetadataCache::with_limit(2);
+        insert(&mut cache, "/path/1");
+        insert(&mut cache, "/path/2");
+        assert!(cache.get(&track_id("/path/1")).is_some());
+        insert(&mut cache, "/path/3");
+
+        assert!(cache.contains_key(&track_id("/path/1")));
+        assert!(!cache.contains_key(&track_id("/path/2")));
+        assert!(cache.contains_key(&track_id("/path/3")));
+    }
+
+    #[test]
+    fn it_keeps_the_recency_queue_small() {
+        let mut cache = MetadataCache::with_limit(10);
+        for n in 0..10 {
+            insert(&mut cache, &format!("/path/{}", n));
+        }
+        for _ in 0..1000 {
+            cache.get(&track_id("/path/0"));
+        }
+        insert(&mut cache, "/path/10");
+
+        assert!(cache.recency.borrow().uses.len() <= 2 * 10 + 16);
+        assert!(cache.contains_key(&track_id("/path/0")));
+        assert!(!cache.contains_key(&track_id("/path/1")));
+    }
+
+    #[test]
+    fn it_does_not_evict_without_a_limit() {
+        let mut cache = MetadataCache::with_capacity(0);
+        for id in &["/path/1", "/path/2", "/path/3"] {
+            insert(&mut cache, id);
+        }
+
+        assert_eq!(cache.entries().len(), 3);
+    }
+}
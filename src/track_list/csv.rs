@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use super::TrackList;
+use crate::metadata::csv_row;
+use crate::MetadataField;
+
+impl TrackList {
+    /// Exports the cached metadata of the list as CSV.
+    ///
+    /// The first row holds the [keys](MetadataField::as_key) of the given fields, followed by a
+    /// row for every track in the list, in order. See [`Metadata::to_csv_row`](crate::Metadata::to_csv_row)
+    /// for how values are written. Tracks without cached metadata get a row of empty cells.
+    ///
+    /// Only cached metadata is written; see [exporting](TrackList#exporting).
+    pub fn to_csv(&self, fields: &[MetadataField]) -> String {
+        let mut csv = Vec::new();
+        self.write_csv(&mut csv, fields)
+            .expect("Writing to a Vec cannot fail");
+        String::from_utf8(csv).expect("CSV is built from strings")
+    }
+
+    /// Writes the same CSV as [`TrackList::to_csv`] to the given writer.
+    pub fn write_csv<W: Write>(&self, mut writer: W, fields: &[MetadataField]) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{}",
+            csv_row(fields.iter().map(MetadataField::as_key))
+        )?;
+
+        let cache = self.metadata_cache.borrow();
+        for id in &self.ids {
+            let row = match cache.entries().get(id) {
+                Some(metadata) => metadata.to_csv_row(fields),
+                None => csv_row(fields.iter().map(|_| "")),
+            };
+            writeln!(writer, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MetadataBuilder, TrackID};
+
+    #[test]
+    fn it_exports_a_row_per_track() {
+        let mut list = TrackList::new(vec![
+            TrackID::new("/path/1").unwrap(),
+            TrackID::new("/path/2").unwrap(),
+        ]);
+        list.add_metadata(
+            MetadataBuilder::new("/path/1")
+                .title("Hello, world")
+                .artists(vec!["Alice"])
+                .build(),
+        );
+
+        let csv = list.to_csv(&[MetadataField::Title, MetadataField::Artists]);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["xesam:title,xesam:artist", "\"Hello, world\",Alice", ","]
+        );
+    }
+}
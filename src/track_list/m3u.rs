@@ -0,0 +1,275 @@
+use failure::Fail;
+
+use std::time::Duration;
+
+use super::{Metadata, TrackID, TrackList};
+use crate::MetadataBuilder;
+
+const HEADER: &str = "#EXTM3U";
+const EXTINF: &str = "#EXTINF:";
+
+/// An M3U playlist could not be parsed into a [`TrackList`].
+///
+/// See [`TrackList::from_m3u`].
+#[derive(Fail, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum M3uParseError {
+    /// The playlist did not start with an `#EXTM3U` line.
+    #[fail(display = "M3U playlist does not start with #EXTM3U")]
+    MissingHeader,
+
+    /// An `#EXTINF` line was not like `#EXTINF:duration,artist - title`.
+    #[fail(display = "Invalid #EXTINF on line {}", line)]
+    InvalidExtinf {
+        /// The line number, starting at 1.
+        line: usize,
+    },
+
+    /// An `#EXTINF` line was not followed by the location of the track.
+    #[fail(display = "#EXTINF on line {} is not followed by a location", line)]
+    MissingLocation {
+        /// The line number of the `#EXTINF` line, starting at 1.
+        line: usize,
+    },
+}
+
+// The parsed `#EXTINF` line that belongs to the next location.
+struct ExtInf {
+    line: usize,
+    length: Option<Duration>,
+    artist: Option<String>,
+    title: Option<String>,
+}
+
+impl TrackList {
+    /// Exports the list as an extended M3U playlist.
+    ///
+    /// Tracks with cached [`Metadata`] get an `#EXTINF:duration,artist - title` line followed by
+    /// their [`url`](Metadata::url). Tracks without cached metadata, or without a URL, are written
+    /// as their bare [`TrackID`] instead. Unknown durations are written as `-1`.
+    ///
+    /// Only cached metadata is written; see [exporting](TrackList#exporting).
+    pub fn to_m3u(&self) -> String {
+        let cache = self.metadata_cache.borrow();
+        let mut m3u = String::from(HEADER);
+        m3u.push('\n');
+
+        for id in &self.ids {
+            let metadata = cache.entries().get(id);
+            if let Some(metadata) = metadata {
+                let seconds = metadata
+                    .length()
+                    .map_or(-1, |length| length.as_secs() as i64);
+                let display = match (metadata.artist_string(), metadata.title()) {
+                    (Some(artist), Some(title)) => format!("{} - {}", artist, title),
+                    (Some(artist), None) => artist,
+                    (None, title) => title.unwrap_or_default().to_string(),
+                };
+                m3u.push_str(&format!("{}{},{}\n", EXTINF, seconds, display));
+            }
+
+            let location = metadata.and_then(Metadata::url).unwrap_or(id.as_str());
+            m3u.push_str(location);
+            m3u.push('\n');
+        }
+
+        m3u
+    }
+
+    /// Parses an extended M3U playlist, like the one from [`TrackList::to_m3u`].
+    ///
+    /// The M3U has no track IDs, so every track gets a [`TrackID`] made from the line number of
+    /// its location, like `/org/mpris/MediaPlayer2/Track/3`. Tracks with an `#EXTINF` line get
+    /// cached [`Metadata`] with their title, artist, length and location as the URL. Other lines
+    /// starting with `#` and empty lines are skipped.
+    ///
+    /// An `#EXTINF` duration may have a fraction and be followed by attributes, like
+    /// `#EXTINF:-1 tvg-id="x",Name`; the attributes are skipped. The display name is split into
+    /// artist and title at the first `" - "`; without one it is all title.
+    pub fn from_m3u(m3u: &str) -> Result<TrackList, M3uParseError> {
+        let mut lines = m3u
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        match lines.next() {
+            Some((_, HEADER)) => {}
+            _ => return Err(M3uParseError::MissingHeader),
+        }
+
+        let mut ids = Vec::new();
+        let mut metadata = Vec::new();
+        let mut pending: Option<ExtInf> = None;
+
+        for (line, content) in lines {
+            if let Some(extinf) = content.strip_prefix(EXTINF) {
+                if let Some(extinf) = pending {
+                    return Err(M3uParseError::MissingLocation { line: extinf.line });
+                }
+                pending = Some(parse_extinf(line, extinf)?);
+            } else if !content.starts_with('#') {
+                let id = TrackID(format!("/org/mpris/MediaPlayer2/Track/{}", line));
+                if let Some(extinf) = pending.take() {
+                    metadata.push(extinf.into_metadata(&id, content));
+                }
+                ids.push(id);
+            }
+        }
+
+        if let Some(extinf) = pending {
+            return Err(M3uParseError::MissingLocation { line: extinf.line });
+        }
+
+        let mut list = TrackList::new(ids);
+        for metadata in metadata {
+            list.add_metadata(metadata);
+        }
+        Ok(list)
+    }
+}
+
+// The duration can have a fraction, like `215.5`, and be followed by attributes, like
+// `-1 tvg-id="x"`. Attribute values are quoted and can contain commas.
+fn parse_extinf(line: usize, extinf: &str) -> Result<ExtInf, M3uParseError> {
+    let mut quoted = false;
+    let comma = extinf
+        .find(|c| {
+            if c == '"' {
+                quoted = !quoted;
+            }
+            c == ',' && !quoted
+        })
+        .ok_or(M3uParseError::InvalidExtinf { line })?;
+    let seconds = extinf[..comma]
+        .split_whitespace()
+        .next()
+        .and_then(|seconds| seconds.parse::<f64>().ok())
+        .filter(|seconds| !seconds.is_nan())
+        .ok_or(M3uParseError::InvalidExtinf { line })?;
+    let display = extinf[comma + 1..].trim();
+
+    let (artist, title) = match display.find(" - ") {
+        Some(split) => (Some(&display[..split]), &display[split + 3..]),
+        None => (None, display),
+    };
+    let non_empty = |value: &str| Some(value.to_string()).filter(|value| !value.is_empty());
+
+    Ok(ExtInf {
+        line,
+        // Negative durations, usually `-1`, mean that the length is unknown.
+        length: Some(seconds)
+            .filter(|seconds| *seconds >= 0.0 && *seconds < u64::MAX as f64)
+            .map(Duration::from_secs_f64),
+        artist: artist.and_then(non_empty),
+        title: non_empty(title),
+    })
+}
+
+impl ExtInf {
+    fn into_metadata(self, id: &TrackID, location: &str) -> Metadata {
+        let mut builder = MetadataBuilder::new(id.as_str()).set_raw("xesam:url", location);
+        if let Some(title) = self.title {
+            builder = builder.title(title);
+        }
+        if let Some(artist) = self.artist {
+            builder = builder.artists(vec![artist]);
+        }
+        if let Some(length) = self.length {
+            builder = builder.length(length);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_through_m3u() {
+        let mut list = TrackList::new(vec![
+            TrackID::new("/path/1").unwrap(),
+            TrackID::new("/path/2").unwrap(),
+        ]);
+        list.add_metadata(
+            MetadataBuilder::new("/path/1")
+                .title("Song")
+                .artists(vec!["Alice", "Bob"])
+                .length(Duration::from_secs(225))
+                .set_raw("xesam:url", "file:///music/song.ogg")
+                .build(),
+        );
+
+        let m3u = list.to_m3u();
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n#EXTINF:225,Alice, Bob - Song\nfile:///music/song.ogg\n/path/2\n"
+        );
+
+        let parsed = TrackList::from_m3u(&m3u).unwrap();
+        assert_eq!(parsed.len(), 2);
+        let cache = parsed.metadata_cache.borrow();
+        let song = &cache.entries()[&parsed.ids[0]];
+        assert_eq!(song.title(), Some("Song"));
+        assert_eq!(song.artist_string(), Some(String::from("Alice, Bob")));
+        assert_eq!(song.length(), Some(Duration::from_secs(225)));
+        assert_eq!(song.url(), Some("file:///music/song.ogg"));
+        assert!(!cache.contains_key(&parsed.ids[1]));
+        drop(cache);
+
+        // Only the uncached track changes, as it gets a new ID.
+        assert_eq!(
+            parsed.to_m3u(),
+            m3u.replace("/path/2", "/org/mpris/MediaPlayer2/Track/4")
+        );
+    }
+
+    #[test]
+    fn it_reads_extinf_without_artist_or_length() {
+        let list = TrackList::from_m3u("#EXTM3U\n\n#EXTINF:-1,Radio\nhttp://radio\n").unwrap();
+        let cache = list.metadata_cache.borrow();
+        let radio = &cache.entries()[&list.ids[0]];
+
+        assert_eq!(list.ids[0].as_str(), "/org/mpris/MediaPlayer2/Track/4");
+        assert_eq!(radio.title(), Some("Radio"));
+        assert_eq!(radio.artists(), None);
+        assert_eq!(radio.length(), None);
+    }
+
+    #[test]
+    fn it_reads_extinf_with_fractions_and_attributes() {
+        let list = TrackList::from_m3u(
+            "#EXTM3U\n#EXTINF:215.5,Song\nsong.ogg\n\
+             #EXTINF:-1 tvg-id=\"x\" tvg-name=\"A, B\",Name\nhttp://tv\n",
+        )
+        .unwrap();
+        let cache = list.metadata_cache.borrow();
+        let song = &cache.entries()[&list.ids[0]];
+        let tv = &cache.entries()[&list.ids[1]];
+
+        assert_eq!(song.title(), Some("Song"));
+        assert_eq!(song.length(), Some(Duration::from_millis(215_500)));
+        assert_eq!(tv.title(), Some("Name"));
+        assert_eq!(tv.length(), None);
+    }
+
+    #[test]
+    fn it_rejects_invalid_m3u() {
+        assert_eq!(
+            TrackList::from_m3u("song.ogg").unwrap_err(),
+            M3uParseError::MissingHeader
+        );
+        assert_eq!(
+            TrackList::from_m3u("#EXTM3U\n#EXTINF:long,Song\nsong.ogg").unwrap_err(),
+            M3uParseError::InvalidExtinf { line: 2 }
+        );
+        assert_eq!(
+            TrackList::from_m3u("#EXTM3U\n#EXTINF:1,Song\n#EXTINF:2,Other\nother.ogg").unwrap_err(),
+            M3uParseError::MissingLocation { line: 2 }
+        );
+        assert_eq!(
+            TrackList::from_m3u("#EXTM3U\n#EXTINF:1,Song\n").unwrap_err(),
+            M3uParseError::MissingLocation { line: 2 }
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use super::{Metadata, MetadataIter, TrackID};
+
+use std::collections::HashMap;
+
+/// An owned, point-in-time copy of a [`TrackList`](super::TrackList) and its cached metadata.
+///
+/// Unlike [`TrackList`](super::TrackList), this has no interior mutability, so it is `Send` and
+/// `Sync` and can be handed to another thread. It never talks to the player; tracks that were not
+/// cached when the snapshot was taken have no [`Metadata`] in it.
+///
+/// See [`TrackList::snapshot`](super::TrackList::snapshot).
+#[derive(Debug, Clone, Default)]
+pub struct TrackListSnapshot {
+    ids: Vec<TrackID>,
+    metadata: HashMap<TrackID, Metadata>,
+}
+
+impl TrackListSnapshot {
+    pub(crate) fn new(ids: Vec<TrackID>, metadata: HashMap<TrackID, Metadata>) -> Self {
+        TrackListSnapshot { ids, metadata }
+    }
+
+    /// Get a list of [`TrackID`]s that are part of this snapshot. The order matters.
+    pub fn ids(&self) -> &[TrackID] {
+        self.ids.as_ref()
+    }
+
+    /// Returns the number of tracks in the snapshot.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// If the snapshot has no tracks.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Checks if the [`TrackID`] is part of this snapshot.
+    pub fn contains(&self, id: &TrackID) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// Returns the index of the first occurrence of the [`TrackID`], or [`None`] if it is not part
+    /// of this snapshot.
+    pub fn index_of(&self, id: &TrackID) -> Option<usize> {
+        self.ids.iter().position(|item_id| item_id == id)
+    }
+
+    /// Returns the [`Metadata`] of the track, if it was cached when the snapshot was taken.
+    pub fn metadata(&self, id: &TrackID) -> Option<&Metadata> {
+        self.metadata.get(id)
+    }
+
+    /// Iterates the [`Metadata`] of the tracks in the snapshot, in order.
+    ///
+    /// Tracks without cached metadata yield a [`Metadata`] that only contains their [`TrackID`],
+    /// just like [`TrackList::metadata_iter`](super::TrackList::metadata_iter) does when loading
+    /// them fails halfway.
+    pub fn metadata_iter(&self) -> MetadataIter {
+        MetadataIter {
+            current: 0,
+            tail: self.ids.len(),
+            order: self.ids.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TrackList;
+
+    fn assert_send_and_sync<T: Send + Sync>(_: &T) {}
+
+    #[test]
+    fn it_copies_ids_and_cache() {
+        let first = TrackID::new("/path/1").unwrap();
+        let second = TrackID::new("/path/2").unwrap();
+        let mut list = TrackList::from(vec![first.clone(), second.clone()]);
+        list.add_metadata(Metadata::new("/path/1"));
+
+        let snapshot = list.snapshot();
+        list.clear();
+        assert_send_and_sync(&snapshot);
+
+        assert_eq!(snapshot.len(), 2);
+        assert!(snapshot.contains(&second));
+        assert_eq!(snapshot.index_of(&second), Some(1));
+        assert!(snapshot.metadata(&first).is_some());
+        assert!(snapshot.metadata(&second).is_none());
+
+        let ids: Vec<_> = snapshot
+            .metadata_iter()
+            .map(|metadata| metadata.track_id())
+            .collect();
+        assert_eq!(ids, vec![Some(first), Some(second)]);
+    }
+}
@@ -0,0 +1,335 @@
+use failure::Fail;
+use quick_xml::events::{BytesDecl, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use std::time::Duration;
+
+use super::{Metadata, TrackID, TrackList};
+use crate::MetadataBuilder;
+
+const NAMESPACE: &str = "http://xspf.org/ns/0/";
+
+/// An XSPF playlist could not be parsed into a [`TrackList`].
+///
+/// See [`TrackList::from_xspf`].
+#[derive(Fail, Debug)]
+pub enum XspfError {
+    /// The playlist is not valid XML.
+    #[fail(display = "Invalid XSPF XML: {}", _0)]
+    Xml(#[cause] quick_xml::Error),
+
+    /// The playlist is missing an element that XSPF requires, like `playlist` or `trackList`.
+    #[fail(display = "XSPF playlist has no <{}> element", _0)]
+    MissingElement(&'static str),
+}
+
+// The fields of a `<track>` that are read back into metadata.
+#[derive(Debug, Default)]
+struct XspfTrack {
+    identifier: Option<String>,
+    location: Option<String>,
+    title: Option<String>,
+    creator: Option<String>,
+    album: Option<String>,
+    duration: Option<Duration>,
+}
+
+impl TrackList {
+    /// Exports the list as an [XSPF](https://xspf.org/) playlist with the given title.
+    ///
+    /// Every track gets its [`TrackID`] as `<identifier>`. Tracks with cached [`Metadata`] also
+    /// get their URL as `<location>`, and their `<title>`, `<creator>` (the artists), `<album>`
+    /// and `<duration>` in milliseconds, as far as they are known.
+    ///
+    /// Only cached metadata is written; see [exporting](TrackList#exporting).
+    ///
+    /// **Requires the `xspf` feature.**
+    pub fn to_xspf(&self, title: &str) -> String {
+        let cache = self.metadata_cache.borrow();
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+        let result = writer
+            .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+            .and_then(|_| {
+                writer
+                    .create_element("playlist")
+                    .with_attribute(("version", "1"))
+                    .with_attribute(("xmlns", NAMESPACE))
+                    .write_inner_content(|writer| {
+                        write_text_element(writer, "title", title)?;
+                        writer
+                            .create_element("trackList")
+                            .write_inner_content(|writer| {
+                                for id in &self.ids {
+                                    write_track(writer, id, cache.entries().get(id))?;
+                                }
+                                Ok::<(), quick_xml::Error>(())
+                            })?;
+                        Ok::<(), quick_xml::Error>(())
+                    })
+                    .map(|_| ())
+            });
+        // Only I/O can fail, and writing to a Vec does not.
+        result.expect("Could not write XSPF to memory");
+
+        String::from_utf8(writer.into_inner()).expect("XSPF writer produced invalid UTF-8")
+    }
+
+    /// Parses an [XSPF](https://xspf.org/) playlist, like the one from [`TrackList::to_xspf`].
+    ///
+    /// Tracks keep the [`TrackID`] in their `<identifier>`; tracks without one, or with one that
+    /// is not a valid track ID, get an ID from their position like
+    /// `/org/mpris/MediaPlayer2/Track/3`. Tracks with a `<location>`, `<title>`, `<creator>`,
+    /// `<album>` or `<duration>` get cached [`Metadata`] with them. Durations that are not a whole
+    /// number of milliseconds are ignored.
+    ///
+    /// **Requires the `xspf` feature.**
+    pub fn from_xspf(xml: &str) -> Result<TrackList, XspfError> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut path: Vec<String> = Vec::new();
+        let mut found_playlist = false;
+        let mut found_track_list = false;
+        let mut tracks = Vec::new();
+        let mut current: Option<XspfTrack> = None;
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(element) => {
+                    let name = local_name(&element);
+                    match (path.as_slice(), name.as_str()) {
+                        ([], "playlist") => found_playlist = true,
+                        ([], _) => return Err(XspfError::MissingElement("playlist")),
+                        ([_], "trackList") => found_track_list = true,
+                        ([_, list], "track") if list == "trackList" => {
+                            current = Some(XspfTrack::default())
+                        }
+                        _ => {}
+                    }
+                    path.push(name);
+                }
+                Event::Empty(element) => {
+                    let name = local_name(&element);
+                    match (path.as_slice(), name.as_str()) {
+                        ([], "playlist") => found_playlist = true,
+                        ([], _) => return Err(XspfError::MissingElement("playlist")),
+                        ([_], "trackList") => found_track_list = true,
+                        ([_, list], "track") if list == "trackList" => {
+                            tracks.push(XspfTrack::default())
+                        }
+                        _ => {}
+                    }
+                }
+                // The guard pops every end tag, not only the ones of tracks.
+                Event::End(_) if path.pop().as_deref() == Some("track") => {
+                    tracks.extend(current.take());
+                }
+                Event::Text(text) => match (current.as_mut(), path.as_slice()) {
+                    (Some(track), [.., parent, field]) if parent == "track" => {
+                        track.set(field, text.unescape()?.into_owned());
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !found_playlist {
+            return Err(XspfError::MissingElement("playlist"));
+        }
+        if !found_track_list {
+            return Err(XspfError::MissingElement("trackList"));
+        }
+
+        let mut ids = Vec::with_capacity(tracks.len());
+        let mut metadata = Vec::new();
+        for (index, track) in tracks.into_iter().enumerate() {
+            let id = track
+                .identifier
+                .as_ref()
+                .and_then(|identifier| TrackID::new(identifier.as_str()).ok())
+                .unwrap_or_else(|| TrackID(format!("/org/mpris/MediaPlayer2/Track/{}", index + 1)));
+            if let Some(track_metadata) = track.into_metadata(&id) {
+                metadata.push(track_metadata);
+            }
+            ids.push(id);
+        }
+
+        let mut list = TrackList::new(ids);
+        for metadata in metadata {
+            list.add_metadata(metadata);
+        }
+        Ok(list)
+    }
+}
+
+impl XspfTrack {
+    fn set(&mut self, field: &str, value: String) {
+        match field {
+            "identifier" => self.identifier = Some(value),
+            "location" => self.location = Some(value),
+            "title" => self.title = Some(value),
+            "creator" => self.creator = Some(value),
+            "album" => self.album = Some(value),
+            "duration" => self.duration = value.parse().ok().map(Duration::from_millis),
+            _ => {}
+        }
+    }
+
+    fn into_metadata(self, id: &TrackID) -> Option<Metadata> {
+        if self.location.is_none()
+            && self.title.is_none()
+            && self.creator.is_none()
+            && self.album.is_none()
+            && self.duration.is_none()
+        {
+            return None;
+        }
+
+        let mut builder = MetadataBuilder::new(id.as_str());
+        if let Some(location) = self.location {
+            builder = builder.set_raw("xesam:url", location.as_str());
+        }
+        if let Some(title) = self.title {
+            builder = builder.title(title);
+        }
+        if let Some(creator) = self.creator {
+            builder = builder.artists(vec![creator]);
+        }
+        if let Some(album) = self.album {
+            builder = builder.album(album);
+        }
+        if let Some(duration) = self.duration {
+            builder = builder.length(duration);
+        }
+        Some(builder.build())
+    }
+}
+
+fn write_track(
+    writer: &mut Writer<Vec<u8>>,
+    id: &TrackID,
+    metadata: Option<&Metadata>,
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element("track")
+        .write_inner_content(|writer| {
+            if let Some(metadata) = metadata {
+                if let Some(url) = metadata.url() {
+                    write_text_element(writer, "location", url)?;
+                }
+            }
+            write_text_element(writer, "identifier", id.as_str())?;
+            if let Some(metadata) = metadata {
+                if let Some(title) = metadata.title() {
+                    write_text_element(writer, "title", title)?;
+                }
+                if let Some(artists) = metadata.artist_string() {
+                    write_text_element(writer, "creator", &artists)?;
+                }
+                if let Some(album) = metadata.album_name() {
+                    write_text_element(writer, "album", album)?;
+                }
+                if let Some(length) = metadata.length() {
+                    write_text_element(writer, "duration", &length.as_millis().to_string())?;
+                }
+            }
+            Ok::<(), quick_xml::Error>(())
+        })
+        .map(|_| ())
+}
+
+fn write_text_element(
+    writer: &mut Writer<Vec<u8>>,
+    name: &str,
+    text: &str,
+) -> Result<(), quick_xml::Error> {
+    writer
+        .create_element(name)
+        .write_text_content(BytesText::new(text))
+        .map(|_| ())
+}
+
+fn local_name(element: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+impl From<quick_xml::Error> for XspfError {
+    fn from(error: quick_xml::Error) -> Self {
+        XspfError::Xml(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_through_xspf() {
+        let mut list = TrackList::new(vec![
+            TrackID::new("/path/1").unwrap(),
+            TrackID::new("/path/2").unwrap(),
+        ]);
+        list.add_metadata(
+            MetadataBuilder::new("/path/1")
+                .title("Rock & Roll")
+                .artists(vec!["Alice", "Bob"])
+                .album("<Album>")
+                .length(Duration::from_millis(225_500))
+                .set_raw("xesam:url", "file:///music/song.ogg")
+                .build(),
+        );
+
+        let xspf = list.to_xspf("Mix");
+        assert!(xspf.contains("<title>Rock &amp; Roll</title>"));
+        assert!(xspf.contains("<duration>225500</duration>"));
+
+        let parsed = TrackList::from_xspf(&xspf).unwrap();
+        assert_eq!(parsed, list);
+        let cache = parsed.metadata_cache.borrow();
+        let song = &cache.entries()[&TrackID::new("/path/1").unwrap()];
+        assert_eq!(song.title(), Some("Rock & Roll"));
+        assert_eq!(song.artist_string(), Some(String::from("Alice, Bob")));
+        assert_eq!(song.album_name(), Some("<Album>"));
+        assert_eq!(song.length(), Some(Duration::from_millis(225_500)));
+        assert_eq!(song.url(), Some("file:///music/song.ogg"));
+        assert!(!cache.contains_key(&TrackID::new("/path/2").unwrap()));
+    }
+
+    #[test]
+    fn it_numbers_tracks_without_identifier() {
+        let list = TrackList::from_xspf(
+            r#"<playlist version="1" xmlns="http://xspf.org/ns/0/"><trackList>
+                <track><location>http://radio</location></track>
+                <track/>
+            </trackList></playlist>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            list.ids(),
+            &[
+                TrackID::new("/org/mpris/MediaPlayer2/Track/1").unwrap(),
+                TrackID::new("/org/mpris/MediaPlayer2/Track/2").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_xspf() {
+        assert!(matches!(
+            TrackList::from_xspf("<playlist><trackList></playlist>"),
+            Err(XspfError::Xml(_))
+        ));
+        assert!(matches!(
+            TrackList::from_xspf("<html></html>"),
+            Err(XspfError::MissingElement("playlist"))
+        ));
+        assert!(matches!(
+            TrackList::from_xspf("<playlist><title>Empty</title></playlist>"),
+            Err(XspfError::MissingElement("trackList"))
+        ));
+    }
+}
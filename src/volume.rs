@@ -0,0 +1,95 @@
+use failure::Fail;
+
+/// The volume of a [`Player`](crate::Player).
+///
+/// MPRIS volumes are never negative, and 1.0 is the normal 100% level. Players may accept values
+/// above 1.0, but it is not recommended.
+///
+/// See: [MPRIS2 specification about `Volume`][vol].
+///
+/// [vol]: https://specifications.freedesktop.org/mpris-spec/latest/Player_Interface.html#Property:Volume
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Volume(f64);
+
+/// A [`Volume`] could not be created from a value.
+#[derive(Fail, Debug, Clone, Copy, PartialEq)]
+pub enum VolumeError {
+    /// The value was NaN.
+    #[fail(display = "Volume must be a number")]
+    NotANumber,
+
+    /// The value was below 0.0.
+    #[fail(display = "Volume must not be negative, but was {}", _0)]
+    Negative(f64),
+}
+
+impl Volume {
+    /// The highest value that [`Volume::clamp`] returns; the normal 100% level.
+    pub const MAX: Volume = Volume(1.0);
+
+    /// Create a new [`Volume`], checking that the value is a valid MPRIS volume.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is NaN or negative.
+    ///
+    /// ```rust
+    /// # use mpris::Volume;
+    /// assert!(Volume::new(0.5).is_ok());
+    /// assert!(Volume::new(1.5).is_ok());
+    /// assert!(Volume::new(-0.1).is_err());
+    /// ```
+    pub fn new(value: f64) -> Result<Volume, VolumeError> {
+        if value.is_nan() {
+            Err(VolumeError::NotANumber)
+        } else if value < 0.0 {
+            Err(VolumeError::Negative(value))
+        } else {
+            Ok(Volume(value))
+        }
+    }
+
+    /// Create a new [`Volume`] from any value by limiting it to the range between 0.0 and
+    /// [`Volume::MAX`]. NaN becomes 0.0.
+    ///
+    /// Use this when adjusting the volume from user input, like volume up/down buttons.
+    pub fn clamp(value: f64) -> Volume {
+        if value.is_nan() {
+            Volume(0.0)
+        } else {
+            Volume(value.clamp(0.0, Volume::MAX.0))
+        }
+    }
+
+    /// Returns the volume as a plain number.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl From<Volume> for f64 {
+    fn from(volume: Volume) -> f64 {
+        volume.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_values() {
+        assert_eq!(Volume::new(0.0).map(Volume::value), Ok(0.0));
+        assert_eq!(Volume::new(2.5).map(Volume::value), Ok(2.5));
+        assert_eq!(Volume::new(-0.1), Err(VolumeError::Negative(-0.1)));
+        assert_eq!(Volume::new(f64::NAN), Err(VolumeError::NotANumber));
+    }
+
+    #[test]
+    fn it_clamps_values() {
+        assert_eq!(Volume::clamp(-1.0), Volume(0.0));
+        assert_eq!(Volume::clamp(0.5), Volume(0.5));
+        assert_eq!(Volume::clamp(1.5), Volume::MAX);
+        assert_eq!(Volume::clamp(f64::NAN), Volume(0.0));
+    }
+}
@@ -0,0 +1,86 @@
+use dbus::ffidisp::Connection;
+use dbus::Message;
+
+use super::{DBusError, Player, PlayerFinder};
+
+/// A [`Player`] appeared on or disappeared from the bus.
+///
+/// See [`PlayerWatcher`].
+#[derive(Debug)]
+pub enum PlayerWatchEvent {
+    /// A new player appeared, or an existing bus name was taken over by another process.
+    PlayerConnected(Player<'static>),
+
+    /// The player with this well-known bus name quit.
+    PlayerDisconnected {
+        /// The well-known bus name of the player, like `org.mpris.MediaPlayer2.spotify`.
+        bus_name: String,
+    },
+}
+
+/// Iterator that blocks until a [`Player`] appears or disappears, and then returns it as a
+/// [`PlayerWatchEvent`].
+///
+/// The watcher listens for `NameOwnerChanged` signals on a connection of its own, so it does not
+/// take any signals away from the [`PlayerFinder`] or its [`Player`]s. Found players are created
+/// like [`PlayerFinder::find_all`] would, including its filters; players that do not pass the
+/// filters are not reported as connected, but still as disconnected.
+///
+/// See [`PlayerFinder::watch`].
+#[derive(Debug)]
+pub struct PlayerWatcher<'a> {
+    finder: &'a PlayerFinder,
+    connection: Connection,
+}
+
+const POLL_TIMEOUT_MS: u32 = 1000;
+
+impl<'a> PlayerWatcher<'a> {
+    pub(crate) fn new(finder: &'a PlayerFinder, connection: Connection) -> Result<Self, DBusError> {
+        let mut rule = String::from(
+            "type='signal',sender='org.freedesktop.DBus',interface='org.freedesktop.DBus',member='NameOwnerChanged'",
+        );
+        // Namespaces only match whole elements, so other prefixes are only checked in `event_for`.
+        if let Some(namespace) = finder.bus_name_prefix().strip_suffix('.') {
+            rule.push_str(&format!(",arg0namespace='{}'", namespace));
+        }
+        connection.add_match(&rule)?;
+        Ok(PlayerWatcher { finder, connection })
+    }
+
+    fn event_for(&self, message: &Message) -> Option<PlayerWatchEvent> {
+        if &*message.member()? != "NameOwnerChanged" {
+            return None;
+        }
+
+        let (bus_name, _old_owner, new_owner) = message.get3::<String, String, String>();
+        let (bus_name, new_owner) = (bus_name?, new_owner?);
+        if !bus_name.starts_with(self.finder.bus_name_prefix()) {
+            return None;
+        }
+
+        if new_owner.is_empty() {
+            Some(PlayerWatchEvent::PlayerDisconnected { bus_name })
+        } else {
+            // The player might already have quit, or it might not pass the filters; either way
+            // there is nothing to report.
+            self.finder
+                .player_for_new_bus(bus_name)
+                .map(PlayerWatchEvent::PlayerConnected)
+        }
+    }
+}
+
+impl<'a> Iterator for PlayerWatcher<'a> {
+    type Item = PlayerWatchEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for message in self.connection.incoming(POLL_TIMEOUT_MS) {
+                if let Some(event) = self.event_for(&message) {
+                    return Some(event);
+                }
+            }
+        }
+    }
+}
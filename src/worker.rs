@@ -0,0 +1,207 @@
+use std::rc::Rc;
+use std::sync::mpsc;
+use std::thread;
+
+use dbus::strings::{BusName, Path};
+
+use super::{BusType, DBusError, Player, RateLimitPolicy, RetryPolicy, TimeoutConfig};
+use crate::find::open_connection;
+use crate::pooled_connection::PooledConnection;
+
+/// A call to run with the [`Player`] on a worker thread.
+pub(crate) type Job = Box<dyn FnOnce(&Player<'static>) + Send>;
+
+/// How to set up the [`Player`] on a worker thread.
+#[derive(Debug, Clone)]
+pub(crate) struct WorkerConfig {
+    pub(crate) bus: BusType,
+    pub(crate) bus_name: String,
+    pub(crate) path: String,
+    pub(crate) timeouts: TimeoutConfig,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) rate_limit: RateLimitPolicy,
+    pub(crate) auto_reconnect_attempts: u32,
+}
+
+impl WorkerConfig {
+    /// A configuration with the defaults of [`Player`], for the given bus name and path.
+    ///
+    /// Returns an [`Err`] if the bus name or path is invalid.
+    pub(crate) fn new(
+        bus: BusType,
+        bus_name: String,
+        path: String,
+        timeout_ms: i32,
+    ) -> Result<Self, DBusError> {
+        // Validate these before spawning anything, as the conversions in `Player::new` panic on
+        // invalid values.
+        BusName::new(bus_name.as_str()).map_err(DBusError::Miscellaneous)?;
+        Path::new(path.as_str()).map_err(DBusError::Miscellaneous)?;
+
+        Ok(WorkerConfig {
+            bus,
+            bus_name,
+            path,
+            timeouts: TimeoutConfig::from_millis(timeout_ms),
+            retry_policy: RetryPolicy::default(),
+            rate_limit: RateLimitPolicy::default(),
+            auto_reconnect_attempts: 0,
+        })
+    }
+
+    /// The configuration of an existing [`Player`], with a new connection to the same bus.
+    pub(crate) fn of(player: &Player<'_>) -> Self {
+        WorkerConfig {
+            // Like `Player::reconnect`, assume the session bus if the bus is not known.
+            bus: player.connection().bus().cloned().unwrap_or_default(),
+            bus_name: player.bus_name().to_string(),
+            path: player.path().to_string(),
+            timeouts: player.timeout_config(),
+            retry_policy: player.retry_policy(),
+            rate_limit: player.rate_limit(),
+            auto_reconnect_attempts: player.auto_reconnect_attempts(),
+        }
+    }
+
+    /// Opens a connection to the bus and creates the [`Player`] on it.
+    pub(crate) fn connect(self) -> Result<Player<'static>, DBusError> {
+        let connection = open_connection(&self.bus)?;
+        let player = Player::for_pooled_connection(
+            Rc::new(PooledConnection::for_bus(connection, self.bus)),
+            BusName::from(self.bus_name),
+            Path::from(self.path),
+            crate::timeout::as_dbus_millis(self.timeouts.default),
+        )?;
+
+        Ok(player
+            .with_timeout_config(self.timeouts)
+            .with_retry_policy(self.retry_policy)
+            .with_rate_limit(self.rate_limit)
+            .with_auto_reconnect(self.auto_reconnect_attempts))
+    }
+}
+
+/// Details about the [`Player`] that are read once when the worker thread starts.
+pub(crate) struct PlayerDetails {
+    pub(crate) unique_name: String,
+    pub(crate) identity: String,
+    pub(crate) has_tracklist_interface: bool,
+}
+
+/// Starts a worker thread with a [`Player`] created from the configuration, and returns the sender
+/// for the [`Job`]s to run on it.
+///
+/// `started` is called with the details of the player, or with the error if it could not be
+/// created. The thread stops right away if `started` returns `false`.
+pub(crate) fn spawn<F>(config: WorkerConfig, started: F) -> mpsc::Sender<Job>
+where
+    F: FnOnce(Result<PlayerDetails, DBusError>) -> bool + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Job>();
+
+    thread::spawn(move || {
+        let player = match config.connect() {
+            Ok(player) => player,
+            Err(error) => {
+                started(Err(error));
+                return;
+            }
+        };
+
+        let details = PlayerDetails {
+            unique_name: player.unique_name().to_owned(),
+            identity: player.identity().to_owned(),
+            has_tracklist_interface: player.supports_track_lists(),
+        };
+        if !started(Ok(details)) {
+            // Nobody is waiting for this player anymore.
+            return;
+        }
+
+        // Will stop when every sender has been dropped.
+        for job in receiver {
+            job(&player);
+        }
+    });
+
+    sender
+}
+
+/// The error of every call after the worker thread of the named handle type has stopped.
+pub(crate) fn stopped(handle: &str) -> DBusError {
+    DBusError::Miscellaneous(format!("The worker thread of the {} has stopped", handle))
+}
+
+/// Generates methods on a worker thread handle that forward to the [`Player`] method of the same
+/// name, through the `call` method of the handle.
+///
+/// Starts with `impl Handle as "Prefix";` for blocking methods, or `async impl Handle as
+/// "Prefix";` for async methods. The prefix is used in the doc comments.
+macro_rules! forward_to_player {
+    (
+        impl $handle:ident as $prefix:literal;
+        $( $name:ident ( $($arg:ident : $ty:ty),* ) -> $ret:ty = |$player:ident| $call:expr; )*
+    ) => {
+        impl $handle {
+            $(
+                #[doc = concat!($prefix, " version of [`Player::", stringify!($name), "`].")]
+                pub fn $name(&self, $($arg: $ty),*) -> Result<$ret, DBusError> {
+                    self.call(move |$player| $call)
+                }
+            )*
+        }
+    };
+    (
+        async impl $handle:ident as $prefix:literal;
+        $( $name:ident ( $($arg:ident : $ty:ty),* ) -> $ret:ty = |$player:ident| $call:expr; )*
+    ) => {
+        impl $handle {
+            $(
+                #[doc = concat!($prefix, " version of [`Player::", stringify!($name), "`].")]
+                pub async fn $name(&self, $($arg: $ty),*) -> Result<$ret, DBusError> {
+                    self.call(move |$player| $call).await
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use forward_to_player;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rejects_invalid_bus_names_and_paths() {
+        let path = String::from("/org/mpris/MediaPlayer2");
+        assert!(
+            WorkerConfig::new(BusType::SessionBus, "not a bus name".into(), path, 100).is_err()
+        );
+
+        let bus_name = String::from("org.mpris.MediaPlayer2.mpris_rs_test");
+        assert!(WorkerConfig::new(BusType::SessionBus, bus_name, "no/path".into(), 100).is_err());
+    }
+
+    #[test]
+    fn it_reports_players_that_cannot_be_created() {
+        let config = WorkerConfig::new(
+            BusType::SessionBus,
+            "org.mpris.MediaPlayer2.mpris_rs_missing_player".into(),
+            "/org/mpris/MediaPlayer2".into(),
+            100,
+        )
+        .unwrap();
+        let (details_sender, details_receiver) = mpsc::channel();
+
+        let sender = spawn(config, move |details| {
+            details_sender.send(details.is_ok()).is_ok()
+        });
+
+        assert_eq!(details_receiver.recv(), Ok(false));
+        // The thread has stopped, so nothing can be sent to it anymore.
+        while sender.send(Box::new(|_| {})).is_ok() {
+            thread::yield_now();
+        }
+    }
+}